@@ -0,0 +1,150 @@
+//! Completion (touche Tab) des commandes et des chemins de l'image, sans std
+
+use crate::cli::tokenize;
+use crate::fs::FileSystem;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Noms de commandes proposes pour le premier mot
+pub const COMMANDS: &[&str] = &[
+    "bpb", "cat", "cd", "chain", "create", "df", "exit", "export-tar", "fat", "find", "fsck", "get", "help", "hexdump", "info",
+    "label", "ls", "put", "pwd", "quit", "stat", "tree", "write",
+];
+
+/// Candidats pour le mot sous le curseur
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Completion {
+    /// Debut du mot a remplacer (en octets dans la ligne)
+    pub start: usize,
+    /// Mots de remplacement, deja echappes ; un dossier finit par `/`
+    pub candidates: Vec<String>,
+}
+
+impl Completion {
+    /// Plus long debut commun a tous les candidats
+    pub fn common_prefix(&self) -> &str {
+        let Some(first) = self.candidates.first() else {
+            return "";
+        };
+        let mut end = first.len();
+        for candidate in &self.candidates[1..] {
+            let same = first
+                .char_indices()
+                .zip(candidate.chars())
+                .take_while(|((_, a), b)| a == b)
+                .last()
+                .map_or(0, |((i, c), _)| i + c.len_utf8());
+            end = end.min(same);
+        }
+        &first[..end]
+    }
+}
+
+/// Complete le dernier mot de `line` (le texte avant le curseur)
+///
+/// Le premier mot est un nom de commande ; les suivants sont des chemins de
+/// l'image, relatifs au dossier courant, compares sans tenir compte de la casse.
+/// Les arguments qui designent l'hote (`put <source>`, `get .. <dest>`) ne sont
+/// pas completes.
+pub fn complete<F: FileSystem + ?Sized>(fs: &F, line: &str) -> Completion {
+    let start = word_start(line);
+    let previous = tokenize::split_args(&line[..start]).unwrap_or_default();
+    let word = unescape(&line[start..]);
+
+    let candidates = match previous.first().map(String::as_str) {
+        None => COMMANDS.iter().filter(|command| command.starts_with(&word)).map(|command| String::from(*command)).collect(),
+        Some(command) if is_host_argument(command, &previous[1..]) => Vec::new(),
+        Some(_) => complete_path(fs, &word),
+    };
+    Completion { start, candidates }
+}
+
+/// `put <hote> <image>`, `get <image> <hote>`, `export-tar <image> <hote>` (options `-r` ignorees)
+fn is_host_argument(command: &str, args: &[String]) -> bool {
+    let position = args.iter().filter(|arg| !arg.starts_with('-')).count();
+    matches!((command, position), ("put", 0) | ("get", 1) | ("export-tar", 1))
+}
+
+fn complete_path<F: FileSystem + ?Sized>(fs: &F, word: &str) -> Vec<String> {
+    let (dir, prefix) = match word.rfind('/') {
+        Some(i) => (&word[..=i], &word[i + 1..]),
+        None => ("", word),
+    };
+    let listed = match dir {
+        "" => ".",
+        "/" => "/",
+        dir => dir.trim_end_matches('/'),
+    };
+    let Ok(entries) = fs.list(listed) else {
+        return Vec::new();
+    };
+
+    let prefix_upper = prefix.to_uppercase();
+    let mut candidates: Vec<String> = entries
+        .iter()
+        .filter(|entry| !entry.is_volume_label())
+        .filter_map(|entry| Some((entry.name().ok()?, entry.is_directory())))
+        // `.` et `..` seulement si on les a commences
+        .filter(|(name, _)| !matches!(name.as_str(), "." | "..") || prefix.starts_with('.'))
+        .filter(|(name, _)| name.to_uppercase().starts_with(&prefix_upper))
+        .map(|(name, directory)| {
+            let mut candidate = escape(dir);
+            candidate.push_str(&escape(&name));
+            if directory {
+                candidate.push('/');
+            }
+            candidate
+        })
+        .collect();
+    candidates.sort_by_key(|candidate| candidate.to_lowercase());
+    candidates
+}
+
+/// debut du dernier mot : apres le dernier espace hors quotes et non echappe
+fn word_start(line: &str) -> usize {
+    let mut start = 0;
+    let mut quote = None;
+    let mut chars = line.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\\') => {
+                chars.next();
+            }
+            (None, '\'' | '"') => quote = Some(c),
+            (None, c) if c.is_whitespace() => start = i + c.len_utf8(),
+            _ => {}
+        }
+    }
+    start
+}
+
+/// mot en cours de saisie sans ses quotes ni `\` (quote ouverte acceptee)
+fn unescape(word: &str) -> String {
+    let mut value = String::new();
+    let mut quote = None;
+    let mut chars = word.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => value.push(c),
+            (None, '\\') => value.extend(chars.next()),
+            (None, '\'' | '"') => quote = Some(c),
+            (None, c) => value.push(c),
+        }
+    }
+    value
+}
+
+/// `\` devant les caracteres que `tokenize` interprete
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_whitespace() || matches!(c, '\\' | '\'' | '"' | '#') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
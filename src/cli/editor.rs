@@ -0,0 +1,380 @@
+//! Edition de ligne du shell interactif, uniquement avec std
+//!
+//! Terminal en mode brut (`stty raw -echo`, sans dependance), touches lues octet
+//! par octet et ligne redessinee a chaque frappe. Fleches et Home / End, historique
+//! (haut / bas), Ctrl-R pour chercher dans l'historique, Tab pour completer.
+
+use crate::cli::complete::Completion;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use std::io::{self, IsTerminal, Read, Write};
+use std::process::{Command as Process, Stdio};
+
+/// Nombre de lignes gardees dans l'historique
+const HISTORY_SIZE: usize = 500;
+
+/// Terminal en mode brut tant que la valeur existe, reglages d'origine restaures au drop
+pub struct RawMode {
+    saved: String,
+}
+
+impl RawMode {
+    /// Passe le terminal de stdin en mode brut ; None si stdin n'est pas un terminal ou sans `stty`
+    pub fn enable() -> Option<Self> {
+        if !io::stdin().is_terminal() {
+            return None;
+        }
+        let saved = stty(&["-g"])?;
+        stty(&["raw", "-echo"])?;
+        Some(Self { saved: saved.trim().into() })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = stty(&[&self.saved]);
+    }
+}
+
+/// `stty` sur le terminal de stdin, sa sortie si la commande reussit
+fn stty(args: &[&str]) -> Option<String> {
+    let output = Process::new("stty").args(args).stdin(Stdio::inherit()).stderr(Stdio::null()).output().ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Touche decodee
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Key {
+    Char(char),
+    Enter,
+    Tab,
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    /// Ctrl + lettre (`Ctrl('r')`)
+    Ctrl(char),
+    Escape,
+}
+
+/// Ligne en cours et historique du shell
+#[derive(Debug, Default)]
+pub struct LineEditor {
+    history: Vec<String>,
+}
+
+/// etat d'une saisie
+struct State<'a> {
+    prompt: &'a str,
+    line: Vec<char>,
+    cursor: usize,
+    /// position dans l'historique (== len : ligne en cours)
+    history_index: usize,
+    /// ligne en cours mise de cote pendant qu'on parcourt l'historique
+    draft: Vec<char>,
+}
+
+impl LineEditor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lignes deja saisies, la plus ancienne d'abord
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    /// Ajoute une ligne a l'historique (ni vide, ni repetee)
+    pub fn add_history(&mut self, line: &str) {
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.trim().is_empty() || self.history.last().map(String::as_str) == Some(line) {
+            return;
+        }
+        if self.history.len() == HISTORY_SIZE {
+            self.history.remove(0);
+        }
+        self.history.push(line.into());
+    }
+
+    /// Lit une ligne en l'editant ; None en fin d'entree (Ctrl-D sur une ligne vide)
+    ///
+    /// Le terminal doit deja etre en mode brut (`RawMode`). `complete` recoit le
+    /// texte avant le curseur. La ligne validee est ajoutee a l'historique.
+    pub fn read_line<R, W, C>(&mut self, prompt: &str, input: &mut R, output: &mut W, mut complete: C) -> io::Result<Option<String>>
+    where
+        R: Read,
+        W: Write,
+        C: FnMut(&str) -> Completion,
+    {
+        let mut state = State { prompt, line: Vec::new(), cursor: 0, history_index: self.history.len(), draft: Vec::new() };
+        redraw(output, &state)?;
+
+        loop {
+            let Some(key) = read_key(input)? else {
+                return Ok(None);
+            };
+            match key {
+                Key::Enter => {
+                    output.write_all(b"\r\n")?;
+                    output.flush()?;
+                    let line: String = state.line.iter().collect();
+                    self.add_history(&line);
+                    return Ok(Some(line));
+                }
+                Key::Ctrl('d') if state.line.is_empty() => {
+                    output.write_all(b"\r\n")?;
+                    output.flush()?;
+                    return Ok(None);
+                }
+                Key::Ctrl('c') => {
+                    // ligne abandonnee, on repart de zero
+                    output.write_all(b"^C\r\n")?;
+                    return Ok(Some(String::new()));
+                }
+                Key::Char(c) => {
+                    state.line.insert(state.cursor, c);
+                    state.cursor += 1;
+                }
+                Key::Backspace | Key::Ctrl('h') if state.cursor > 0 => {
+                    state.cursor -= 1;
+                    state.line.remove(state.cursor);
+                }
+                Key::Delete | Key::Ctrl('d') if state.cursor < state.line.len() => {
+                    state.line.remove(state.cursor);
+                }
+                Key::Left | Key::Ctrl('b') => state.cursor = state.cursor.saturating_sub(1),
+                Key::Right | Key::Ctrl('f') => state.cursor = (state.cursor + 1).min(state.line.len()),
+                Key::Home | Key::Ctrl('a') => state.cursor = 0,
+                Key::End | Key::Ctrl('e') => state.cursor = state.line.len(),
+                Key::Ctrl('k') => state.line.truncate(state.cursor),
+                Key::Ctrl('u') => {
+                    state.line.drain(..state.cursor);
+                    state.cursor = 0;
+                }
+                Key::Ctrl('w') => {
+                    // mot precedent et les espaces qui le suivent
+                    let mut start = state.cursor;
+                    while start > 0 && state.line[start - 1].is_whitespace() {
+                        start -= 1;
+                    }
+                    while start > 0 && !state.line[start - 1].is_whitespace() {
+                        start -= 1;
+                    }
+                    state.line.drain(start..state.cursor);
+                    state.cursor = start;
+                }
+                Key::Ctrl('l') => output.write_all(b"\x1b[H\x1b[2J")?,
+                Key::Up | Key::Ctrl('p') => self.history_step(&mut state, -1),
+                Key::Down | Key::Ctrl('n') => self.history_step(&mut state, 1),
+                Key::Tab => self.complete(&mut state, output, &mut complete)?,
+                Key::Ctrl('r') => {
+                    if let Some(line) = self.search(&mut state, input, output)? {
+                        output.write_all(b"\r\n")?;
+                        output.flush()?;
+                        self.add_history(&line);
+                        return Ok(Some(line));
+                    }
+                }
+                _ => {}
+            }
+            redraw(output, &state)?;
+        }
+    }
+
+    /// ligne precedente (-1) ou suivante (+1) de l'historique
+    fn history_step(&self, state: &mut State, step: isize) {
+        let Some(index) = state.history_index.checked_add_signed(step).filter(|&i| i <= self.history.len()) else {
+            return;
+        };
+        if state.history_index == self.history.len() {
+            state.draft = state.line.clone();
+        }
+        state.history_index = index;
+        state.line = match self.history.get(index) {
+            Some(line) => line.chars().collect(),
+            None => state.draft.clone(),
+        };
+        state.cursor = state.line.len();
+    }
+
+    /// Tab : un seul candidat le remplace, plusieurs completent le debut commun ou s'affichent
+    fn complete<W: Write, C: FnMut(&str) -> Completion>(&self, state: &mut State, output: &mut W, complete: &mut C) -> io::Result<()> {
+        let before: String = state.line[..state.cursor].iter().collect();
+        let completion = complete(&before);
+        let word_chars = before[completion.start..].chars().count();
+        let replacement = match completion.candidates.as_slice() {
+            [] => return Ok(()),
+            [single] if single.ends_with('/') => single.clone(),
+            [single] => {
+                let mut word = single.clone();
+                word.push(' ');
+                word
+            }
+            _ if completion.common_prefix().chars().count() > word_chars => String::from(completion.common_prefix()),
+            candidates => {
+                output.write_all(b"\r\n")?;
+                for candidate in candidates {
+                    // nom seul, sans le dossier
+                    let name = candidate.trim_end_matches('/').rsplit('/').next().unwrap_or(candidate);
+                    let marker = if candidate.ends_with('/') { "/" } else { "" };
+                    write!(output, "{}{}  ", name, marker)?;
+                }
+                output.write_all(b"\r\n")?;
+                return Ok(());
+            }
+        };
+        let start = state.cursor - word_chars;
+        state.line.splice(start..state.cursor, replacement.chars());
+        state.cursor = start + replacement.chars().count();
+        Ok(())
+    }
+
+    /// Ctrl-R : recherche dans l'historique, du plus recent au plus ancien
+    ///
+    /// Ctrl-R encore : correspondance plus ancienne ; Entree renvoie la ligne a
+    /// executer ; Ctrl-G ou Ctrl-C annule ; toute autre touche garde la ligne
+    /// trouvee pour l'editer.
+    fn search<R: Read, W: Write>(&self, state: &mut State, input: &mut R, output: &mut W) -> io::Result<Option<String>> {
+        let mut query = String::new();
+        let mut found: Option<usize> = None;
+        let mut failing = false;
+        loop {
+            let matched = found.map_or("", |i| self.history[i].as_str());
+            write!(output, "\r({}reverse-i-search)'{}': {}\x1b[K", if failing { "failing " } else { "" }, query, matched)?;
+            output.flush()?;
+
+            let Some(key) = read_key(input)? else {
+                return Ok(None);
+            };
+            // on cherche avant cet index
+            let before = match key {
+                Key::Char(c) => {
+                    query.push(c);
+                    // la ligne trouvee peut encore convenir
+                    found.map_or(self.history.len(), |i| i + 1)
+                }
+                Key::Backspace | Key::Ctrl('h') => {
+                    query.pop();
+                    self.history.len()
+                }
+                Key::Ctrl('r') => found.unwrap_or(self.history.len()),
+                Key::Ctrl('g') | Key::Ctrl('c') => return Ok(None),
+                key => {
+                    if let Some(i) = found {
+                        state.line = self.history[i].chars().collect();
+                        state.cursor = state.line.len();
+                    }
+                    if key == Key::Enter {
+                        return Ok(Some(state.line.iter().collect()));
+                    }
+                    return Ok(None);
+                }
+            };
+            let next = self.history[..before].iter().rposition(|line| !query.is_empty() && line.contains(query.as_str()));
+            failing = next.is_none() && !query.is_empty();
+            if next.is_some() || query.is_empty() {
+                found = next;
+            }
+        }
+    }
+}
+
+/// efface la ligne du terminal et la reecrit, curseur en place
+fn redraw<W: Write>(output: &mut W, state: &State) -> io::Result<()> {
+    let line: String = state.line.iter().collect();
+    write!(output, "\r{}{}\x1b[K", state.prompt, line)?;
+    let after = state.line.len() - state.cursor;
+    if after > 0 {
+        write!(output, "\x1b[{}D", after)?;
+    }
+    output.flush()
+}
+
+fn read_byte<R: Read>(input: &mut R) -> io::Result<Option<u8>> {
+    let mut byte = [0u8; 1];
+    loop {
+        match input.read(&mut byte) {
+            Ok(0) => return Ok(None),
+            Ok(_) => return Ok(Some(byte[0])),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// une touche : octet de controle, sequence d'echappement (`ESC [ A`) ou caractere UTF-8
+fn read_key<R: Read>(input: &mut R) -> io::Result<Option<Key>> {
+    let Some(byte) = read_byte(input)? else {
+        return Ok(None);
+    };
+    let key = match byte {
+        b'\r' | b'\n' => Key::Enter,
+        b'\t' => Key::Tab,
+        0x7F => Key::Backspace,
+        0x1B => return read_escape(input),
+        0x01..=0x1A => Key::Ctrl((b'a' + byte - 1) as char),
+        0x00..=0x1F => return Ok(Some(Key::Escape)),
+        0x20..=0x7E => Key::Char(byte as char),
+        _ => {
+            // caractere multi-octets : longueur donnee par le premier octet
+            let len = match byte {
+                0xC0..=0xDF => 2,
+                0xE0..=0xEF => 3,
+                0xF0..=0xF7 => 4,
+                _ => return Ok(Some(Key::Escape)),
+            };
+            let mut bytes = vec![byte];
+            for _ in 1..len {
+                bytes.extend(read_byte(input)?);
+            }
+            match core::str::from_utf8(&bytes).ok().and_then(|text| text.chars().next()) {
+                Some(c) => Key::Char(c),
+                None => Key::Escape,
+            }
+        }
+    };
+    Ok(Some(key))
+}
+
+/// suite de `ESC` : `[A`..`[D`, `[H` / `[F`, `OH` / `OF`, `[1~` `[3~` `[4~`
+fn read_escape<R: Read>(input: &mut R) -> io::Result<Option<Key>> {
+    let Some(kind) = read_byte(input)? else {
+        return Ok(Some(Key::Escape));
+    };
+    if kind != b'[' && kind != b'O' {
+        return Ok(Some(Key::Escape));
+    }
+    let mut param = 0u32;
+    loop {
+        let Some(byte) = read_byte(input)? else {
+            return Ok(Some(Key::Escape));
+        };
+        let key = match byte {
+            b'0'..=b'9' => {
+                // une longue suite de chiffres ne doit pas deborder
+                param = param.saturating_mul(10).saturating_add((byte - b'0') as u32);
+                continue;
+            }
+            b';' => continue,
+            b'A' => Key::Up,
+            b'B' => Key::Down,
+            b'C' => Key::Right,
+            b'D' => Key::Left,
+            b'H' => Key::Home,
+            b'F' => Key::End,
+            b'~' => match param {
+                1 | 7 => Key::Home,
+                3 => Key::Delete,
+                4 | 8 => Key::End,
+                _ => Key::Escape,
+            },
+            _ => Key::Escape,
+        };
+        return Ok(Some(key));
+    }
+}
@@ -0,0 +1,306 @@
+//! Copies entre l'hote et l'image (`get` / `put`), uniquement avec std
+
+use crate::cli::json::Json;
+use crate::cli::{Cli, Volume};
+use crate::fs::{format, Attributes, DateTime, Fat32Fs, FileSystem, FileSystemError, FormatOptions, Metadata};
+use crate::fs::time::{FixedTimeProvider, SystemTimeProvider, TimeProvider};
+use alloc::boxed::Box;
+use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::Write;
+use std::fs::{self, File, FileTimes};
+use std::path::{Path, PathBuf};
+
+/// erreur d'E/S cote hote, avec le chemin concerne
+fn host_error(path: &Path, e: std::io::Error) -> FileSystemError {
+    FileSystemError::IoError(format!("{}: {}", path.display(), e))
+}
+
+/// Une entree traitee par `get` / `put`, dans l'ordre de la copie
+pub(crate) enum Transfer {
+    /// Dossier cree (ou deja present) a la destination
+    Directory { source: String, dest: String },
+    /// Fichier copie
+    File { source: String, dest: String, size: usize },
+    /// Lien symbolique de l'hote, ignore
+    SymbolicLink { source: String },
+}
+
+/// sortie texte de `get` / `put` : une ligne par entree
+pub(crate) fn write_transfers(verb: &str, transfers: &[Transfer], output: &mut String) {
+    for transfer in transfers {
+        let _ = match transfer {
+            Transfer::Directory { source, dest } => writeln!(output, "{} {} -> {}/", verb, source, dest),
+            Transfer::File { source, dest, size } => writeln!(output, "{} {} -> {} ({} bytes)", verb, source, dest, size),
+            Transfer::SymbolicLink { source } => writeln!(output, "skip {} (symbolic link)", source),
+        };
+    }
+}
+
+/// `get` / `put` en JSON : `{"source", "dest", "entries": [{"kind", "source", "dest", "size"}]}`
+pub(crate) fn transfers_json(source: &str, dest: &str, transfers: &[Transfer]) -> Json {
+    let entries = transfers
+        .iter()
+        .map(|transfer| match transfer {
+            Transfer::Directory { source, dest } => Json::Object(vec![
+                ("kind", "directory".into()),
+                ("source", source.as_str().into()),
+                ("dest", dest.as_str().into()),
+                ("size", Json::Null),
+            ]),
+            Transfer::File { source, dest, size } => Json::Object(vec![
+                ("kind", "file".into()),
+                ("source", source.as_str().into()),
+                ("dest", dest.as_str().into()),
+                ("size", (*size).into()),
+            ]),
+            Transfer::SymbolicLink { source } => Json::Object(vec![
+                ("kind", "skipped_symlink".into()),
+                ("source", source.as_str().into()),
+                ("dest", Json::Null),
+                ("size", Json::Null),
+            ]),
+        })
+        .collect();
+    Json::Object(vec![("source", source.into()), ("dest", dest.into()), ("entries", Json::Array(entries))])
+}
+
+/// chemin de l'image pour `name` dans le dossier `dir`
+fn image_join(dir: &str, name: &str) -> String {
+    format!("{}/{}", dir.trim_end_matches('/'), name)
+}
+
+impl Cli {
+    /// `get [-r] <image-path> <host-path>` : copie de l'image vers l'hote
+    ///
+    /// Si `dest` est un dossier existant, la copie va dedans. Les dates de
+    /// modification et d'acces sont reportees, READ_ONLY rend le fichier en lecture seule.
+    pub(crate) fn get(&mut self, source: &str, dest: &str, recursive: bool) -> Result<Vec<Transfer>, FileSystemError> {
+        let meta = self.volume.fs().metadata(source)?;
+        let mut dest = PathBuf::from(dest);
+        if dest.is_dir() && meta.location.is_some() {
+            dest.push(meta.name());
+        }
+        if meta.is_directory() && !recursive {
+            return Err(FileSystemError::InvalidPath(format!("{} is a directory (use -r)", source)));
+        }
+        let mut visited = BTreeSet::new();
+        let mut transfers = Vec::new();
+        self.get_entry(source, &dest, &meta, &mut visited, &mut transfers)?;
+        Ok(transfers)
+    }
+
+    fn get_entry(
+        &self,
+        source: &str,
+        dest: &Path,
+        meta: &Metadata,
+        visited: &mut BTreeSet<u32>,
+        transfers: &mut Vec<Transfer>,
+    ) -> Result<(), FileSystemError> {
+        let fs = self.volume.fs();
+        if meta.is_directory() {
+            // un `..` ou une entree dupliquee peut reboucler sur un dossier deja copie
+            if !visited.insert(meta.first_cluster) {
+                return Ok(());
+            }
+            fs::create_dir_all(dest).map_err(|e| host_error(dest, e))?;
+            transfers.push(Transfer::Directory { source: source.to_string(), dest: dest.display().to_string() });
+            for entry in fs.list(source)? {
+                let name = entry.name()?;
+                if name == "." || name == ".." || entry.is_volume_label() {
+                    continue;
+                }
+                let child = image_join(source, &name);
+                let child_meta = fs.metadata(&child)?;
+                self.get_entry(&child, &dest.join(&name), &child_meta, visited, transfers)?;
+            }
+            // apres le contenu : creer les fichiers change la date du dossier
+            let _ = set_host_times(dest, meta, false);
+            return Ok(());
+        }
+
+        let data = fs.read_file(source)?;
+        fs::write(dest, &data).map_err(|e| host_error(dest, e))?;
+        set_host_times(dest, meta, true).map_err(|e| host_error(dest, e))?;
+        if meta.attributes.is_read_only() {
+            let mut permissions = fs::metadata(dest).map_err(|e| host_error(dest, e))?.permissions();
+            permissions.set_readonly(true);
+            fs::set_permissions(dest, permissions).map_err(|e| host_error(dest, e))?;
+        }
+        transfers.push(Transfer::File { source: source.to_string(), dest: dest.display().to_string(), size: data.len() });
+        Ok(())
+    }
+
+    /// `put [-r] <host-path> <image-path>` : copie de l'hote vers l'image
+    ///
+    /// Si `dest` est un dossier existant de l'image, la copie va dedans. Les dates
+    /// de creation, modification et acces sont reportees (FAT uniquement), un
+    /// fichier en lecture seule recoit l'attribut READ_ONLY.
+    pub(crate) fn put(&mut self, source: &str, dest: &str, recursive: bool) -> Result<Vec<Transfer>, FileSystemError> {
+        let source = Path::new(source);
+        let host_meta = fs::metadata(source).map_err(|e| host_error(source, e))?;
+        if host_meta.is_dir() && !recursive {
+            return Err(FileSystemError::InvalidPath(format!("{} is a directory (use -r)", source.display())));
+        }
+
+        let into_dir = self.volume.fs().metadata(dest).map(|meta| meta.is_directory()).unwrap_or(false);
+        let dest = match (into_dir, source.file_name()) {
+            (true, Some(name)) => image_join(dest, &host_name(source, name)?),
+            _ => dest.to_string(),
+        };
+        let mut transfers = Vec::new();
+        self.put_entry(source, &dest, &host_meta, true, &mut transfers)?;
+        Ok(transfers)
+    }
+
+    /// `host_times` : reporter les dates de l'hote, sinon garder celles de l'horloge du volume
+    fn put_entry(
+        &mut self,
+        source: &Path,
+        dest: &str,
+        host_meta: &fs::Metadata,
+        host_times: bool,
+        transfers: &mut Vec<Transfer>,
+    ) -> Result<(), FileSystemError> {
+        if host_meta.is_dir() {
+            match self.volume.fs().metadata(dest) {
+                Ok(meta) if meta.is_directory() => {}
+                Ok(_) => return Err(FileSystemError::DirectoryEntryError(format!("{} already exists", dest))),
+                Err(_) => self.volume.fs_mut().create_dir(dest)?,
+            }
+            transfers.push(Transfer::Directory {
+                source: source.display().to_string(),
+                dest: dest.trim_end_matches('/').to_string(),
+            });
+
+            for (path, child_meta, name) in host_children(source, transfers)? {
+                self.put_entry(&path, &image_join(dest, &name), &child_meta, host_times, transfers)?;
+            }
+            return self.set_image_metadata(dest, host_meta, host_times);
+        }
+
+        let data = fs::read(source).map_err(|e| host_error(source, e))?;
+        self.volume.fs_mut().write_file(dest, &data)?;
+        self.set_image_metadata(dest, host_meta, host_times)?;
+        transfers.push(Transfer::File { source: source.display().to_string(), dest: dest.to_string(), size: data.len() });
+        Ok(())
+    }
+
+    /// reporte les dates et la lecture seule de l'hote sur l'entree de l'image
+    fn set_image_metadata(&mut self, dest: &str, host_meta: &fs::Metadata, host_times: bool) -> Result<(), FileSystemError> {
+        let Volume::Fat(fs) = &mut self.volume else {
+            return Ok(());
+        };
+        if host_times {
+            let created = host_meta.created().ok().map(DateTime::from_system_time);
+            let modified = host_meta.modified().ok().map(DateTime::from_system_time);
+            let accessed = host_meta.accessed().ok().map(DateTime::from_system_time);
+            fs.set_times(dest, created, modified, accessed)?;
+        }
+
+        if host_meta.is_file() && host_meta.permissions().readonly() {
+            let mut attributes = fs.metadata(dest)?.attributes;
+            attributes.insert(Attributes::READ_ONLY);
+            fs.set_attributes(dest, attributes)?;
+        }
+        Ok(())
+    }
+}
+
+/// `build` : formate un volume FAT32 et y copie le contenu du dossier `dir`
+///
+/// Les entrees sont copiees dans l'ordre des noms. En mode `reproducible`, toutes
+/// les dates valent `options.timestamp` (celles de l'hote sont ignorees) : memes
+/// entrees et memes options, meme image octet pour octet.
+pub fn build_image(dir: &Path, options: &FormatOptions, reproducible: bool, output: &mut String) -> Result<Vec<u8>, FileSystemError> {
+    let host_meta = fs::metadata(dir).map_err(|e| host_error(dir, e))?;
+    if !host_meta.is_dir() {
+        return Err(FileSystemError::DirectoryNotFound(format!("{} is not a directory", dir.display())));
+    }
+
+    let image = format::format(options)?;
+    let time_provider: Box<dyn TimeProvider> = if reproducible {
+        Box::new(FixedTimeProvider::new(options.timestamp))
+    } else {
+        Box::new(SystemTimeProvider)
+    };
+    // Safety: image qui vient d'etre formatee
+    let fs = unsafe { Fat32Fs::new(&image)? }.with_time_provider(time_provider);
+    drop(image);
+    let mut cli = Cli::from_volume(Volume::Fat(fs));
+
+    let mut transfers = Vec::new();
+    for (path, child_meta, name) in host_children(dir, &mut transfers)? {
+        cli.put_entry(&path, &image_join("/", &name), &child_meta, !reproducible, &mut transfers)?;
+    }
+    write_transfers("put", &transfers, output);
+
+    let Volume::Fat(fs) = cli.volume else {
+        unreachable!("build_image mounts a FAT volume");
+    };
+    Ok(fs.into_device_data())
+}
+
+#[cfg(feature = "tar")]
+impl Cli {
+    /// `export-tar <image-path> <out.tar>` : archive ecrite sur l'hote
+    ///
+    /// Renvoie la taille de l'archive. Avec `-` rien n'est ecrit : `execute_raw` renvoie l'archive.
+    pub(crate) fn export_tar(&self, source: &str, dest: &str) -> Result<usize, FileSystemError> {
+        let archive = crate::fs::tar::export(self.volume.fs(), source)?;
+        if dest != "-" {
+            let dest = Path::new(dest);
+            fs::write(dest, &archive).map_err(|e| host_error(dest, e))?;
+        }
+        Ok(archive.len())
+    }
+}
+
+/// enfants d'un dossier de l'hote, tries par nom (l'image ne depend pas de l'ordre de read_dir)
+///
+/// Les liens symboliques ne sont pas suivis : un lien vers un dossier parent
+/// ferait boucler la copie. Ils sont notes dans `transfers` et ignores.
+fn host_children(dir: &Path, transfers: &mut Vec<Transfer>) -> Result<Vec<(PathBuf, fs::Metadata, String)>, FileSystemError> {
+    let mut children: Vec<_> = fs::read_dir(dir)
+        .and_then(|entries| entries.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| host_error(dir, e))?;
+    children.sort_by_key(|child| child.file_name());
+
+    let mut found = Vec::with_capacity(children.len());
+    for child in children {
+        let path = child.path();
+        let meta = fs::symlink_metadata(&path).map_err(|e| host_error(&path, e))?;
+        if meta.file_type().is_symlink() {
+            transfers.push(Transfer::SymbolicLink { source: path.display().to_string() });
+            continue;
+        }
+        let name = host_name(&path, &child.file_name())?;
+        found.push((path, meta, name));
+    }
+    Ok(found)
+}
+
+/// nom d'un fichier de l'hote, qui doit etre en UTF-8 pour aller dans l'image
+fn host_name(path: &Path, name: &std::ffi::OsStr) -> Result<String, FileSystemError> {
+    name.to_str()
+        .map(String::from)
+        .ok_or_else(|| FileSystemError::InvalidPath(format!("{}: name is not valid UTF-8", path.display())))
+}
+
+/// dates de modification et d'acces de l'image sur un fichier (ou dossier) de l'hote
+fn set_host_times(dest: &Path, meta: &Metadata, write: bool) -> std::io::Result<()> {
+    let mut times = FileTimes::new();
+    if let Some(modified) = meta.modified {
+        times = times.set_modified(modified.to_system_time());
+    }
+    if let Some(accessed) = meta.accessed {
+        times = times.set_accessed(accessed.to_system_time());
+    }
+    // un dossier ne s'ouvre pas en ecriture
+    let file = if write { File::options().write(true).open(dest)? } else { File::open(dest)? };
+    file.set_times(times)
+}
@@ -0,0 +1,410 @@
+use crate::cli::json::Json;
+use crate::cli::tree::clusters_text;
+use crate::cli::{Cli, Volume};
+use crate::fs::{ClusterChain, Fat32Fs, FatType, FileSystemError, FsckIssue};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+/// Zone lue par `hexdump`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DumpUnit {
+    Sector,
+    Cluster,
+}
+
+impl Cli {
+    /// volume FAT monte, sinon Unsupported (commandes d'inspection)
+    fn fat_volume(&self) -> Result<&Fat32Fs, FileSystemError> {
+        match &self.volume {
+            Volume::Fat(fs) => Ok(fs),
+            Volume::ExFat(_) => Err(FileSystemError::Unsupported("Raw inspection is only available on FAT volumes".into())),
+        }
+    }
+
+    /// `hexdump sector N` / `hexdump cluster N`, offsets absolus dans l'image
+    pub(crate) fn write_hexdump(&self, unit: DumpUnit, index: u32, output: &mut String) -> Result<(), FileSystemError> {
+        let fs = self.fat_volume()?;
+        let (data, offset) = match unit {
+            DumpUnit::Sector => {
+                let data = fs.read_sector(index)?;
+                let _ = writeln!(output, "Sector {} ({})", index, sector_region(fs, index));
+                (data, index as usize * fs.boot_sector().bytes_per_sector() as usize)
+            }
+            DumpUnit::Cluster => {
+                let data = fs.read_cluster(index)?;
+                let offset = fs.cluster_offset(index)?;
+                let first_sector = offset / fs.boot_sector().bytes_per_sector() as usize;
+                let _ = writeln!(output, "Cluster {} (sector {}, {} bytes)", index, first_sector, data.len());
+                (data, offset)
+            }
+        };
+        write_hex_lines(&data, offset, output);
+        Ok(())
+    }
+
+    /// `hexdump` en JSON : `{"unit", "index", "offset", "region", "data"}`, octets en hexa
+    pub(crate) fn hexdump_json(&self, unit: DumpUnit, index: u32) -> Result<Json, FileSystemError> {
+        let fs = self.fat_volume()?;
+        let (unit_name, data, offset) = match unit {
+            DumpUnit::Sector => {
+                let data = fs.read_sector(index)?;
+                ("sector", data, index as usize * fs.boot_sector().bytes_per_sector() as usize)
+            }
+            DumpUnit::Cluster => ("cluster", fs.read_cluster(index)?, fs.cluster_offset(index)?),
+        };
+        let first_sector = (offset / fs.boot_sector().bytes_per_sector() as usize) as u32;
+        let mut hex = String::with_capacity(data.len() * 2);
+        for byte in &data {
+            let _ = write!(hex, "{:02x}", byte);
+        }
+        Ok(Json::Object(vec![
+            ("unit", unit_name.into()),
+            ("index", index.into()),
+            ("offset", offset.into()),
+            ("region", sector_region(fs, first_sector).into()),
+            ("data", hex.into()),
+        ]))
+    }
+
+    /// `fat N` : valeur brute de l'entree et sa signification
+    pub(crate) fn write_fat_entry(&self, cluster: u32, output: &mut String) -> Result<(), FileSystemError> {
+        let fs = self.fat_volume()?;
+        let table = fs.fat_table();
+        let value = table.get_entry(cluster)?;
+        let fat_type = table.fat_type();
+        let width = match fat_type {
+            FatType::Fat12 => 3,
+            FatType::Fat16 => 4,
+            FatType::Fat32 | FatType::ExFat => 8,
+        };
+        let last_cluster = fs.boot_sector().cluster_count() + 1;
+        let _ = writeln!(
+            output,
+            "FAT entry {} ({}): 0x{:0width$X} = {}",
+            cluster, fat_type, value, fat_entry_meaning(fat_type, cluster, value, last_cluster), width = width
+        );
+        Ok(())
+    }
+
+    /// `fat N` en JSON : `{"cluster", "fat_type", "value", "meaning"}`
+    pub(crate) fn fat_entry_json(&self, cluster: u32) -> Result<Json, FileSystemError> {
+        let fs = self.fat_volume()?;
+        let table = fs.fat_table();
+        let value = table.get_entry(cluster)?;
+        let last_cluster = fs.boot_sector().cluster_count() + 1;
+        Ok(Json::Object(vec![
+            ("cluster", cluster.into()),
+            ("fat_type", format!("{}", table.fat_type()).into()),
+            ("value", value.into()),
+            ("meaning", fat_entry_meaning(table.fat_type(), cluster, value, last_cluster).into()),
+        ]))
+    }
+
+    /// `chain N` : clusters de la chaine, les suites contigues en `a-b`
+    pub(crate) fn write_chain(&self, cluster: u32, output: &mut String) -> Result<(), FileSystemError> {
+        let fs = self.fat_volume()?;
+        let chain = ClusterChain::new(fs.fat_table(), cluster)?;
+        let cluster_size = fs.boot_sector().cluster_size();
+        let _ = writeln!(
+            output,
+            "Chain from cluster {}: {} ({} bytes)",
+            cluster, clusters_text(chain.len()), chain.len() as u64 * cluster_size as u64
+        );
+
+        output.push(' ');
+        let clusters = chain.clusters();
+        let mut start = 0;
+        while start < clusters.len() {
+            let mut end = start;
+            while end + 1 < clusters.len() && clusters[end + 1] == clusters[end] + 1 {
+                end += 1;
+            }
+            if end == start {
+                let _ = write!(output, " {} ->", clusters[start]);
+            } else {
+                let _ = write!(output, " {}-{} ->", clusters[start], clusters[end]);
+            }
+            start = end + 1;
+        }
+        output.push_str(" end\n");
+        Ok(())
+    }
+
+    /// `chain N` en JSON : `{"start", "clusters": [..], "bytes"}`
+    pub(crate) fn chain_json(&self, cluster: u32) -> Result<Json, FileSystemError> {
+        let fs = self.fat_volume()?;
+        let chain = ClusterChain::new(fs.fat_table(), cluster)?;
+        let bytes = chain.len() as u64 * fs.boot_sector().cluster_size() as u64;
+        Ok(Json::Object(vec![
+            ("start", cluster.into()),
+            ("clusters", Json::Array(chain.clusters().iter().map(|&cluster| cluster.into()).collect())),
+            ("bytes", bytes.into()),
+        ]))
+    }
+
+    /// `bpb` : tous les champs du boot sector, puis les valeurs qui en decoulent
+    pub(crate) fn write_bpb(&self, output: &mut String) -> Result<(), FileSystemError> {
+        let fs = self.fat_volume()?;
+        let bs = fs.boot_sector();
+        let bytes = bs.as_bytes();
+        let text = |field: &[u8]| String::from_utf8_lossy(field).into_owned();
+
+        let _ = writeln!(output, "  Jump:                 {:02X} {:02X} {:02X}", bytes[0], bytes[1], bytes[2]);
+        let _ = writeln!(output, "  OEM name:             {:?}", text(&bytes[3..11]));
+        let _ = writeln!(output, "  Bytes per sector:     {}", { bs.bytes_per_sector });
+        let _ = writeln!(output, "  Sectors per cluster:  {}", { bs.sectors_per_cluster });
+        let _ = writeln!(output, "  Reserved sectors:     {}", { bs.reserved_sector_count });
+        let _ = writeln!(output, "  FATs:                 {}", { bs.num_fats });
+        let _ = writeln!(output, "  Root entries:         {}", { bs.root_entry_count });
+        let _ = writeln!(output, "  Total sectors (16):   {}", { bs.total_sectors_16 });
+        let _ = writeln!(output, "  Media:                0x{:02X}", { bs.media });
+        let _ = writeln!(output, "  Sectors per FAT (16): {}", { bs.sectors_per_fat_16 });
+        let _ = writeln!(output, "  Sectors per track:    {}", { bs.sectors_per_track });
+        let _ = writeln!(output, "  Heads:                {}", { bs.num_heads });
+        let _ = writeln!(output, "  Hidden sectors:       {}", { bs.hidden_sectors });
+        let _ = writeln!(output, "  Total sectors (32):   {}", { bs.total_sectors_32 });
+
+        // la suite depend du type : BPB FAT32 a l'offset 36, BPB etendu FAT12/16 sinon
+        let extended = if fs.fat_type() == FatType::Fat32 {
+            let _ = writeln!(output, "  Sectors per FAT (32): {}", { bs.sectors_per_fat_32 });
+            let _ = writeln!(output, "  Ext flags:            0x{:04X}", { bs.ext_flags });
+            let _ = writeln!(output, "  FS version:           {}.{}", { bs.fat_version } >> 8, { bs.fat_version } & 0xFF);
+            let _ = writeln!(output, "  Root cluster:         {}", { bs.root_cluster });
+            let _ = writeln!(output, "  FSInfo sector:        {}", { bs.fs_info });
+            let _ = writeln!(output, "  Backup boot sector:   {}", { bs.backup_boot_sector });
+            64
+        } else {
+            36
+        };
+        let _ = writeln!(output, "  Drive number:         0x{:02X}", bytes[extended]);
+        let _ = writeln!(output, "  Boot signature:       0x{:02X}", bytes[extended + 2]);
+        let volume_id = u32::from_le_bytes([bytes[extended + 3], bytes[extended + 4], bytes[extended + 5], bytes[extended + 6]]);
+        let _ = writeln!(output, "  Volume ID:            {:04X}-{:04X}", volume_id >> 16, volume_id & 0xFFFF);
+        let _ = writeln!(output, "  Volume label:         {:?}", text(&bytes[extended + 7..extended + 18]));
+        let _ = writeln!(output, "  FS type:              {:?}", text(&bytes[extended + 18..extended + 26]));
+        let boot_code = &bytes[extended + 26..510];
+        let _ = writeln!(
+            output,
+            "  Boot code:            {} bytes ({} non-zero)",
+            boot_code.len(), boot_code.iter().filter(|&&b| b != 0).count()
+        );
+        let _ = writeln!(output, "  Signature:            0x{:04X}", { bs.boot_signature_end });
+
+        let _ = writeln!(output, "  --");
+        let _ = writeln!(output, "  FAT type:             {} ({} clusters)", fs.fat_type(), bs.cluster_count());
+        let _ = writeln!(output, "  Cluster size:         {} bytes", bs.cluster_size());
+        let _ = writeln!(output, "  FAT start sector:     {}", bs.fat_start_sector());
+        let _ = writeln!(output, "  Sectors per FAT:      {}", bs.sectors_per_fat());
+        if bs.root_dir_sectors() > 0 {
+            let _ = writeln!(output, "  Root dir sectors:     {} at {}", bs.root_dir_sectors(), bs.root_dir_start_sector());
+        }
+        let _ = writeln!(output, "  Data start sector:    {}", bs.data_start_sector());
+        Ok(())
+    }
+
+    /// `bpb` en JSON : memes champs que le texte ; ceux du BPB FAT32 valent null en FAT12/16
+    pub(crate) fn bpb_json(&self) -> Result<Json, FileSystemError> {
+        let fs = self.fat_volume()?;
+        let bs = fs.boot_sector();
+        let bytes = bs.as_bytes();
+        let text = |field: &[u8]| Json::from(String::from_utf8_lossy(field).into_owned());
+        let fat32 = fs.fat_type() == FatType::Fat32;
+        let fat32_field = |value: u32| if fat32 { Json::from(value) } else { Json::Null };
+        let extended = if fat32 { 64 } else { 36 };
+        let volume_id = u32::from_le_bytes([bytes[extended + 3], bytes[extended + 4], bytes[extended + 5], bytes[extended + 6]]);
+        let root_dir_sectors = bs.root_dir_sectors();
+
+        Ok(Json::Object(vec![
+            ("jump", Json::Array(bytes[0..3].iter().map(|&b| Json::from(b as u32)).collect())),
+            ("oem_name", text(&bytes[3..11])),
+            ("bytes_per_sector", Json::from({ bs.bytes_per_sector } as u32)),
+            ("sectors_per_cluster", Json::from({ bs.sectors_per_cluster } as u32)),
+            ("reserved_sectors", Json::from({ bs.reserved_sector_count } as u32)),
+            ("fats", Json::from({ bs.num_fats } as u32)),
+            ("root_entries", Json::from({ bs.root_entry_count } as u32)),
+            ("total_sectors_16", Json::from({ bs.total_sectors_16 } as u32)),
+            ("media", Json::from({ bs.media } as u32)),
+            ("sectors_per_fat_16", Json::from({ bs.sectors_per_fat_16 } as u32)),
+            ("sectors_per_track", Json::from({ bs.sectors_per_track } as u32)),
+            ("heads", Json::from({ bs.num_heads } as u32)),
+            ("hidden_sectors", Json::from(bs.hidden_sectors)),
+            ("total_sectors_32", Json::from(bs.total_sectors_32)),
+            ("sectors_per_fat_32", fat32_field(bs.sectors_per_fat_32)),
+            ("ext_flags", fat32_field({ bs.ext_flags } as u32)),
+            ("fs_version", fat32_field({ bs.fat_version } as u32)),
+            ("root_cluster", fat32_field(bs.root_cluster)),
+            ("fs_info_sector", fat32_field({ bs.fs_info } as u32)),
+            ("backup_boot_sector", fat32_field({ bs.backup_boot_sector } as u32)),
+            ("drive_number", Json::from(bytes[extended] as u32)),
+            ("boot_signature", Json::from(bytes[extended + 2] as u32)),
+            ("volume_id", format!("{:04X}-{:04X}", volume_id >> 16, volume_id & 0xFFFF).into()),
+            ("volume_label", text(&bytes[extended + 7..extended + 18])),
+            ("fs_type", text(&bytes[extended + 18..extended + 26])),
+            ("signature", Json::from({ bs.boot_signature_end } as u32)),
+            ("fat_type", format!("{}", fs.fat_type()).into()),
+            ("cluster_count", bs.cluster_count().into()),
+            ("cluster_size", bs.cluster_size().into()),
+            ("fat_start_sector", bs.fat_start_sector().into()),
+            ("sectors_per_fat", bs.sectors_per_fat().into()),
+            ("root_dir_sectors", root_dir_sectors.into()),
+            ("root_dir_start_sector", if root_dir_sectors > 0 { Json::from(bs.root_dir_start_sector()) } else { Json::Null }),
+            ("data_start_sector", bs.data_start_sector().into()),
+        ]))
+    }
+
+    /// `fsck` : problemes trouves par `Fat32Fs::check`, puis le bilan
+    pub(crate) fn write_fsck(&self, output: &mut String) -> Result<(), FileSystemError> {
+        let report = self.fat_volume()?.check()?;
+        for issue in &report.issues {
+            let _ = writeln!(output, "{}", issue);
+        }
+        let _ = write!(
+            output,
+            "{} director{}, {} file{}, {}: ",
+            report.directories,
+            if report.directories == 1 { "y" } else { "ies" },
+            report.files,
+            if report.files == 1 { "" } else { "s" },
+            clusters_text(report.reachable_clusters as usize)
+        );
+        match report.issues.len() {
+            0 => output.push_str("no problems found\n"),
+            1 => output.push_str("1 problem found\n"),
+            count => {
+                let _ = writeln!(output, "{} problems found", count);
+            }
+        }
+        Ok(())
+    }
+
+    /// `fsck` en JSON : `{"clean", "directories", "files", "reachable_clusters", "issues": [..]}`
+    pub(crate) fn fsck_json(&self) -> Result<Json, FileSystemError> {
+        let report = self.fat_volume()?.check()?;
+        Ok(Json::Object(vec![
+            ("clean", report.is_clean().into()),
+            ("directories", report.directories.into()),
+            ("files", report.files.into()),
+            ("reachable_clusters", report.reachable_clusters.into()),
+            ("issues", Json::Array(report.issues.iter().map(fsck_issue_json).collect())),
+        ]))
+    }
+}
+
+/// un probleme de `fsck` : `kind`, `path`, `message`, puis les champs propres au probleme
+fn fsck_issue_json(issue: &FsckIssue) -> Json {
+    let mut fields: Vec<(&'static str, Json)> = vec![
+        ("kind", issue.kind().into()),
+        ("path", issue.path().into()),
+        ("message", format!("{}", issue).into()),
+    ];
+    match issue {
+        FsckIssue::FatCopyMismatch { copy } => fields.push(("copy", (*copy as u32).into())),
+        FsckIssue::BrokenChain { reason, .. } => fields.push(("reason", reason.as_str().into())),
+        FsckIssue::CrossLinked { cluster, other, .. } => {
+            fields.push(("cluster", (*cluster).into()));
+            fields.push(("other", other.as_str().into()));
+        }
+        FsckIssue::SizeMismatch { size, clusters, .. } => {
+            fields.push(("size", (*size).into()));
+            fields.push(("clusters", (*clusters).into()));
+        }
+        FsckIssue::LostClusters { count, first } => {
+            fields.push(("count", (*count).into()));
+            fields.push(("first", (*first).into()));
+        }
+        FsckIssue::FreeCountMismatch { stored, actual } => {
+            fields.push(("stored", (*stored).into()));
+            fields.push(("actual", (*actual).into()));
+        }
+    }
+    Json::Object(fields)
+}
+
+/// role d'un secteur dans le volume
+fn sector_region(fs: &Fat32Fs, sector: u32) -> String {
+    let bs = fs.boot_sector();
+    let mut region = String::new();
+    if sector == 0 {
+        region.push_str("boot sector");
+    } else if Some(sector) == bs.fs_info_sector() {
+        region.push_str("FSInfo");
+    } else if Some(sector) == bs.backup_boot_sector() {
+        region.push_str("backup boot sector");
+    } else if sector < bs.fat_start_sector() {
+        region.push_str("reserved");
+    } else if sector < bs.root_dir_start_sector() {
+        let relative = sector - bs.fat_start_sector();
+        let _ = write!(region, "FAT #{}, sector {}", relative / bs.sectors_per_fat() + 1, relative % bs.sectors_per_fat());
+    } else if sector < bs.data_start_sector() {
+        region.push_str("root directory");
+    } else {
+        let relative = sector - bs.data_start_sector();
+        let cluster = relative / bs.sectors_per_cluster() + 2;
+        if cluster - 2 < bs.cluster_count() {
+            let _ = write!(region, "data, cluster {}", cluster);
+        } else {
+            region.push_str("past the last cluster");
+        }
+    }
+    region
+}
+
+/// signification d'une entree de FAT
+fn fat_entry_meaning(fat_type: FatType, cluster: u32, value: u32, last_cluster: u32) -> String {
+    let mut meaning = String::new();
+    // premiere valeur reservee (0xFF0 / 0xFFF0 / 0x0FFFFFF0), juste avant "defectueux"
+    let reserved_start = fat_type.bad_cluster_marker() - 7;
+    match (cluster, value) {
+        (0, _) => {
+            let _ = write!(meaning, "reserved entry (media descriptor 0x{:02X})", value & 0xFF);
+        }
+        (1, _) => meaning.push_str("reserved entry"),
+        (_, 0) => meaning.push_str("free"),
+        (_, 1) => meaning.push_str("reserved value (invalid in a chain)"),
+        (_, value) if fat_type.is_bad_cluster(value) => meaning.push_str("bad cluster"),
+        (_, value) if fat_type.is_end_of_chain(value) => meaning.push_str("end of chain"),
+        (_, value) if value >= reserved_start => meaning.push_str("reserved value"),
+        (_, value) if value > last_cluster => {
+            let _ = write!(meaning, "points past the last cluster {} (corrupt)", last_cluster);
+        }
+        (_, value) => {
+            let _ = write!(meaning, "next cluster {}", value);
+        }
+    }
+    meaning
+}
+
+/// lignes `hexdump -C` : offset, 16 octets en hexa, ASCII ; lignes repetees remplacees par `*`
+fn write_hex_lines(data: &[u8], base: usize, output: &mut String) {
+    let mut previous: Option<&[u8]> = None;
+    let mut skipping = false;
+    for (i, line) in data.chunks(16).enumerate() {
+        if previous == Some(line) && line.len() == 16 {
+            if !skipping {
+                output.push_str("*\n");
+                skipping = true;
+            }
+            continue;
+        }
+        previous = Some(line);
+        skipping = false;
+
+        let _ = write!(output, "{:08x} ", base + i * 16);
+        for (j, byte) in line.iter().enumerate() {
+            if j == 8 {
+                output.push(' ');
+            }
+            let _ = write!(output, " {:02x}", byte);
+        }
+        for j in line.len()..16 {
+            output.push_str(if j == 8 { "    " } else { "   " });
+        }
+        output.push_str("  |");
+        output.extend(line.iter().map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' }));
+        output.push_str("|\n");
+    }
+    let _ = writeln!(output, "{:08x}", base + data.len());
+}
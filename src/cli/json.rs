@@ -0,0 +1,186 @@
+//! Sortie JSON de la CLI (`--json`), sans serde
+//!
+//! Les documents sont construits en `Json` puis affiches sur une ligne ; l'ordre
+//! des cles est celui de construction, donc stable d'une version a l'autre.
+
+use crate::fs::{Attributes, DateTime, DirEntry, FileSystemError};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Valeur JSON
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(u64),
+    String(String),
+    Array(Vec<Json>),
+    /// Cles dans l'ordre d'affichage
+    Object(Vec<(&'static str, Json)>),
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Self {
+        Json::String(String::from(value))
+    }
+}
+
+impl From<String> for Json {
+    fn from(value: String) -> Self {
+        Json::String(value)
+    }
+}
+
+impl From<u64> for Json {
+    fn from(value: u64) -> Self {
+        Json::Number(value)
+    }
+}
+
+impl From<u32> for Json {
+    fn from(value: u32) -> Self {
+        Json::Number(value as u64)
+    }
+}
+
+impl From<usize> for Json {
+    fn from(value: usize) -> Self {
+        Json::Number(value as u64)
+    }
+}
+
+impl From<bool> for Json {
+    fn from(value: bool) -> Self {
+        Json::Bool(value)
+    }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(value: Option<T>) -> Self {
+        value.map(Into::into).unwrap_or(Json::Null)
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Null => f.write_str("null"),
+            Json::Bool(value) => write!(f, "{}", value),
+            Json::Number(value) => write!(f, "{}", value),
+            Json::String(value) => write_string(f, value),
+            Json::Array(items) => {
+                f.write_str("[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                f.write_str("]")
+            }
+            Json::Object(fields) => {
+                f.write_str("{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+/// chaine entre guillemets, caracteres de controle en `\uXXXX`
+fn write_string(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in value.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    f.write_str("\"")
+}
+
+/// Document d'erreur : `{"error":{"code":"file_not_found","message":"..."}}`
+///
+/// `code` vaut `FileSystemError::code`, ou `usage` pour une commande mal formee.
+pub fn error_document(code: &str, message: &str) -> String {
+    let mut document = Json::Object(vec![("error", error_object(code, message))]).to_string();
+    document.push('\n');
+    document
+}
+
+/// `{"code": ..., "message": ...}`
+fn error_object(code: &str, message: &str) -> Json {
+    Json::Object(vec![("code", code.into()), ("message", message.into())])
+}
+
+/// une erreur dans un document (entree illisible de `tree`)
+pub(crate) fn error(error: &FileSystemError) -> Json {
+    error_object(error.code(), &error.to_string())
+}
+
+impl FileSystemError {
+    /// L'erreur en document JSON (voir `error_document`)
+    pub fn to_json(&self) -> String {
+        error_document(self.code(), &self.to_string())
+    }
+}
+
+/// date ISO 8601 sans fuseau (les dates FAT sont en heure locale)
+pub(crate) fn date(value: Option<DateTime>) -> Json {
+    value
+        .map(|t| {
+            Json::String(format!(
+                "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+                t.year, t.month, t.day, t.hour, t.minute, t.second
+            ))
+        })
+        .unwrap_or(Json::Null)
+}
+
+/// attributs en liste de noms (`["read_only","archive"]`)
+pub(crate) fn attributes(attributes: Attributes) -> Json {
+    let names = [
+        (Attributes::READ_ONLY, "read_only"),
+        (Attributes::HIDDEN, "hidden"),
+        (Attributes::SYSTEM, "system"),
+        (Attributes::VOLUME_ID, "volume_id"),
+        (Attributes::DIRECTORY, "directory"),
+        (Attributes::ARCHIVE, "archive"),
+    ];
+    Json::Array(
+        names
+            .iter()
+            .filter(|(flag, _)| attributes.contains(*flag))
+            .map(|(_, name)| Json::from(*name))
+            .collect(),
+    )
+}
+
+/// une entree de `ls`
+pub(crate) fn entry(name: &str, entry: &DirEntry) -> Json {
+    Json::Object(vec![
+        ("name", name.into()),
+        ("short_name", entry.entry.short_name().ok().into()),
+        ("kind", if entry.is_directory() { "directory" } else { "file" }.into()),
+        ("size", entry.file_size().into()),
+        ("attributes", attributes(entry.attributes())),
+        ("first_cluster", entry.first_cluster().into()),
+        ("created", date(entry.created())),
+        ("modified", date(entry.modified())),
+        ("accessed", date(entry.accessed())),
+    ])
+}
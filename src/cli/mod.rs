@@ -1,24 +1,88 @@
-use crate::fs::{Fat32Fs, FileSystem, FileSystemError};
-use crate::fs::path::PathBuf;
-use alloc::string::String;
-use alloc::vec::Vec;
+//! Moteur de commandes de la CLI, sans std
+//!
+//! `Command::parse` transforme une ligne en commande et `Cli::execute` renvoie la
+//! sortie sous forme de texte : le binaire `main.rs` l'affiche sur le terminal, une
+//! cible embarquee peut l'envoyer sur une UART :
+//!
+//! ```ignore
+//! let mut cli = unsafe { Cli::new(image)? };
+//! let command = Command::parse(line);
+//! match cli.execute(command) {
+//!     Ok(output) => uart.write_str(&output),
+//!     Err(e) => writeln!(uart, "Error: {}", e),
+//! }
+//! ```
+
+pub mod complete;
+#[cfg(feature = "std")]
+pub mod editor;
+#[cfg(feature = "std")]
+mod host;
+mod inspect;
+pub mod json;
+pub mod script;
+pub mod tokenize;
+mod tree;
+
+pub use inspect::DumpUnit;
+pub use script::{ScriptReport, ScriptStep};
+#[cfg(feature = "std")]
+pub use host::build_image;
+
+use crate::fs::{glob, DirEntry, ExFatFs, Fat32Fs, FileKind, FileSystem, FileSystemError};
+use json::Json;
+use crate::fs::exfat::ExFatBootSector;
 use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::Write;
 
 /// CLI command enum
+///
+/// Les chemins restent du texte : ils peuvent etre relatifs (`.`, `..`) ou
+/// contenir des motifs glob, resolus a l'execution.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Command {
-    /// List directory contents
-    List(Option<PathBuf>),
+    /// List directory contents (`-l` : attributs, taille, dates, cluster et nom 8.3 ;
+    /// `-a` : entrees cachees, systeme, `.` et `..` ; `-S` / `-t` : tri, `-r` : ordre inverse)
+    List { path: Option<String>, long: bool, all: bool, sort: ListSort, reverse: bool },
     /// Read file contents
-    Read(PathBuf),
+    Read(String),
+    /// Chercher sous un dossier les noms qui correspondent au motif
+    Find { root: String, name: String },
+    /// Arborescence avec tailles et clusters (`-s` : alias 8.3)
+    Tree { path: Option<String>, short_names: bool },
     /// Change directory
-    ChangeDirectory(PathBuf),
-    /// Create file
-    CreateFile(PathBuf),
-    /// Write to file
-    Write(PathBuf, Vec<u8>),
+    ChangeDirectory(String),
     /// Print current directory
     PrintWorkingDirectory,
+    /// Taille, attributs et dates d'une entree
+    Stat(String),
+    /// Taille du volume, espace libre et label
+    Info,
+    /// Afficher le label, ou le changer
+    Label(Option<String>),
+    /// Create file
+    CreateFile(String),
+    /// Write to file
+    Write(String, Vec<u8>),
+    /// Copier de l'image vers l'hote (`-r` : dossiers)
+    Get { source: String, dest: String, recursive: bool },
+    /// Copier de l'hote vers l'image (`-r` : dossiers)
+    Put { source: String, dest: String, recursive: bool },
+    /// Archive tar d'un fichier ou d'un dossier (`-` : sortie brute)
+    ExportTar { source: String, dest: String },
+    /// Octets d'un secteur ou d'un cluster, facon `hexdump -C`
+    HexDump { unit: DumpUnit, index: u32 },
+    /// Entree de FAT d'un cluster et sa signification
+    FatEntry(u32),
+    /// Chaine de clusters a partir d'un cluster
+    Chain(u32),
+    /// Tous les champs du boot sector
+    Bpb,
+    /// Verifier le volume sans le modifier (chaines, clusters perdus, copies de la FAT)
+    Fsck,
     /// Exit
     Exit,
     /// Help
@@ -27,162 +91,775 @@ pub enum Command {
     Unknown(String),
 }
 
+/// Ordre des entrees de `ls`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ListSort {
+    /// Ordre du dossier sur le disque
+    #[default]
+    Directory,
+    /// Les plus gros d'abord (`-S`)
+    Size,
+    /// Les plus recemment modifies d'abord (`-t`)
+    Modified,
+}
+
 impl Command {
     /// Parse command from input string
+    ///
+    /// La ligne passe par `tokenize` : quotes, `\` et commentaires. Les donnees de
+    /// `write` gardent les espaces d'origine entre les mots.
     pub fn parse(input: &str) -> Self {
-        let parts: Vec<&str> = input.trim().split_whitespace().collect();
+        let tokens = match tokenize::tokenize(input) {
+            Ok(tokens) => tokens,
+            Err(FileSystemError::InvalidPath(msg)) => return Command::Unknown(msg),
+            Err(e) => return Command::Unknown(e.to_string()),
+        };
+        let args: Vec<&str> = tokens.iter().map(|token| token.value.as_str()).collect();
+        match Self::from_args(&args) {
+            Command::Write(path, _) => {
+                let mut data = tokens[2].value.clone();
+                for token in &tokens[3..] {
+                    data.push_str(&token.separator);
+                    data.push_str(&token.value);
+                }
+                Command::Write(path, data.into_bytes())
+            }
+            command => command,
+        }
+    }
+
+    /// Commande deja decoupee en arguments, par exemple `argv` apres l'image
+    pub fn from_args<S: AsRef<str>>(args: &[S]) -> Self {
+        let parts: Vec<&str> = args.iter().map(|arg| arg.as_ref()).collect();
         if parts.is_empty() {
             return Command::Unknown(String::new());
         }
-        
+
         match parts[0] {
             "ls" | "list" => {
-                if parts.len() > 1 {
-                    match PathBuf::new(parts[1]) {
-                        Ok(path) => Command::List(Some(path)),
-                        Err(_) => Command::Unknown(format!("Invalid path: {}", parts[1])),
+                let (mut long, mut all, mut sort, mut reverse) = (false, false, ListSort::Directory, false);
+                let mut path = None;
+                for arg in &parts[1..] {
+                    // options groupees : `-laS`
+                    let Some(flags) = arg.strip_prefix('-').filter(|flags| !flags.is_empty()) else {
+                        path = Some(arg.to_string());
+                        continue;
+                    };
+                    for flag in flags.chars() {
+                        match flag {
+                            'l' => long = true,
+                            'a' => all = true,
+                            'S' => sort = ListSort::Size,
+                            't' => sort = ListSort::Modified,
+                            'r' => reverse = true,
+                            _ => return Command::Unknown("Usage: ls [-l] [-a] [-S|-t] [-r] [path]".into()),
+                        }
                     }
-                } else {
-                    Command::List(None)
                 }
+                Command::List { path, long, all, sort, reverse }
             }
-            "cat" | "read" => {
-                if parts.len() > 1 {
-                    match PathBuf::new(parts[1]) {
-                        Ok(path) => Command::Read(path),
-                        Err(_) => Command::Unknown(format!("Invalid path: {}", parts[1])),
+            "cat" | "read" => match parts.get(1) {
+                Some(path) => Command::Read(path.to_string()),
+                None => Command::Unknown("Usage: cat <file>".into()),
+            },
+            "find" => {
+                // find [path] [-name PATTERN]
+                let mut root = ".";
+                let mut name = Some("*");
+                let mut args = parts[1..].iter();
+                while let Some(arg) = args.next() {
+                    match *arg {
+                        "-name" => name = args.next().copied(),
+                        other => root = other,
                     }
-                } else {
-                    Command::Unknown("Missing file path".into())
                 }
-            }
-            "cd" => {
-                if parts.len() > 1 {
-                    match PathBuf::new(parts[1]) {
-                        Ok(path) => Command::ChangeDirectory(path),
-                        Err(_) => Command::Unknown(format!("Invalid path: {}", parts[1])),
-                    }
-                } else {
-                    Command::ChangeDirectory(PathBuf::root())
+                match name {
+                    Some(name) => Command::Find { root: root.to_string(), name: name.to_string() },
+                    None => Command::Unknown("Usage: find [path] [-name pattern]".into()),
                 }
             }
-            "pwd" => Command::PrintWorkingDirectory,
-            "create" => {
-                if parts.len() > 1 {
-                    match PathBuf::new(parts[1]) {
-                        Ok(path) => Command::CreateFile(path),
-                        Err(_) => Command::Unknown(format!("Invalid path: {}", parts[1])),
-                    }
-                } else {
-                    Command::Unknown("Missing file path".into())
-                }
+            "tree" => {
+                let short_names = parts[1..].iter().any(|p| matches!(*p, "-s" | "--short-names"));
+                let path = parts[1..].iter().find(|p| !p.starts_with('-')).map(|p| p.to_string());
+                Command::Tree { path, short_names }
             }
+            "cd" => Command::ChangeDirectory(parts.get(1).copied().unwrap_or("/").to_string()),
+            "pwd" => Command::PrintWorkingDirectory,
+            "stat" => match parts.get(1) {
+                Some(path) => Command::Stat(path.to_string()),
+                None => Command::Unknown("Usage: stat <path>".into()),
+            },
+            "info" | "df" => Command::Info,
+            // le label peut contenir des espaces : on prend tous les arguments
+            "label" if parts.len() > 1 => Command::Label(Some(parts[1..].join(" "))),
+            "label" => Command::Label(None),
+            "create" => match parts.get(1) {
+                Some(path) => Command::CreateFile(path.to_string()),
+                None => Command::Unknown("Missing file path".into()),
+            },
             "write" => {
                 if parts.len() > 2 {
-                    match PathBuf::new(parts[1]) {
-                        Ok(path) => {
-                            let data = parts[2..].join(" ").into_bytes();
-                            Command::Write(path, data)
-                        }
-                        Err(_) => Command::Unknown(format!("Invalid path: {}", parts[1])),
-                    }
+                    let data = parts[2..].join(" ").into_bytes();
+                    Command::Write(parts[1].to_string(), data)
                 } else {
                     Command::Unknown("Missing file path or data".into())
                 }
             }
+            "get" | "put" => {
+                let recursive = parts[1..].contains(&"-r");
+                let paths: Vec<&str> = parts[1..].iter().copied().filter(|p| *p != "-r").collect();
+                match (parts[0], paths.as_slice()) {
+                    ("get", [source, dest]) => Command::Get { source: source.to_string(), dest: dest.to_string(), recursive },
+                    ("put", [source, dest]) => Command::Put { source: source.to_string(), dest: dest.to_string(), recursive },
+                    ("get", _) => Command::Unknown("Usage: get [-r] <image-path> <host-path>".into()),
+                    _ => Command::Unknown("Usage: put [-r] <host-path> <image-path>".into()),
+                }
+            }
+            "export-tar" => match parts[1..] {
+                [source, dest] => Command::ExportTar { source: source.to_string(), dest: dest.to_string() },
+                _ => Command::Unknown("Usage: export-tar <image-path> <out.tar|->".into()),
+            },
+            "hexdump" => {
+                let unit = match parts.get(1) {
+                    Some(&"sector") => Some(DumpUnit::Sector),
+                    Some(&"cluster") => Some(DumpUnit::Cluster),
+                    _ => None,
+                };
+                match (unit, parts.get(2).and_then(|n| parse_number(n)), parts.len()) {
+                    (Some(unit), Some(index), 3) => Command::HexDump { unit, index },
+                    _ => Command::Unknown("Usage: hexdump <sector|cluster> <n>".into()),
+                }
+            }
+            "fat" => match (parts.get(1).and_then(|n| parse_number(n)), parts.len()) {
+                (Some(cluster), 2) => Command::FatEntry(cluster),
+                _ => Command::Unknown("Usage: fat <cluster>".into()),
+            },
+            "chain" => match (parts.get(1).and_then(|n| parse_number(n)), parts.len()) {
+                (Some(cluster), 2) => Command::Chain(cluster),
+                _ => Command::Unknown("Usage: chain <cluster>".into()),
+            },
+            "bpb" => Command::Bpb,
+            "fsck" => Command::Fsck,
             "exit" | "quit" | "q" => Command::Exit,
             "help" | "?" => Command::Help,
-            _ => Command::Unknown(format!("Unknown command: {}", parts[0])),
+            _ => Command::Unknown(format!("Unknown command: {}. Type 'help' for help.", parts[0])),
+        }
+    }
+}
+
+/// nombre decimal ou hexadecimal (`0x1F`)
+fn parse_number(text: &str) -> Option<u32> {
+    match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+impl Command {
+    /// La commande modifie-t-elle l'image (a sauvegarder ensuite) ?
+    pub fn modifies(&self) -> bool {
+        matches!(self, Command::CreateFile(_) | Command::Write(..) | Command::Put { .. } | Command::Label(Some(_)))
+    }
+}
+
+/// Format de la sortie de `Cli::execute`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Texte pour un terminal
+    #[default]
+    Text,
+    /// Un document JSON par commande (`--json`)
+    Json,
+}
+
+/// Volume monte : FAT12/16/32 ou exFAT
+pub enum Volume {
+    Fat(Fat32Fs),
+    ExFat(ExFatFs),
+}
+
+impl Volume {
+    /// Monte une image : exFAT se reconnait au nom dans le boot sector, le reste passe par Fat32Fs
+    ///
+    /// # Safety
+    ///
+    /// device_data doit contenir une image FAT ou exFAT complete, boot sector en tete.
+    pub unsafe fn mount(device_data: &[u8]) -> Result<Self, FileSystemError> {
+        if ExFatBootSector::is_exfat(device_data) {
+            ExFatFs::new(device_data).map(Volume::ExFat)
+        } else {
+            Fat32Fs::new(device_data).map(Volume::Fat)
+        }
+    }
+
+    /// Le systeme de fichiers, quel que soit son type
+    pub fn fs(&self) -> &dyn FileSystem {
+        match self {
+            Volume::Fat(fs) => fs,
+            Volume::ExFat(fs) => fs,
+        }
+    }
+
+    /// Le systeme de fichiers, en ecriture
+    pub fn fs_mut(&mut self) -> &mut dyn FileSystem {
+        match self {
+            Volume::Fat(fs) => fs,
+            Volume::ExFat(fs) => fs,
+        }
+    }
+
+    /// Image avec les modifications faites (None en exFAT, monte en lecture seule)
+    pub fn device_data(&self) -> Option<Vec<u8>> {
+        match self {
+            Volume::Fat(fs) => Some(fs.device_data().to_vec()),
+            Volume::ExFat(_) => None,
         }
     }
 }
 
 /// CLI handler
 pub struct Cli {
-    /// Filesystem instance
-    fs: Fat32Fs,
+    /// Volume monte
+    volume: Volume,
+    /// Texte ou JSON
+    format: OutputFormat,
 }
 
 impl Cli {
     /// Create a new CLI instance
-    /// 
+    ///
     /// # Safety
-    /// 
-    /// The device_data must be valid FAT32 filesystem data.
+    ///
+    /// The device_data must be a valid FAT or exFAT filesystem image.
     pub unsafe fn new(device_data: &[u8]) -> Result<Self, FileSystemError> {
-        let fs = Fat32Fs::new(device_data)?;
-        Ok(Self { fs })
+        Ok(Self::from_volume(Volume::mount(device_data)?))
     }
-    
+
+    /// CLI sur un volume deja monte (horloge, options de Fat32Fs...)
+    pub fn from_volume(volume: Volume) -> Self {
+        Self { volume, format: OutputFormat::Text }
+    }
+
+    /// Sortie en texte (par defaut) ou en JSON
+    pub fn with_output_format(mut self, format: OutputFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Format de sortie courant
+    pub fn output_format(&self) -> OutputFormat {
+        self.format
+    }
+
     /// Execute a command
+    ///
+    /// Chaque ligne de la sortie finit par `\n`, sauf pour `cat` qui renvoie le fichier tel quel.
+    /// En `OutputFormat::Json`, un document JSON sur une ligne (voir `execute_json`).
     pub fn execute(&mut self, command: Command) -> Result<String, FileSystemError> {
+        if self.format == OutputFormat::Json {
+            return self.execute_json(command);
+        }
+        let mut output = String::new();
         match command {
-            Command::List(path) => {
-                let target_path = path
-                    .map(|p| p.as_path().clone())
-                    .unwrap_or_else(|| self.fs.current_directory().clone());
-                
-                let entries = self.fs.list(&target_path)?;
-                let mut output = String::new();
-                for entry in entries {
-                    // TODO: Format directory entries properly
-                    output.push_str(&format!("{:?}\n", entry));
-                }
-                Ok(output)
+            Command::List { path, long, all, sort, reverse } => {
+                let path = path.as_deref().unwrap_or(".");
+                let entries = self.list_entries(path, all, sort, reverse)?;
+                if entries.is_empty() {
+                    output.push_str(if glob::is_pattern(path) { "(no match)\n" } else { "(empty)\n" });
+                }
+                for (name, entry) in entries {
+                    match name {
+                        Ok(name) => write_entry(&mut output, &name, &entry, long),
+                        Err(e) => {
+                            let _ = writeln!(output, "<error: {}>", e);
+                        }
+                    }
+                }
             }
             Command::Read(path) => {
-                let data = self.fs.read_file(path.as_path())?;
-                let data_len = data.len();
-                match String::from_utf8(data) {
-                    Ok(s) => Ok(s),
-                    Err(_) => Ok(format!("<binary data, {} bytes>", data_len)),
+                for file in self.matching_files(&path)? {
+                    let data = self.volume.fs().read_file(&file)?;
+                    let data_len = data.len();
+                    match String::from_utf8(data) {
+                        Ok(text) => output.push_str(&text),
+                        Err(_) => {
+                            let _ = writeln!(output, "<binary data, {} bytes>", data_len);
+                        }
+                    }
+                }
+            }
+            Command::Find { root, name } => {
+                let pattern = format!("{}/**/{}", root.trim_end_matches('/'), name);
+                for (path, entry) in self.volume.fs().glob(&pattern)? {
+                    let _ = writeln!(output, "{}{}", path, if entry.is_directory() { "/" } else { "" });
                 }
             }
+            Command::Tree { path, short_names } => {
+                self.write_tree(path.as_deref().unwrap_or("."), short_names, &mut output)?;
+            }
             Command::ChangeDirectory(path) => {
-                self.fs.cd(path.as_path())?;
-                Ok(format!("Changed to: {}", self.fs.current_directory().to_string()))
+                self.volume.fs_mut().cd(&path)?;
             }
             Command::PrintWorkingDirectory => {
-                Ok(self.fs.current_directory().to_string())
+                output.push_str(&self.volume.fs().pwd());
+                output.push('\n');
+            }
+            Command::Stat(path) => {
+                let meta = self.volume.fs().metadata(&path)?;
+                let date = |t: Option<crate::DateTime>| t.map(|t| t.to_string()).unwrap_or_else(|| "-".into());
+                let _ = writeln!(output, "  Name:       {}", meta.name());
+                let _ = writeln!(output, "  Short name: {}", meta.short_name);
+                let _ = writeln!(output, "  Kind:       {:?}", meta.kind);
+                let _ = writeln!(output, "  Size:       {} ({} allocated)", meta.size, meta.allocated_size);
+                let _ = writeln!(output, "  Attributes: {}", meta.attributes);
+                let _ = writeln!(output, "  Cluster:    {}", meta.first_cluster);
+                if let Some(location) = meta.location {
+                    let _ = writeln!(output, "  Entry:      #{} in directory cluster {}", location.index, location.dir_cluster);
+                }
+                let _ = writeln!(output, "  Created:    {}", date(meta.created));
+                let _ = writeln!(output, "  Modified:   {}", date(meta.modified));
+                let _ = writeln!(output, "  Accessed:   {}", date(meta.accessed));
             }
+            Command::Info => self.write_info(&mut output),
+            Command::Label(new_label) => match (&mut self.volume, new_label) {
+                (Volume::Fat(fs), Some(new_label)) => fs.set_label(&new_label)?,
+                (Volume::Fat(fs), None) => {
+                    let _ = writeln!(output, "{}", fs.label().as_deref().unwrap_or("(no label)"));
+                }
+                (Volume::ExFat(_), Some(_)) => {
+                    return Err(FileSystemError::ReadOnly("exFAT support is read-only".into()));
+                }
+                (Volume::ExFat(fs), None) => {
+                    let _ = writeln!(output, "{}", fs.volume_label().unwrap_or("(no label)"));
+                }
+            },
             Command::CreateFile(path) => {
-                self.fs.create_file(path.as_path())?;
-                Ok(format!("Created file: {}", path.to_string()))
+                self.volume.fs_mut().create_file(&path)?;
             }
             Command::Write(path, data) => {
-                self.fs.write_file(path.as_path(), &data)?;
-                Ok(format!("Wrote {} bytes to: {}", data.len(), path.to_string()))
+                self.volume.fs_mut().write_file(&path, &data)?;
+                let _ = writeln!(output, "Wrote {} bytes to {}", data.len(), path);
+            }
+            #[cfg(feature = "std")]
+            Command::Get { source, dest, recursive } => {
+                host::write_transfers("get", &self.get(&source, &dest, recursive)?, &mut output);
+            }
+            #[cfg(feature = "std")]
+            Command::Put { source, dest, recursive } => {
+                host::write_transfers("put", &self.put(&source, &dest, recursive)?, &mut output);
+            }
+            #[cfg(not(feature = "std"))]
+            Command::Get { .. } | Command::Put { .. } => {
+                return Err(FileSystemError::Unsupported("get / put need the std feature".into()));
+            }
+            #[cfg(all(feature = "std", feature = "tar"))]
+            Command::ExportTar { source, dest } => {
+                let size = self.export_tar(&source, &dest)?;
+                if dest == "-" {
+                    let _ = writeln!(output, "<tar archive, {} bytes>", size);
+                } else {
+                    let _ = writeln!(output, "export-tar {} -> {} ({} bytes)", source, dest, size);
+                }
+            }
+            #[cfg(not(all(feature = "std", feature = "tar")))]
+            Command::ExportTar { .. } => {
+                return Err(FileSystemError::Unsupported("export-tar needs the std and tar features".into()));
+            }
+            Command::HexDump { unit, index } => self.write_hexdump(unit, index, &mut output)?,
+            Command::FatEntry(cluster) => self.write_fat_entry(cluster, &mut output)?,
+            Command::Chain(cluster) => self.write_chain(cluster, &mut output)?,
+            Command::Bpb => self.write_bpb(&mut output)?,
+            Command::Fsck => self.write_fsck(&mut output)?,
+            Command::Help => output.push_str(HELP),
+            Command::Exit => output.push_str("Goodbye!\n"),
+            Command::Unknown(msg) => return Err(FileSystemError::InvalidPath(msg)),
+        }
+        Ok(output)
+    }
+
+    /// Comme `execute`, mais `cat` et `export-tar ... -` renvoient les octets tels quels
+    ///
+    /// Pour rediriger un fichier binaire (`mini-fat32 image cat /a.bin > a.bin`).
+    pub fn execute_raw(&mut self, command: Command) -> Result<Vec<u8>, FileSystemError> {
+        if self.format == OutputFormat::Json {
+            return self.execute(command).map(String::into_bytes);
+        }
+        match command {
+            Command::Read(path) => {
+                let mut data = Vec::new();
+                for file in self.matching_files(&path)? {
+                    data.extend_from_slice(&self.volume.fs().read_file(&file)?);
+                }
+                Ok(data)
+            }
+            #[cfg(feature = "tar")]
+            Command::ExportTar { source, dest } if dest == "-" => crate::fs::tar::export(self.volume.fs(), &source),
+            command => self.execute(command).map(String::into_bytes),
+        }
+    }
+
+    /// Comme `execute`, en JSON
+    ///
+    /// Chaque commande a son document (voir le README) ; `cat` donne le contenu en
+    /// UTF-8, ou en hexa pour un fichier binaire.
+    pub fn execute_json(&mut self, command: Command) -> Result<String, FileSystemError> {
+        let document = match command {
+            Command::List { path, all, sort, reverse, .. } => {
+                let path = path.as_deref().unwrap_or(".");
+                let entries = self.list_entries(path, all, sort, reverse)?
+                    .into_iter()
+                    .map(|(name, entry)| name.map(|name| json::entry(&name, &entry)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Json::Object(vec![("path", path.into()), ("entries", Json::Array(entries))])
+            }
+            Command::Stat(path) => {
+                let meta = self.volume.fs().metadata(&path)?;
+                let kind = match meta.kind {
+                    FileKind::File => "file",
+                    FileKind::Directory => "directory",
+                    FileKind::VolumeLabel => "volume_label",
+                };
+                let location = meta.location.map(|location| {
+                    Json::Object(vec![("index", location.index.into()), ("dir_cluster", location.dir_cluster.into())])
+                });
+                Json::Object(vec![
+                    ("path", path.as_str().into()),
+                    ("name", meta.name().into()),
+                    ("short_name", meta.short_name.as_str().into()),
+                    ("kind", kind.into()),
+                    ("size", meta.size.into()),
+                    ("allocated_size", meta.allocated_size.into()),
+                    ("attributes", json::attributes(meta.attributes)),
+                    ("first_cluster", meta.first_cluster.into()),
+                    ("entry", location.unwrap_or(Json::Null)),
+                    ("created", json::date(meta.created)),
+                    ("modified", json::date(meta.modified)),
+                    ("accessed", json::date(meta.accessed)),
+                ])
+            }
+            Command::Info => {
+                let summary = self.volume_summary();
+                Json::Object(vec![
+                    ("type", summary.kind.into()),
+                    ("label", summary.label.into()),
+                    ("volume_id", summary.volume_id.map(|id| format!("{:04X}-{:04X}", id >> 16, id & 0xFFFF)).into()),
+                    ("cluster_size", summary.cluster_size.into()),
+                    ("total_clusters", summary.total.into()),
+                    ("used_clusters", summary.used.into()),
+                    ("free_clusters", summary.free.into()),
+                    ("bad_clusters", summary.bad.into()),
+                    ("fs_info_free_clusters", summary.fs_info_free.into()),
+                ])
+            }
+            Command::Tree { path, .. } => self.tree_json(path.as_deref().unwrap_or("."))?,
+            Command::Read(path) => {
+                let mut files = Vec::new();
+                for file in self.matching_files(&path)? {
+                    let data = self.volume.fs().read_file(&file)?;
+                    let size = data.len();
+                    let (encoding, data) = match String::from_utf8(data) {
+                        Ok(text) => ("utf-8", text),
+                        Err(e) => ("hex", e.into_bytes().iter().map(|b| format!("{:02x}", b)).collect()),
+                    };
+                    files.push(Json::Object(vec![
+                        ("path", file.into()),
+                        ("size", size.into()),
+                        ("encoding", encoding.into()),
+                        ("data", data.into()),
+                    ]));
+                }
+                Json::Object(vec![("files", Json::Array(files))])
+            }
+            Command::Find { root, name } => {
+                let pattern = format!("{}/**/{}", root.trim_end_matches('/'), name);
+                let matches = self.volume.fs().glob(&pattern)?
+                    .into_iter()
+                    .map(|(path, entry)| json::entry(&path.to_string(), &entry))
+                    .collect();
+                Json::Object(vec![("root", root.into()), ("name", name.into()), ("matches", Json::Array(matches))])
+            }
+            Command::ChangeDirectory(path) => {
+                self.volume.fs_mut().cd(&path)?;
+                Json::Object(vec![("cwd", self.volume.fs().pwd().into())])
+            }
+            Command::PrintWorkingDirectory => Json::Object(vec![("cwd", self.volume.fs().pwd().into())]),
+            Command::Label(new_label) => {
+                let label = match (&mut self.volume, new_label) {
+                    (Volume::Fat(fs), Some(new_label)) => {
+                        fs.set_label(&new_label)?;
+                        fs.label()
+                    }
+                    (Volume::Fat(fs), None) => fs.label(),
+                    (Volume::ExFat(_), Some(_)) => {
+                        return Err(FileSystemError::ReadOnly("exFAT support is read-only".into()));
+                    }
+                    (Volume::ExFat(fs), None) => fs.volume_label().map(String::from),
+                };
+                Json::Object(vec![("label", label.into())])
+            }
+            Command::CreateFile(path) => {
+                self.volume.fs_mut().create_file(&path)?;
+                Json::Object(vec![("path", path.into()), ("size", 0u32.into())])
+            }
+            Command::Write(path, data) => {
+                self.volume.fs_mut().write_file(&path, &data)?;
+                Json::Object(vec![("path", path.into()), ("size", data.len().into())])
+            }
+            #[cfg(feature = "std")]
+            Command::Get { source, dest, recursive } => {
+                let transfers = self.get(&source, &dest, recursive)?;
+                host::transfers_json(&source, &dest, &transfers)
+            }
+            #[cfg(feature = "std")]
+            Command::Put { source, dest, recursive } => {
+                let transfers = self.put(&source, &dest, recursive)?;
+                host::transfers_json(&source, &dest, &transfers)
+            }
+            #[cfg(not(feature = "std"))]
+            Command::Get { .. } | Command::Put { .. } => {
+                return Err(FileSystemError::Unsupported("get / put need the std feature".into()));
             }
-            Command::Help => {
-                Ok(String::from(
-                    "Available commands:\n\
-                     ls [path]          - List directory contents\n\
-                     cat <path>         - Read file contents\n\
-                     cd [path]          - Change directory\n\
-                     pwd                - Print current directory\n\
-                     create <path>      - Create a new file\n\
-                     write <path> <data> - Write data to file\n\
-                     help               - Show this help\n\
-                     exit               - Exit the CLI"
-                ))
+            #[cfg(all(feature = "std", feature = "tar"))]
+            Command::ExportTar { source, dest } => {
+                let size = self.export_tar(&source, &dest)?;
+                Json::Object(vec![("source", source.into()), ("dest", dest.into()), ("size", size.into())])
             }
-            Command::Exit => {
-                Ok(String::from("Exiting..."))
+            #[cfg(not(all(feature = "std", feature = "tar")))]
+            Command::ExportTar { .. } => {
+                return Err(FileSystemError::Unsupported("export-tar needs the std and tar features".into()));
             }
-            Command::Unknown(msg) => {
-                Err(FileSystemError::InvalidPath(msg))
+            Command::HexDump { unit, index } => self.hexdump_json(unit, index)?,
+            Command::FatEntry(cluster) => self.fat_entry_json(cluster)?,
+            Command::Chain(cluster) => self.chain_json(cluster)?,
+            Command::Bpb => self.bpb_json()?,
+            Command::Fsck => self.fsck_json()?,
+            Command::Help => help_json(),
+            Command::Exit => Json::Object(vec![("exit", true.into())]),
+            Command::Unknown(msg) => return Err(FileSystemError::InvalidPath(msg)),
+        };
+        let mut output = document.to_string();
+        output.push('\n');
+        Ok(output)
+    }
+
+    /// entrees de `ls` (nom, ou chemin avec un motif), filtrees et triees
+    fn list_entries(
+        &self,
+        path: &str,
+        all: bool,
+        sort: ListSort,
+        reverse: bool,
+    ) -> Result<Vec<ListedEntry>, FileSystemError> {
+        let fs = self.volume.fs();
+        // avec un motif on affiche le chemin de chaque correspondance
+        let mut entries: Vec<ListedEntry> = if glob::is_pattern(path) {
+            fs.glob(path)?.into_iter().map(|(path, entry)| (Ok(path.to_string()), entry)).collect()
+        } else {
+            fs.list(path)?.into_iter().map(|entry| (entry.name(), entry)).collect()
+        };
+        if !all {
+            entries.retain(|(name, entry)| {
+                !entry.is_hidden() && !entry.is_system() && !matches!(name.as_deref(), Ok(".") | Ok(".."))
+            });
+        }
+        // tri stable : a egalite, l'ordre du dossier est garde
+        match sort {
+            ListSort::Directory => {}
+            ListSort::Size => entries.sort_by_key(|(_, entry)| core::cmp::Reverse(entry.file_size())),
+            ListSort::Modified => entries.sort_by_key(|(_, entry)| core::cmp::Reverse(entry.modified())),
+        }
+        if reverse {
+            entries.reverse();
+        }
+        Ok(entries)
+    }
+
+    /// fichiers designes par un chemin ou un motif (un motif sans correspondance est une erreur)
+    fn matching_files(&self, path: &str) -> Result<Vec<String>, FileSystemError> {
+        if !glob::is_pattern(path) {
+            return Ok(vec![path.to_string()]);
+        }
+        let found = self.volume.fs().glob(path)?;
+        if found.is_empty() {
+            return Err(FileSystemError::FileNotFound(format!("no match for {}", path)));
+        }
+        Ok(found.into_iter().filter(|(_, entry)| !entry.is_directory()).map(|(path, _)| path.to_string()).collect())
+    }
+
+    /// chiffres de info / df, FAT ou exFAT
+    fn volume_summary(&self) -> VolumeSummary {
+        match &self.volume {
+            Volume::Fat(fs) => {
+                let info = fs.volume_info();
+                VolumeSummary {
+                    kind: info.fat_type.to_string(),
+                    label: info.volume_label,
+                    volume_id: info.volume_id,
+                    cluster_size: info.cluster_size,
+                    total: info.total_clusters,
+                    used: info.used_clusters,
+                    free: info.free_clusters,
+                    bad: Some(info.bad_clusters),
+                    fs_info_free: info.fs_info_free_clusters,
+                }
+            }
+            Volume::ExFat(fs) => {
+                let bs = fs.boot_sector();
+                let free = fs.allocation_bitmap().free_clusters();
+                VolumeSummary {
+                    kind: "exFAT".to_string(),
+                    label: fs.volume_label().map(String::from),
+                    volume_id: Some(bs.volume_serial),
+                    cluster_size: bs.cluster_size(),
+                    total: bs.cluster_count(),
+                    used: bs.cluster_count() - free,
+                    free,
+                    bad: None,
+                    fs_info_free: None,
+                }
             }
         }
     }
-    
+
+    /// commande info / df
+    fn write_info(&self, output: &mut String) {
+        let VolumeSummary { kind, label, volume_id: serial, cluster_size, total, used, free, bad, fs_info_free } = self.volume_summary();
+        // lignes propres a FAT : clusters defectueux, compteur FSInfo faux
+        let mut notes = Vec::new();
+        if let Some(bad) = bad.filter(|&bad| bad > 0) {
+            notes.push(format!("  Bad:            {} clusters", bad));
+        }
+        if let Some(fs_info_free) = fs_info_free.filter(|&count| count != free) {
+            notes.push(format!("  FSInfo free:    {} clusters (stale)", fs_info_free));
+        }
+        let bytes = |clusters: u32| clusters as u64 * cluster_size as u64;
+        let _ = writeln!(output, "  Type:           {}", kind);
+        let _ = writeln!(output, "  Label:          {}", label.as_deref().unwrap_or("-"));
+        match serial {
+            Some(id) => {
+                let _ = writeln!(output, "  Volume ID:      {:04X}-{:04X}", id >> 16, id & 0xFFFF);
+            }
+            None => output.push_str("  Volume ID:      -\n"),
+        }
+        let _ = writeln!(output, "  Cluster size:   {} bytes", cluster_size);
+        let _ = writeln!(output, "  Total:          {} clusters ({} bytes)", total, bytes(total));
+        let _ = writeln!(output, "  Used:           {} clusters ({} bytes)", used, bytes(used));
+        let _ = writeln!(output, "  Free:           {} clusters ({} bytes)", free, bytes(free));
+        for note in notes {
+            output.push_str(&note);
+            output.push('\n');
+        }
+    }
+
+    /// Volume monte
+    pub fn volume(&self) -> &Volume {
+        &self.volume
+    }
+
+    /// Volume monte, en ecriture
+    pub fn volume_mut(&mut self) -> &mut Volume {
+        &mut self.volume
+    }
+
     /// Get filesystem reference
-    pub fn filesystem(&self) -> &Fat32Fs {
-        &self.fs
+    pub fn filesystem(&self) -> &dyn FileSystem {
+        self.volume.fs()
     }
-    
+
     /// Get mutable filesystem reference
-    pub fn filesystem_mut(&mut self) -> &mut Fat32Fs {
-        &mut self.fs
+    pub fn filesystem_mut(&mut self) -> &mut dyn FileSystem {
+        self.volume.fs_mut()
+    }
+}
+
+/// Entree de `ls` : nom (ou chemin avec un motif) et entree du dossier
+type ListedEntry = (Result<String, FileSystemError>, DirEntry);
+
+/// Chiffres de `info`, communs a FAT et exFAT
+struct VolumeSummary {
+    kind: String,
+    label: Option<String>,
+    volume_id: Option<u32>,
+    cluster_size: u32,
+    total: u32,
+    used: u32,
+    free: u32,
+    /// clusters defectueux (FAT seulement)
+    bad: Option<u32>,
+    /// compteur de clusters libres de FSInfo (FAT32)
+    fs_info_free: Option<u32>,
+}
+
+/// une ligne de `ls`, ou de `ls -l` : modifie, cree (a 10 ms pres), dernier acces (jour
+/// seulement), premier cluster, nom 8.3 puis nom long
+fn write_entry(output: &mut String, name: &str, entry: &DirEntry, long: bool) {
+    let marker = if entry.is_directory() { "/" } else { "" };
+    if !long {
+        let _ = writeln!(output, "{}{}", name, marker);
+        return;
+    }
+    let kind = if entry.is_directory() { 'd' } else { '-' };
+    let modified = entry.modified().map(|t| t.to_string()).unwrap_or_else(|| "-".into());
+    let created = entry.created()
+        .map(|t| format!("{}.{:02}", t, t.millisecond / 10))
+        .unwrap_or_else(|| "-".into());
+    let accessed = entry.accessed()
+        .map(|t| format!("{:04}-{:02}-{:02}", t.year, t.month, t.day))
+        .unwrap_or_else(|| "-".into());
+    let short_name = entry.entry.short_name().unwrap_or_else(|_| "?".into());
+    let _ = writeln!(
+        output,
+        "{} {} {:>10}  {:<19}  {:<22}  {:<10}  {:>8}  {:<12}  {}{}",
+        kind, entry.attributes(), entry.file_size(), modified, created, accessed, entry.first_cluster(), short_name, name, marker
+    );
+}
+
+/// `help` en JSON : `{"commands": [{"usage", "description", "details": [..]}]}`, tire de `HELP`
+fn help_json() -> Json {
+    let mut commands: Vec<Vec<(&'static str, Json)>> = Vec::new();
+    for line in HELP.lines().skip(1) {
+        match line.strip_prefix("  ").and_then(|line| line.split_once(" - ")) {
+            // une commande : deux espaces, usage, ` - `, description
+            Some((usage, description)) if !usage.starts_with(' ') => commands.push(vec![
+                ("usage", usage.trim_end().into()),
+                ("description", description.into()),
+                ("details", Json::Array(Vec::new())),
+            ]),
+            // ligne de detail sous la commande precedente
+            _ => {
+                if let Some((_, Json::Array(details))) = commands.last_mut().and_then(|command| command.last_mut()) {
+                    details.push(line.trim().into());
+                }
+            }
+        }
     }
+    Json::Object(vec![("commands", Json::Array(commands.into_iter().map(Json::Object).collect()))])
 }
 
+const HELP: &str = "\
+Available commands:
+  ls [-laStr] [path] - List directory contents
+                  -l: attributes, size, modified, created, accessed, first cluster, 8.3 name
+                  -a: include hidden, system, . and ..; -S: by size; -t: by date; -r: reverse
+  cat <file>    - Read and display file
+  find [path] [-name pattern] - Search entries below path
+                  Paths accept patterns: *, ?, [a-z], ** (any depth), case-insensitive
+  tree [-s] [path] - Show the hierarchy with sizes and clusters (-s: 8.3 aliases)
+  cd [path]     - Change directory
+  pwd           - Print current directory
+  stat <path>   - Show size, attributes and dates of an entry
+  info/df       - Show volume size, free space and label
+  label [name]  - Show or change the volume label
+  create <path> - Create an empty file
+  write <path> <data> - Write data to file
+  get [-r] <image-path> <host-path> - Copy a file (or directory with -r) to the host
+  put [-r] <host-path> <image-path> - Copy a host file (or directory with -r) into the image
+  export-tar <path> <out.tar|-> - Archive a file or directory (ustar/pax, - for stdout)
+  hexdump <sector|cluster> <n> - Dump raw bytes (n in decimal or 0x hex)
+  fat <n>       - Show the FAT entry of a cluster and its meaning
+  chain <n>     - Show the cluster chain starting at a cluster
+  bpb           - Show every boot sector field
+  fsck          - Check chains, lost clusters, FAT copies and FSInfo (read-only)
+  exit/quit/q   - Exit CLI
+  help          - Show this help
+";
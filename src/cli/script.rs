@@ -0,0 +1,118 @@
+use crate::cli::{tokenize, Cli, Command};
+use crate::fs::FileSystemError;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+/// Resultat d'une commande du script
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptStep {
+    /// Numero de ligne (a partir de 1)
+    pub line: usize,
+    /// Texte de la commande, sans espaces autour
+    pub text: String,
+    /// Erreur de la commande, None si elle a reussi
+    pub error: Option<FileSystemError>,
+}
+
+/// Bilan d'un script
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScriptReport {
+    /// Commandes executees, dans l'ordre
+    pub steps: Vec<ScriptStep>,
+    /// Arret sur erreur (`set -e`) : les lignes suivantes n'ont pas ete lues
+    pub stopped: bool,
+    /// Au moins une commande reussie a modifie l'image
+    pub modified: bool,
+}
+
+impl ScriptReport {
+    /// Nombre de commandes en echec
+    pub fn failures(&self) -> usize {
+        self.steps.iter().filter(|step| step.error.is_some()).count()
+    }
+
+    /// Toutes les commandes ont reussi ?
+    pub fn is_success(&self) -> bool {
+        self.failures() == 0
+    }
+
+    /// Une ligne par commande (`ok` / `FAILED`) puis le total
+    pub fn summary(&self) -> String {
+        let mut out = String::new();
+        for step in &self.steps {
+            match &step.error {
+                None => {
+                    let _ = writeln!(out, "  ok      {:>4}: {}", step.line, step.text);
+                }
+                Some(e) => {
+                    let _ = writeln!(out, "  FAILED  {:>4}: {} ({})", step.line, step.text, e);
+                }
+            }
+        }
+        let _ = write!(out, "{} commands, {} ok, {} failed", self.steps.len(), self.steps.len() - self.failures(), self.failures());
+        if self.stopped {
+            out.push_str(", stopped on error");
+        }
+        out.push('\n');
+        out
+    }
+}
+
+impl Cli {
+    /// Execute un script, une commande par ligne
+    ///
+    /// Les lignes vides et les commentaires (`#`) sont ignores, les chemins avec
+    /// des espaces se mettent entre quotes. `set -e` arrete le script a la premiere
+    /// erreur, `set +e` continue (valeur initiale : `stop_on_error`) ; `exit` termine.
+    /// `on_step` recoit chaque resultat et la sortie brute de la commande.
+    pub fn run_script<F>(&mut self, script: &str, stop_on_error: bool, mut on_step: F) -> ScriptReport
+    where
+        F: FnMut(&ScriptStep, &[u8]),
+    {
+        let mut report = ScriptReport::default();
+        let mut stop_on_error = stop_on_error;
+
+        for (index, line) in script.lines().enumerate() {
+            let text = line.trim();
+            let (output, error) = match tokenize::split_args(text) {
+                Ok(args) if args.is_empty() => continue,
+                Ok(args) => match args.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+                    ["set", "-e"] => {
+                        stop_on_error = true;
+                        continue;
+                    }
+                    ["set", "+e"] => {
+                        stop_on_error = false;
+                        continue;
+                    }
+                    _ => match Command::parse(text) {
+                        Command::Exit => break,
+                        Command::Unknown(msg) => (Vec::new(), Some(FileSystemError::InvalidPath(msg))),
+                        command => {
+                            let modifies = command.modifies();
+                            match self.execute_raw(command) {
+                                Ok(output) => {
+                                    report.modified |= modifies;
+                                    (output, None)
+                                }
+                                Err(e) => (Vec::new(), Some(e)),
+                            }
+                        }
+                    },
+                },
+                Err(e) => (Vec::new(), Some(e)),
+            };
+
+            let step = ScriptStep { line: index + 1, text: String::from(text), error };
+            on_step(&step, &output);
+            let failed = step.error.is_some();
+            report.steps.push(step);
+            if failed && stop_on_error {
+                report.stopped = true;
+                break;
+            }
+        }
+        report
+    }
+}
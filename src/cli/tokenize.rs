@@ -0,0 +1,76 @@
+use crate::fs::FileSystemError;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Argument d'une ligne de commande
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    /// Valeur, quotes et `\` retires
+    pub value: String,
+    /// Espaces qui precedent l'argument dans la ligne, tels quels
+    pub separator: String,
+}
+
+/// Decoupe une ligne en arguments, comme un shell simplifie
+///
+/// - les espaces separent les arguments ;
+/// - `'...'` garde tout tel quel, `"..."` aussi sauf `\"` et `\\` ;
+/// - hors quotes, `\` protege le caractere suivant (`my\ file.txt`) ;
+/// - un `#` en debut d'argument commence un commentaire jusqu'a la fin de la ligne.
+pub fn tokenize(line: &str) -> Result<Vec<Token>, FileSystemError> {
+    let mut tokens = Vec::new();
+    let mut value = String::new();
+    let mut separator = String::new();
+    // un argument vide entre quotes ("") compte quand meme
+    let mut in_arg = false;
+    let mut quote: Option<char> = None;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') if matches!(chars.peek(), Some('"') | Some('\\')) => {
+                value.extend(chars.next());
+            }
+            (Some(_), c) => value.push(c),
+            (None, '\\') => match chars.next() {
+                Some(escaped) => {
+                    value.push(escaped);
+                    in_arg = true;
+                }
+                None => return Err(FileSystemError::InvalidPath("Trailing backslash".into())),
+            },
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_arg = true;
+            }
+            (None, '#') if !in_arg => break,
+            (None, c) if c.is_whitespace() => {
+                if in_arg {
+                    tokens.push(Token {
+                        value: core::mem::take(&mut value),
+                        separator: core::mem::take(&mut separator),
+                    });
+                    in_arg = false;
+                }
+                separator.push(c);
+            }
+            (None, c) => {
+                value.push(c);
+                in_arg = true;
+            }
+        }
+    }
+    if quote.is_some() {
+        return Err(FileSystemError::InvalidPath("Unterminated quote".into()));
+    }
+    if in_arg {
+        tokens.push(Token { value, separator });
+    }
+    Ok(tokens)
+}
+
+/// Les valeurs des arguments de `tokenize`
+pub fn split_args(line: &str) -> Result<Vec<String>, FileSystemError> {
+    Ok(tokenize(line)?.into_iter().map(|token| token.value).collect())
+}
@@ -0,0 +1,234 @@
+use crate::cli::json::{self, Json};
+use crate::cli::{Cli, Volume};
+use crate::fs::path::PathBuf;
+use crate::fs::{DirEntry, FileSystemError};
+use alloc::collections::BTreeSet;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+/// Totaux affiches en bas de `tree`
+#[derive(Debug, Default)]
+struct TreeTotals {
+    directories: usize,
+    files: usize,
+    bytes: u64,
+    clusters: usize,
+}
+
+/// Entree de `tree`, parcourue une fois puis affichee en texte ou en JSON
+#[derive(Debug)]
+struct TreeNode {
+    name: String,
+    short_name: String,
+    directory: bool,
+    size: u64,
+    clusters: usize,
+    /// dossier deja parcouru (entree dupliquee, image corrompue), pas repris
+    cycle: bool,
+    /// chaine de clusters illisible
+    error: Option<FileSystemError>,
+    children: Vec<TreeNode>,
+}
+
+/// `cluster` / `clusters`
+pub(super) fn clusters_text(count: usize) -> String {
+    let mut text = count.to_string();
+    text.push_str(if count == 1 { " cluster" } else { " clusters" });
+    text
+}
+
+impl Cli {
+    /// Commande `tree` : arborescence, tailles et clusters alloues, totaux en bas
+    ///
+    /// `short_names` ajoute l'alias 8.3 entre crochets quand il differe du nom long.
+    pub(crate) fn write_tree(&self, path: &str, short_names: bool, output: &mut String) -> Result<(), FileSystemError> {
+        let (root, totals) = self.tree(path)?;
+        let _ = writeln!(output, "{} ({})", root.name, clusters_text(root.clusters));
+        let mut prefix = String::new();
+        write_tree_level(&root.children, short_names, &mut prefix, output);
+        let _ = writeln!(
+            output,
+            "\n{} directories, {} files, {} bytes, {} allocated",
+            totals.directories, totals.files, totals.bytes, clusters_text(totals.clusters)
+        );
+        Ok(())
+    }
+
+    /// `tree` en JSON : `{"path", "root": {.., "children": [..]}, "totals": {..}}`
+    pub(crate) fn tree_json(&self, path: &str) -> Result<Json, FileSystemError> {
+        let (root, totals) = self.tree(path)?;
+        Ok(Json::Object(vec![
+            ("path", root.name.as_str().into()),
+            ("root", node_json(&root)),
+            (
+                "totals",
+                Json::Object(vec![
+                    ("directories", totals.directories.into()),
+                    ("files", totals.files.into()),
+                    ("bytes", totals.bytes.into()),
+                    ("clusters", totals.clusters.into()),
+                ]),
+            ),
+        ]))
+    }
+
+    /// arborescence sous `path` ; la racine porte le chemin complet comme nom
+    fn tree(&self, path: &str) -> Result<(TreeNode, TreeTotals), FileSystemError> {
+        let fs = self.volume.fs();
+        let meta = fs.metadata(path)?;
+        if !meta.is_directory() {
+            let mut msg = String::from(path);
+            msg.push_str(" is not a directory");
+            return Err(FileSystemError::DirectoryNotFound(msg));
+        }
+        let root_path = if path.starts_with('/') {
+            PathBuf::new(path)?
+        } else {
+            let mut root = PathBuf::new(&fs.pwd())?;
+            root.push(path)?;
+            root
+        };
+
+        let mut totals = TreeTotals::default();
+        let clusters = self.allocated_clusters(&root_path, meta.first_cluster).unwrap_or(0);
+        totals.clusters += clusters;
+        let mut visited = BTreeSet::from([meta.first_cluster]);
+        let children = self.tree_children(&root_path, &mut visited, &mut totals)?;
+        let root = TreeNode {
+            name: root_path.to_string(),
+            short_name: meta.short_name.clone(),
+            directory: true,
+            size: 0,
+            clusters,
+            cycle: false,
+            error: None,
+            children,
+        };
+        Ok((root, totals))
+    }
+
+    fn tree_children(
+        &self,
+        dir: &PathBuf,
+        visited: &mut BTreeSet<u32>,
+        totals: &mut TreeTotals,
+    ) -> Result<Vec<TreeNode>, FileSystemError> {
+        let entries: Vec<DirEntry> = self.volume.fs().list(&dir.to_string())?
+            .into_iter()
+            .filter(|entry| !entry.is_volume_label() && !matches!(entry.name().as_deref(), Ok(".") | Ok("..")))
+            .collect();
+
+        let mut nodes = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let name = entry.name()?;
+            let mut node = TreeNode {
+                short_name: entry.entry.short_name().unwrap_or_else(|_| "?".into()),
+                name,
+                directory: entry.is_directory(),
+                size: entry.file_size() as u64,
+                clusters: 0,
+                cycle: false,
+                error: None,
+                children: Vec::new(),
+            };
+
+            // un dossier deja parcouru (entree dupliquee, image corrompue) n'est pas repris
+            if node.directory && !visited.insert(entry.first_cluster()) {
+                node.cycle = true;
+                nodes.push(node);
+                continue;
+            }
+
+            let mut path = dir.clone();
+            path.push(&node.name)?;
+            match self.allocated_clusters(&path, entry.first_cluster()) {
+                Ok(clusters) => node.clusters = clusters,
+                Err(e) => {
+                    node.error = Some(e);
+                    nodes.push(node);
+                    continue;
+                }
+            }
+            totals.clusters += node.clusters;
+
+            if node.directory {
+                totals.directories += 1;
+                node.children = self.tree_children(&path, visited, totals)?;
+            } else {
+                totals.files += 1;
+                totals.bytes += node.size;
+            }
+            nodes.push(node);
+        }
+        Ok(nodes)
+    }
+
+    /// clusters alloues : longueur de la chaine en FAT, taille allouee en exFAT (chaine facultative)
+    fn allocated_clusters(&self, path: &PathBuf, first_cluster: u32) -> Result<usize, FileSystemError> {
+        match &self.volume {
+            Volume::Fat(fs) => {
+                let first_cluster = if path.as_path().is_root() { fs.root_dir_cluster() } else { first_cluster };
+                if first_cluster < 2 {
+                    return Ok(0);
+                }
+                Ok(fs.get_cluster_chain(first_cluster)?.len())
+            }
+            Volume::ExFat(fs) => {
+                let meta = crate::fs::FileSystem::metadata(fs, &path.to_string())?;
+                Ok(meta.allocated_size.div_ceil(fs.boot_sector().cluster_size() as u64) as usize)
+            }
+        }
+    }
+}
+
+fn write_tree_level(nodes: &[TreeNode], short_names: bool, prefix: &mut String, output: &mut String) {
+    for (i, node) in nodes.iter().enumerate() {
+        let last = i + 1 == nodes.len();
+        let marker = if node.directory { "/" } else { "" };
+        let _ = write!(output, "{}{}{}{}", prefix, if last { "└── " } else { "├── " }, node.name, marker);
+        if short_names && node.short_name != node.name {
+            let _ = write!(output, " [{}]", node.short_name);
+        }
+
+        if node.cycle {
+            let _ = writeln!(output, " (cycle)");
+            continue;
+        }
+        if let Some(e) = &node.error {
+            let _ = writeln!(output, " <error: {}>", e);
+            continue;
+        }
+        if !node.directory {
+            let _ = writeln!(output, " ({} bytes, {})", node.size, clusters_text(node.clusters));
+            continue;
+        }
+        let _ = writeln!(output, " ({})", clusters_text(node.clusters));
+
+        let len = prefix.len();
+        prefix.push_str(if last { "    " } else { "│   " });
+        write_tree_level(&node.children, short_names, prefix, output);
+        prefix.truncate(len);
+    }
+}
+
+fn node_json(node: &TreeNode) -> Json {
+    let mut fields = vec![
+        ("name", node.name.as_str().into()),
+        ("short_name", node.short_name.as_str().into()),
+        ("kind", if node.directory { "directory" } else { "file" }.into()),
+        ("size", node.size.into()),
+        ("clusters", node.clusters.into()),
+    ];
+    if node.cycle {
+        fields.push(("cycle", true.into()));
+    }
+    if let Some(e) = &node.error {
+        fields.push(("error", json::error(e)));
+    }
+    if node.directory && !node.cycle {
+        fields.push(("children", Json::Array(node.children.iter().map(node_json).collect())));
+    }
+    Json::Object(fields)
+}
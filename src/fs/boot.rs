@@ -1,7 +1,10 @@
 use crate::fs::FileSystemError;
+use crate::fs::fat_table::FatType;
 
-/// FAT32 Boot Sector, reproduire le boot sector
-
+/// FAT Boot Sector, reproduire le boot sector
+///
+/// La structure suit le layout FAT32 ; pour FAT12/16 les champs a partir de
+/// l'offset 36 contiennent le BPB etendu FAT12/16, lu via les accesseurs.
 #[repr(C, packed)]
 pub struct BootSector { //structure qui represente les donnees au debut du fat
     /// Jump instruction (3 bytes)
@@ -66,13 +69,14 @@ pub struct BootSector { //structure qui represente les donnees au debut du fat
 
 impl BootSector { //lire les 512 premiers octect 
     
-    /// 
-    /// # Documentation d'une fonction Unsafe
+    /// Parse un boot sector FAT12, FAT16 ou FAT32
+    ///
+    /// # Safety
     /// 
     /// c'est unsafe car on transmet des octets dans le boot sector et on est pas sure a 100 que c'est exactement les données du boot sector
-
+    ///
     /// c'est safe si en input c'est EXACTEMENT 512 o et les données sont bien celle du boot sector
-    /// The caller must ensure the data is valid FAT32 boot sector data.
+    /// The caller must ensure the data is valid FAT boot sector data.
     pub unsafe fn from_bytes(data: &[u8]) -> Result<Self, FileSystemError> {
         if data.len() < 512 { // verifier qu'on a bien au moins 512o
             return Err(FileSystemError::InvalidBootSector(
@@ -87,18 +91,47 @@ impl BootSector { //lire les 512 premiers octect
         // core::ptr::read lire une valeur de type pointé en rust
         let bs = core::ptr::read(data.as_ptr() as *const BootSector); 
         
-        // on verifie que la signature c'est FAT 32
-        if bs.fs_type[0] != b'F' || bs.fs_type[1] != b'A' || bs.fs_type[2] != b'T' || bs.fs_type[3] != b'3' {
-            return Err(FileSystemError::InvalidBootSector(
-                "Not a FAT32 filesystem".into()
-            ));
-        }
         // on verifie que la fin du boot sector c'est bien AA 55 c'est le magic number de la fin du boot sector
         if bs.boot_signature_end != 0xAA55 {
             return Err(FileSystemError::InvalidBootSector(
                 "Invalid boot sector signature".into()
             ));
         }
+
+        // le type de FAT se deduit du nombre de clusters, pas de fs_type :
+        // on verifie donc que la geometrie permet ce calcul
+        let bytes_per_sector = bs.bytes_per_sector;
+        if !matches!(bytes_per_sector, 512 | 1024 | 2048 | 4096) {
+            return Err(FileSystemError::InvalidBootSector(
+                "Invalid bytes per sector".into()
+            ));
+        }
+        if bs.sectors_per_cluster == 0 || !bs.sectors_per_cluster.is_power_of_two() {
+            return Err(FileSystemError::InvalidBootSector(
+                "Invalid sectors per cluster".into()
+            ));
+        }
+        if bs.num_fats == 0 || bs.reserved_sector_count == 0 {
+            return Err(FileSystemError::InvalidBootSector(
+                "Invalid reserved sector or FAT count".into()
+            ));
+        }
+        if bs.sectors_per_fat() == 0 {
+            return Err(FileSystemError::InvalidBootSector(
+                "Sectors per FAT must not be 0".into()
+            ));
+        }
+        if bs.total_sectors() <= bs.data_start_sector() {
+            return Err(FileSystemError::InvalidBootSector(
+                "No data region".into()
+            ));
+        }
+        // en FAT32 la racine est un cluster normal et il n'y a pas de zone fixe
+        if bs.fat_type() == FatType::Fat32 && (bs.root_entry_count != 0 || bs.root_cluster < 2) {
+            return Err(FileSystemError::InvalidBootSector(
+                "Invalid FAT32 root directory".into()
+            ));
+        }
         
         Ok(bs) // tout est good on renvoi notre boot sector
     }
@@ -122,24 +155,125 @@ impl BootSector { //lire les 512 premiers octect
     pub fn fat_start_sector(&self) -> u32 {
         self.reserved_sector_count as u32
     }
+
+    /// Nombre total de secteurs du volume (champ 16 bits s'il est non nul)
+    pub fn total_sectors(&self) -> u32 {
+        if self.total_sectors_16 != 0 {
+            self.total_sectors_16 as u32
+        } else {
+            self.total_sectors_32
+        }
+    }
+
+    /// Nombre d'entrees de la racine fixe FAT12/16 (0 en FAT32)
+    pub fn root_entry_count(&self) -> u32 {
+        self.root_entry_count as u32
+    }
+
+    /// Secteur de debut de la racine fixe FAT12/16 (juste apres les FATs)
+    pub fn root_dir_start_sector(&self) -> u32 {
+        self.fat_start_sector()
+            .saturating_add(self.sectors_per_fat().saturating_mul(self.num_fats as u32))
+    }
+
+    /// Nombre de secteurs occupes par la racine fixe FAT12/16 (0 en FAT32)
+    pub fn root_dir_sectors(&self) -> u32 {
+        let bps = self.bytes_per_sector();
+        (self.root_entry_count() * 32).div_ceil(bps)
+    }
     
  
     pub fn data_start_sector(&self) -> u32 {
-        self.fat_start_sector() + (self.sectors_per_fat_32 * self.num_fats as u32)
+        self.root_dir_start_sector().saturating_add(self.root_dir_sectors())
+    }
+
+    /// Nombre de clusters de la zone de donnees, c'est lui qui determine le type de FAT
+    pub fn cluster_count(&self) -> u32 {
+        let data_sectors = self.total_sectors().saturating_sub(self.data_start_sector());
+        data_sectors / self.sectors_per_cluster()
+    }
+
+    /// Type de FAT selon le nombre de clusters, comme l'exige la spec Microsoft
+    pub fn fat_type(&self) -> FatType {
+        FatType::from_cluster_count(self.cluster_count())
     }
     
-    
+    /// Cluster racine (FAT32 uniquement, la racine FAT12/16 n'est pas un cluster)
     pub fn root_cluster(&self) -> u32 {
         self.root_cluster
     }
     
-    
+    /// Secteurs par FAT (champ 16 bits s'il est non nul, sinon champ FAT32)
     pub fn sectors_per_fat(&self) -> u32 {
-        self.sectors_per_fat_32
+        if self.sectors_per_fat_16 != 0 {
+            self.sectors_per_fat_16 as u32
+        } else {
+            self.sectors_per_fat_32
+        }
     }
     
     
     pub fn num_fats(&self) -> u8 {
         self.num_fats
     }
+
+    /// Secteur FSInfo (FAT32 uniquement, None si absent)
+    pub fn fs_info_sector(&self) -> Option<u32> {
+        match (self.fat_type(), self.fs_info) {
+            (FatType::Fat32, sector) if sector != 0 && sector != 0xFFFF => Some(sector as u32),
+            _ => None,
+        }
+    }
+
+    /// Les 512 octets du boot sector, tels que lus
+    pub fn as_bytes(&self) -> &[u8; 512] {
+        // Safety: la structure est packed (alignement 1, pas de padding) et fait
+        // exactement 512 octets, tous initialises par from_bytes
+        unsafe { &*(self as *const BootSector as *const [u8; 512]) }
+    }
+
+    /// offset du BPB etendu : 36 en FAT12/16, 64 en FAT32
+    fn extended_bpb_offset(&self) -> usize {
+        match self.fat_type() {
+            FatType::Fat32 | FatType::ExFat => 64,
+            FatType::Fat12 | FatType::Fat16 => 36,
+        }
+    }
+
+    /// Numero de serie du volume, si le BPB etendu est present (signature 0x28 ou 0x29)
+    pub fn volume_id(&self) -> Option<u32> {
+        let bytes = self.as_bytes();
+        let offset = self.extended_bpb_offset();
+        if !matches!(bytes[offset + 2], 0x28 | 0x29) {
+            return None;
+        }
+        let mut id = [0u8; 4];
+        id.copy_from_slice(&bytes[offset + 3..offset + 7]);
+        Some(u32::from_le_bytes(id))
+    }
+
+    /// Offset du label dans le boot sector, si la signature vaut 0x29
+    pub fn volume_label_offset(&self) -> Option<usize> {
+        let offset = self.extended_bpb_offset();
+        if self.as_bytes()[offset + 2] != 0x29 {
+            return None;
+        }
+        Some(offset + 7)
+    }
+
+    /// Label du BPB (11 octets completes par des espaces), si la signature vaut 0x29
+    pub fn bpb_volume_label(&self) -> Option<[u8; 11]> {
+        let offset = self.volume_label_offset()?;
+        let mut label = [0u8; 11];
+        label.copy_from_slice(&self.as_bytes()[offset..offset + 11]);
+        Some(label)
+    }
+
+    /// Secteur de la copie de secours du boot sector (FAT32 uniquement)
+    pub fn backup_boot_sector(&self) -> Option<u32> {
+        match (self.fat_type(), self.backup_boot_sector) {
+            (FatType::Fat32, sector) if sector != 0 && sector != 0xFFFF => Some(sector as u32),
+            _ => None,
+        }
+    }
 }
@@ -42,33 +42,42 @@ impl ClusterChain {
             clusters.push(current);
             
             // Get next cluster from FAT
-            let next = fat_table.get_entry(current)?;
-            
-            // Check for end of chain markers
-            if next >= 0x0FFFFFF8 {
-                // End of chain
-                break;
+            match Self::next_cluster(fat_table, current)? {
+                Some(next) => current = next,
+                None => break,
             }
-            
-            if next == 0x0FFFFFF7 {
-                return Err(FileSystemError::ClusterChainError(
-                    "Bad cluster in chain".into()
-                ));
-            }
-            
-            if next < 2 {
-                return Err(FileSystemError::ClusterChainError(
-                    "Invalid next cluster number".into()
-                ));
-            }
-            
-            current = next;
             iterations += 1;
         }
         
         Ok(Self { clusters })
     }
-    
+
+    /// Cluster suivant dans la FAT, None en fin de chaine
+    ///
+    /// Permet de suivre une chaine sans la construire en entier.
+    pub fn next_cluster(fat_table: &FatTable, current: u32) -> Result<Option<u32>, FileSystemError> {
+        let next = fat_table.get_entry(current)?;
+
+        // Check for end of chain markers
+        if fat_table.fat_type().is_end_of_chain(next) {
+            return Ok(None);
+        }
+
+        if fat_table.fat_type().is_bad_cluster(next) {
+            return Err(FileSystemError::ClusterChainError(
+                "Bad cluster in chain".into()
+            ));
+        }
+
+        if next < 2 {
+            return Err(FileSystemError::ClusterChainError(
+                "Invalid next cluster number".into()
+            ));
+        }
+
+        Ok(Some(next))
+    }
+
     /// Get all cluster numbers in the chain
     pub fn clusters(&self) -> &[u32] {
         &self.clusters
@@ -1,144 +1,274 @@
 use crate::fs::FileSystemError;
-use crate::fs::entry::{DirectoryEntry, DirEntry, LongFileNameEntry};
-use crate::fs::cluster::ClusterChain;
+use crate::fs::glob;
+use crate::fs::entry::{lfn_checksum, DirectoryEntry, DirEntry, LongFileNameEntry};
 use alloc::vec::Vec;
 use alloc::string::String;
 
 /// Directory management
 pub struct Directory;
 
+/// Entree de repertoire avec sa position dans les donnees du dossier
+pub struct DirSlot {
+    /// Short name entry
+    pub entry: DirectoryEntry,
+    /// Nom long reconstruit depuis les entrees LFN, si elles sont valides
+    pub long_name: Option<String>,
+    /// Index (en entrees de 32 octets) de la premiere entree LFN, ou de l'entree courte
+    pub first_index: usize,
+    /// Index de l'entree courte
+    pub index: usize,
+}
+
+impl DirSlot {
+    /// Le nom (long ou court) correspond-il, sans tenir compte de la casse ?
+    pub fn matches(&self, name: &str) -> bool {
+        if let Some(ref long_name) = self.long_name {
+            if long_name.eq_ignore_ascii_case(name) {
+                return true;
+            }
+        }
+        match self.entry.short_name() {
+            Ok(short_name) => short_name.eq_ignore_ascii_case(name),
+            Err(_) => false,
+        }
+    }
+
+    /// Le nom (long ou court) correspond-il au motif glob, sans tenir compte de la casse ?
+    pub fn matches_pattern(&self, pattern: &str) -> bool {
+        if let Some(ref long_name) = self.long_name {
+            if glob::matches(pattern, long_name) {
+                return true;
+            }
+        }
+        match self.entry.short_name() {
+            Ok(short_name) => glob::matches(pattern, &short_name),
+            Err(_) => false,
+        }
+    }
+}
+
+/// Suite d'entrees LFN en cours de lecture (stockees de la derniere partie a la premiere)
+struct LfnRun {
+    /// Index de la premiere entree LFN
+    first_index: usize,
+    /// Checksum du nom court attendu
+    checksum: u8,
+    /// Numero de sequence attendu pour la prochaine entree
+    next_sequence: u8,
+    /// Parties du nom, dans l'ordre du disque
+    parts: Vec<Vec<u16>>,
+}
+
+impl LfnRun {
+    /// Reconstruit le nom si la suite est complete et correspond au nom court
+    fn finish(self, entry: &DirectoryEntry) -> Option<String> {
+        if self.next_sequence != 0 || self.checksum != lfn_checksum(&entry.name) {
+            return None;
+        }
+        let mut chars = Vec::new();
+        for part in self.parts.iter().rev() {
+            chars.extend_from_slice(part);
+        }
+        if let Some(end) = chars.iter().position(|&ch| ch == 0x0000) {
+            chars.truncate(end);
+        }
+        Some(char::decode_utf16(chars).map(|r| r.unwrap_or(char::REPLACEMENT_CHARACTER)).collect())
+    }
+}
+
+/// Lecteur d'entrees de 32 octets, une a la fois
+///
+/// Garde la suite LFN en cours entre deux appels : les donnees d'un dossier
+/// peuvent etre fournies cluster par cluster, meme si un nom long est a cheval.
+#[derive(Default)]
+pub struct DirScanner {
+    /// Suite LFN en cours
+    lfn: Option<LfnRun>,
+    /// Index de la prochaine entree
+    index: usize,
+    /// Entree 0x00 rencontree : fin du dossier
+    done: bool,
+}
+
+impl DirScanner {
+    /// Nouveau lecteur, au debut du dossier
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fin du dossier atteinte ?
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Lit l'entree suivante, renvoie une entree courte complete (avec son nom long)
+    ///
+    /// Les entrees libres, supprimees et les LFN orphelines (checksum ou sequence
+    /// invalide) sont ignorees ; les labels de volume sont renvoyes.
+    pub fn push(&mut self, chunk: &[u8]) -> Option<DirSlot> {
+        if self.done || chunk.len() < 32 {
+            return None;
+        }
+        let index = self.index;
+        self.index += 1;
+
+        if chunk[0] == 0x00 {
+            // End of directory
+            self.done = true;
+            return None;
+        }
+
+        if chunk[0] == 0xE5 {
+            // Deleted entry, skip
+            self.lfn = None;
+            return None;
+        }
+
+        // Check if this is a Long File Name entry
+        if chunk[11] == 0x0F {
+            // Safety: chunk fait 32 octets et LongFileNameEntry est packed (alignement 1)
+            let entry = unsafe { core::ptr::read(chunk.as_ptr() as *const LongFileNameEntry) };
+            if !entry.is_valid() {
+                self.lfn = None;
+                return None;
+            }
+            let sequence = entry.sequence_number();
+            if entry.is_last() {
+                // premiere entree sur le disque : elle porte le plus grand numero
+                self.lfn = Some(LfnRun {
+                    first_index: index,
+                    checksum: entry.checksum,
+                    next_sequence: sequence,
+                    parts: Vec::new(),
+                });
+            }
+            self.lfn = match self.lfn.take() {
+                Some(mut run) if run.next_sequence == sequence && sequence > 0 && run.checksum == entry.checksum => {
+                    run.parts.push(entry.name_chars());
+                    run.next_sequence -= 1;
+                    Some(run)
+                }
+                _ => None,
+            };
+            return None;
+        }
+
+        // Regular directory entry
+        // Safety: chunk fait 32 octets
+        match unsafe { DirectoryEntry::from_bytes(chunk) } {
+            Ok(entry) => {
+                let (first_index, long_name) = match self.lfn.take() {
+                    Some(run) => {
+                        let first_index = run.first_index;
+                        match run.finish(&entry) {
+                            Some(name) => (first_index, Some(name)),
+                            None => (index, None),
+                        }
+                    }
+                    None => (index, None),
+                };
+                Some(DirSlot { entry, long_name, first_index, index })
+            }
+            Err(_) => {
+                self.lfn = None;
+                None
+            }
+        }
+    }
+}
+
 impl Directory {
-    /// Read all entries from a directory cluster chain
-    /// 
+    /// Parcourt les entrees d'un dossier en gardant leur position
+    ///
+    /// Les entrees libres, supprimees et les LFN orphelines (checksum ou sequence
+    /// invalide) sont ignorees ; les labels de volume sont renvoyes.
+    pub fn scan(data: &[u8]) -> Vec<DirSlot> {
+        let mut scanner = DirScanner::new();
+        let mut slots = Vec::new();
+        for chunk in data.chunks_exact(32) {
+            if let Some(slot) = scanner.push(chunk) {
+                slots.push(slot);
+            }
+            if scanner.is_done() {
+                break;
+            }
+        }
+        slots
+    }
+
+    /// Read all entries from the raw data of a directory (cluster chain or FAT12/16 root region)
+    ///
     /// # Safety
-    /// 
-    /// The cluster_chain must be valid and the data must contain valid directory entries.
+    ///
+    /// The data must contain valid directory entries.
     pub unsafe fn read_entries(
-        _cluster_chain: &ClusterChain,
         data: &[u8],
     ) -> Result<Vec<DirEntry>, FileSystemError> {
         let mut entries = Vec::new();
-        let mut lfn_parts: Vec<(u8, Vec<u16>)> = Vec::new();
-        
-        // Parse entries (32 bytes each)
-        for chunk in data.chunks_exact(32) {
-            if chunk[0] == 0x00 {
-                // End of directory
-                break;
-            }
-            
-            if chunk[0] == 0xE5 {
-                // Deleted entry, skip
-                lfn_parts.clear();
+        for slot in Self::scan(data) {
+            // Skip volume labels
+            if slot.entry.is_volume_label() {
                 continue;
             }
-            
-            // Check if this is a Long File Name entry
-            if chunk[11] == 0x0F {
-                // Safety: This is a valid LFN entry structure
-                let lfn = core::ptr::read(chunk.as_ptr() as *const LongFileNameEntry);
-                if lfn.is_valid() {
-                    let seq = lfn.sequence_number();
-                    let chars = lfn.name_chars();
-                    lfn_parts.push((seq, chars));
-                    
-                    if lfn.is_last() {
-                        // Sort by sequence number (descending)
-                        lfn_parts.sort_by(|a, b| b.0.cmp(&a.0));
-                        // Reconstruct long name
-                        let mut long_name = String::new();
-                        for (_, chars) in &lfn_parts {
-                            for &ch in chars {
-                                if ch == 0 || ch == 0xFFFF {
-                                    break;
-                                }
-                                // Convert UTF-16 to char (simplified)
-                                if ch < 0x80 {
-                                    long_name.push(ch as u8 as char);
-                                }
-                            }
-                        }
-                        
-                        // Next entry should be the short name entry
-                        continue;
-                    }
-                }
-                continue;
-            }
-            
-            // Regular directory entry
-            match DirectoryEntry::from_bytes(chunk) {
-                Ok(entry) => {
-                    // Skip volume labels
-                    if entry.is_volume_label() {
-                        lfn_parts.clear();
-                        continue;
-                    }
-                    
-                    let mut dir_entry = DirEntry::new(entry);
-                    
-                    // If we have LFN parts, use them
-                    if !lfn_parts.is_empty() {
-                        let mut long_name = String::new();
-                        for (_, chars) in &lfn_parts {
-                            for &ch in chars {
-                                if ch == 0 || ch == 0xFFFF {
-                                    break;
-                                }
-                                if ch < 0x80 {
-                                    long_name.push(ch as u8 as char);
-                                }
-                            }
-                        }
-                        if !long_name.is_empty() {
-                            dir_entry = dir_entry.with_long_name(long_name);
-                        }
-                        lfn_parts.clear();
-                    }
-                    
-                    entries.push(dir_entry);
-                }
-                Err(_) => {
-                    lfn_parts.clear();
-                    continue;
-                }
+            let mut dir_entry = DirEntry::new(slot.entry);
+            if let Some(long_name) = slot.long_name {
+                dir_entry = dir_entry.with_long_name(long_name);
             }
+            entries.push(dir_entry);
         }
-        
         Ok(entries)
     }
-    
-    /// Find an entry by name in directory data
+
+    /// Find an entry by long or short name in directory data
     pub fn find_entry(
         data: &[u8],
         name: &str,
     ) -> Result<Option<DirectoryEntry>, FileSystemError> {
-        // Parse entries (32 bytes each)
-        for chunk in data.chunks_exact(32) {
+        Ok(Self::find_slot(data, name).map(|slot| slot.entry))
+    }
+
+    /// Find an entry by long or short name, with its position in the directory
+    pub fn find_slot(data: &[u8], name: &str) -> Option<DirSlot> {
+        Self::scan(data)
+            .into_iter()
+            .find(|slot| !slot.entry.is_volume_label() && slot.matches(name))
+    }
+
+    /// Toutes les entrees dont le nom correspond au motif glob (`*`, `?`, `[...]`)
+    pub fn find_matching(data: &[u8], pattern: &str) -> Vec<DirSlot> {
+        Self::scan(data)
+            .into_iter()
+            .filter(|slot| !slot.entry.is_volume_label() && slot.matches_pattern(pattern))
+            .collect()
+    }
+
+    /// Cherche `count` entrees libres consecutives, renvoie l'index de la premiere
+    ///
+    /// Une entree 0x00 marque la fin du dossier : tout ce qui suit est libre.
+    pub fn find_free_run(data: &[u8], count: usize) -> Option<usize> {
+        let mut run_start = 0;
+        let mut run_len = 0;
+        for (index, chunk) in data.chunks_exact(32).enumerate() {
             if chunk[0] == 0x00 {
-                break;
+                let available = data.len() / 32 - index;
+                return if run_len + available >= count {
+                    Some(if run_len > 0 { run_start } else { index })
+                } else {
+                    None
+                };
             }
             if chunk[0] == 0xE5 {
-                continue;
-            }
-            
-            unsafe {
-                match DirectoryEntry::from_bytes(chunk) {
-                    Ok(entry) => {
-                        match entry.short_name() {
-                            Ok(entry_name) => {
-                                if entry_name == name || entry_name.eq_ignore_ascii_case(name) {
-                                    return Ok(Some(entry));
-                                }
-                            }
-                            Err(_) => continue,
-                        }
-                    }
-                    Err(_) => continue,
+                if run_len == 0 {
+                    run_start = index;
+                }
+                run_len += 1;
+                if run_len == count {
+                    return Some(run_start);
                 }
+            } else {
+                run_len = 0;
             }
         }
-        
-        Ok(None)
+        None
     }
 }
@@ -1,9 +1,11 @@
 use crate::fs::FileSystemError;
+use crate::fs::time::DateTime;
 use alloc::string::String;
 use alloc::vec::Vec;
 
 /// Directory entry (short name format - 32 bytes)
 #[repr(C, packed)]
+#[derive(Clone, Copy)]
 pub struct DirectoryEntry {
     /// Short name (8.3 format)
     pub name: [u8; 11],
@@ -31,6 +33,111 @@ pub struct DirectoryEntry {
     pub file_size: u32,
 }
 
+/// Attribute flags of a directory entry (byte 11)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
+pub struct Attributes(u8);
+
+impl Attributes {
+    /// Writes are refused
+    pub const READ_ONLY: Attributes = Attributes(0x01);
+    /// Not shown in normal listings
+    pub const HIDDEN: Attributes = Attributes(0x02);
+    /// Belongs to the operating system
+    pub const SYSTEM: Attributes = Attributes(0x04);
+    /// Volume label (only in the root directory)
+    pub const VOLUME_ID: Attributes = Attributes(0x08);
+    /// Subdirectory
+    pub const DIRECTORY: Attributes = Attributes(0x10);
+    /// Modified since the last backup
+    pub const ARCHIVE: Attributes = Attributes(0x20);
+
+    /// No flag set
+    pub const fn empty() -> Self {
+        Attributes(0)
+    }
+
+    /// Build from the raw byte (bits 6-7 are reserved and dropped)
+    pub const fn from_bits(bits: u8) -> Self {
+        Attributes(bits & 0x3F)
+    }
+
+    /// Raw byte as stored on disk
+    pub const fn bits(&self) -> u8 {
+        self.0
+    }
+
+    /// True if every flag of `other` is set
+    pub const fn contains(&self, other: Attributes) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Set the flags of `other`
+    pub fn insert(&mut self, other: Attributes) {
+        self.0 |= other.0;
+    }
+
+    /// Clear the flags of `other`
+    pub fn remove(&mut self, other: Attributes) {
+        self.0 &= !other.0;
+    }
+
+    /// Read-only flag
+    pub fn is_read_only(&self) -> bool {
+        self.contains(Self::READ_ONLY)
+    }
+
+    /// Hidden flag
+    pub fn is_hidden(&self) -> bool {
+        self.contains(Self::HIDDEN)
+    }
+
+    /// System flag
+    pub fn is_system(&self) -> bool {
+        self.contains(Self::SYSTEM)
+    }
+
+    /// Volume ID flag
+    pub fn is_volume_id(&self) -> bool {
+        self.contains(Self::VOLUME_ID)
+    }
+
+    /// Directory flag
+    pub fn is_directory(&self) -> bool {
+        self.contains(Self::DIRECTORY)
+    }
+
+    /// Archive flag
+    pub fn is_archive(&self) -> bool {
+        self.contains(Self::ARCHIVE)
+    }
+}
+
+impl core::ops::BitOr for Attributes {
+    type Output = Attributes;
+
+    fn bitor(self, rhs: Attributes) -> Attributes {
+        Attributes(self.0 | rhs.0)
+    }
+}
+
+impl core::fmt::Display for Attributes {
+    /// Flags as in `attrib` : `RHSVDA`, `-` when not set
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let flags = [
+            (Self::READ_ONLY, 'R'),
+            (Self::HIDDEN, 'H'),
+            (Self::SYSTEM, 'S'),
+            (Self::VOLUME_ID, 'V'),
+            (Self::DIRECTORY, 'D'),
+            (Self::ARCHIVE, 'A'),
+        ];
+        for (flag, letter) in flags {
+            write!(f, "{}", if self.contains(flag) { letter } else { '-' })?;
+        }
+        Ok(())
+    }
+}
+
 impl DirectoryEntry {
     /// Parse directory entry from raw bytes
     /// 
@@ -74,6 +181,11 @@ impl DirectoryEntry {
         (self.attributes & 0x08) != 0
     }
     
+    /// Attribute flags
+    pub fn attributes(&self) -> Attributes {
+        Attributes::from_bits(self.attributes)
+    }
+
     /// Get first cluster number
     pub fn first_cluster(&self) -> u32 {
         ((self.first_cluster_high as u32) << 16) | (self.first_cluster_low as u32)
@@ -83,7 +195,127 @@ impl DirectoryEntry {
     pub fn file_size(&self) -> u32 {
         self.file_size
     }
+
+    /// Creation date and time (10 ms resolution), None if not recorded
+    pub fn created(&self) -> Option<DateTime> {
+        DateTime::from_fat(self.creation_date, self.creation_time, self.creation_time_tenths)
+    }
+
+    /// Last write date and time (2 s resolution)
+    pub fn modified(&self) -> Option<DateTime> {
+        DateTime::from_fat(self.last_write_date, self.last_write_time, 0)
+    }
+
+    /// Last access date (FAT only records the day)
+    pub fn accessed(&self) -> Option<DateTime> {
+        DateTime::from_fat_date(self.last_access_date)
+    }
     
+    /// Serialize the entry back to its 32-byte on-disk form
+    pub fn to_bytes(&self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        bytes[0..11].copy_from_slice(&self.name);
+        bytes[11] = self.attributes;
+        bytes[12] = self.nt_reserved;
+        bytes[13] = self.creation_time_tenths;
+        bytes[14..16].copy_from_slice(&{ self.creation_time }.to_le_bytes());
+        bytes[16..18].copy_from_slice(&{ self.creation_date }.to_le_bytes());
+        bytes[18..20].copy_from_slice(&{ self.last_access_date }.to_le_bytes());
+        bytes[20..22].copy_from_slice(&{ self.first_cluster_high }.to_le_bytes());
+        bytes[22..24].copy_from_slice(&{ self.last_write_time }.to_le_bytes());
+        bytes[24..26].copy_from_slice(&{ self.last_write_date }.to_le_bytes());
+        bytes[26..28].copy_from_slice(&{ self.first_cluster_low }.to_le_bytes());
+        bytes[28..32].copy_from_slice(&{ self.file_size }.to_le_bytes());
+        bytes
+    }
+
+    /// Set first cluster number (high and low words)
+    pub fn set_first_cluster(&mut self, cluster: u32) {
+        self.first_cluster_high = (cluster >> 16) as u16;
+        self.first_cluster_low = cluster as u16;
+    }
+
+    /// Encode a name that is already a valid upper-case 8.3 name, None otherwise
+    ///
+    /// A name that needs lower case, more than 8.3 characters or other characters
+    /// must be stored with long file name entries and a generated alias.
+    pub fn encode_short_name(name: &str) -> Option<[u8; 11]> {
+        let (base, ext) = match name.rfind('.') {
+            Some(dot) => (&name[..dot], &name[dot + 1..]),
+            None => (name, ""),
+        };
+        if base.is_empty() || base.len() > 8 || ext.len() > 3 || (name.ends_with('.') && ext.is_empty()) {
+            return None;
+        }
+        if !base.bytes().chain(ext.bytes()).all(is_short_name_char) {
+            return None;
+        }
+
+        let mut short = [b' '; 11];
+        short[..base.len()].copy_from_slice(base.as_bytes());
+        short[8..8 + ext.len()].copy_from_slice(ext.as_bytes());
+        Some(short)
+    }
+
+    /// Basis name for the alias of a long name: 8.3, upper case, invalid characters as `_`
+    pub fn short_name_basis(name: &str) -> [u8; 11] {
+        let trimmed = name.trim_start_matches('.');
+        let (base, ext) = match trimmed.rfind('.') {
+            Some(dot) => (&trimmed[..dot], &trimmed[dot + 1..]),
+            None => (trimmed, ""),
+        };
+        let convert = |c: char| -> Option<u8> {
+            if c == ' ' || c == '.' {
+                None
+            } else if c.is_ascii() && is_short_name_char(c.to_ascii_uppercase() as u8) {
+                Some(c.to_ascii_uppercase() as u8)
+            } else {
+                Some(b'_')
+            }
+        };
+
+        let mut short = [b' '; 11];
+        for (slot, b) in short[..8].iter_mut().zip(base.chars().filter_map(convert)) {
+            *slot = b;
+        }
+        for (slot, b) in short[8..].iter_mut().zip(ext.chars().filter_map(convert)) {
+            *slot = b;
+        }
+        if short[0] == b' ' {
+            short[0] = b'_';
+        }
+        short
+    }
+
+    /// Apply a numeric tail (`~N`) to a basis name, as Windows does for aliases
+    ///
+    /// `None` si `~N` ne tient pas dans les 8 caracteres du nom (N >= 10 000 000).
+    pub fn with_numeric_tail(basis: &[u8; 11], n: u32) -> Option<[u8; 11]> {
+        let mut digits = [0u8; 10];
+        let mut len = 0;
+        let mut value = n;
+        loop {
+            digits[len] = b'0' + (value % 10) as u8;
+            len += 1;
+            value /= 10;
+            if value == 0 {
+                break;
+            }
+        }
+
+        let base_len = basis[..8].iter().position(|&b| b == b' ').unwrap_or(8);
+        let keep = base_len.min(8usize.checked_sub(len + 1)?);
+        let mut short = *basis;
+        short[keep] = b'~';
+        for i in 0..len {
+            short[keep + 1 + i] = digits[len - 1 - i];
+        }
+        for slot in &mut short[keep + 1 + len..8] {
+            *slot = b' ';
+        }
+        Some(short)
+    }
+
     /// Get short name as string (8.3 format)
     pub fn short_name(&self) -> Result<String, FileSystemError> {
         let mut name_bytes = Vec::new();
@@ -151,7 +383,53 @@ pub struct LongFileNameEntry {
     pub name3: [u16; 2],
 }
 
+/// Checksum of a short name, stored in each of its long file name entries
+pub fn lfn_checksum(short_name: &[u8; 11]) -> u8 {
+    short_name.iter().fold(0u8, |sum, &b| sum.rotate_right(1).wrapping_add(b))
+}
+
+/// Characters allowed in a short name (upper case only)
+pub(crate) fn is_short_name_char(b: u8) -> bool {
+    b.is_ascii_uppercase()
+        || b.is_ascii_digit()
+        || b >= 0x80
+        || b"$%'-_@~`!(){}^#&".contains(&b)
+}
+
 impl LongFileNameEntry {
+    /// Build the LFN entries for a long name, in on-disk order (last part first)
+    pub fn entries_for(long_name: &str, checksum: u8) -> Result<Vec<[u8; 32]>, FileSystemError> {
+        let chars: Vec<u16> = long_name.encode_utf16().collect();
+        if chars.is_empty() || chars.len() > 255 {
+            return Err(FileSystemError::InvalidPath("Long file name must be 1 to 255 characters".into()));
+        }
+
+        let count = chars.len().div_ceil(13);
+        let mut entries = Vec::with_capacity(count);
+        for part in (0..count).rev() {
+            // le nom se termine par 0x0000 puis le reste est rempli de 0xFFFF
+            let mut slots = [0xFFFFu16; 13];
+            let start = part * 13;
+            let end = (start + 13).min(chars.len());
+            slots[..end - start].copy_from_slice(&chars[start..end]);
+            if end - start < 13 {
+                slots[end - start] = 0x0000;
+            }
+
+            let mut bytes = [0u8; 32];
+            bytes[0] = (part + 1) as u8 | if part == count - 1 { 0x40 } else { 0 };
+            bytes[11] = 0x0F;
+            bytes[13] = checksum;
+            let offsets = [1, 3, 5, 7, 9, 14, 16, 18, 20, 22, 24, 28, 30];
+            for (slot, &offset) in slots.iter().zip(offsets.iter()) {
+                bytes[offset..offset + 2].copy_from_slice(&slot.to_le_bytes());
+            }
+            entries.push(bytes);
+        }
+        Ok(entries)
+    }
+
+
     /// Check if this is a valid LFN entry
     pub fn is_valid(&self) -> bool {
         self.attributes == 0x0F && self.type_ == 0x00 && self.first_cluster == 0x0000
@@ -223,6 +501,36 @@ impl DirEntry {
         self.entry.is_file()
     }
     
+    /// Attribute flags
+    pub fn attributes(&self) -> Attributes {
+        self.entry.attributes()
+    }
+
+    /// Check if entry is read-only
+    pub fn is_read_only(&self) -> bool {
+        self.attributes().is_read_only()
+    }
+
+    /// Check if entry is hidden
+    pub fn is_hidden(&self) -> bool {
+        self.attributes().is_hidden()
+    }
+
+    /// Check if entry belongs to the system
+    pub fn is_system(&self) -> bool {
+        self.attributes().is_system()
+    }
+
+    /// Check if entry is a volume label
+    pub fn is_volume_label(&self) -> bool {
+        self.entry.is_volume_label()
+    }
+
+    /// Check if the archive flag is set
+    pub fn is_archive(&self) -> bool {
+        self.attributes().is_archive()
+    }
+
     /// Get first cluster
     pub fn first_cluster(&self) -> u32 {
         self.entry.first_cluster()
@@ -232,4 +540,19 @@ impl DirEntry {
     pub fn file_size(&self) -> u32 {
         self.entry.file_size()
     }
+
+    /// Get creation timestamp
+    pub fn created(&self) -> Option<DateTime> {
+        self.entry.created()
+    }
+
+    /// Get modification timestamp
+    pub fn modified(&self) -> Option<DateTime> {
+        self.entry.modified()
+    }
+
+    /// Get last access date
+    pub fn accessed(&self) -> Option<DateTime> {
+        self.entry.accessed()
+    }
 }
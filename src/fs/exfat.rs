@@ -0,0 +1,746 @@
+use crate::fs::{FileSystem, FileSystemError, DirEntry};
+use crate::fs::entry::DirectoryEntry;
+use crate::fs::fat_table::{FatTable, FatType};
+use crate::fs::cluster::ClusterChain;
+use crate::fs::path::{Path, PathBuf};
+use crate::fs::metadata::Metadata;
+use alloc::vec::Vec;
+use alloc::string::{String, ToString};
+
+/// exFAT Boot Sector (secteur 0 de la Main Boot Region)
+#[repr(C, packed)]
+pub struct ExFatBootSector {
+    /// Jump instruction (EB 76 90)
+    pub jmp_boot: [u8; 3],
+    /// File system name ("EXFAT   ")
+    pub fs_name: [u8; 8],
+    /// Zone du BPB FAT, doit etre a zero en exFAT
+    pub must_be_zero: [u8; 53],
+    /// Partition offset (sectors)
+    pub partition_offset: u64,
+    /// Volume length (sectors)
+    pub volume_length: u64,
+    /// FAT offset (sectors)
+    pub fat_offset: u32,
+    /// FAT length (sectors)
+    pub fat_length: u32,
+    /// Cluster heap offset (sectors)
+    pub cluster_heap_offset: u32,
+    /// Number of clusters in the cluster heap
+    pub cluster_count: u32,
+    /// First cluster of the root directory
+    pub root_cluster: u32,
+    /// Volume serial number
+    pub volume_serial: u32,
+    /// File system revision (1.00)
+    pub fs_revision: u16,
+    /// Volume flags (bit 0 = active FAT)
+    pub volume_flags: u16,
+    /// log2(bytes per sector)
+    pub bytes_per_sector_shift: u8,
+    /// log2(sectors per cluster)
+    pub sectors_per_cluster_shift: u8,
+    /// Number of FATs (1 or 2)
+    pub num_fats: u8,
+    /// Drive select
+    pub drive_select: u8,
+    /// Percent of the cluster heap in use
+    pub percent_in_use: u8,
+    /// Reserved (7 bytes)
+    pub reserved: [u8; 7],
+    /// Boot code (390 bytes)
+    pub boot_code: [u8; 390],
+    /// Boot sector signature (0xAA55)
+    pub boot_signature: u16,
+}
+
+impl ExFatBootSector {
+    /// Parse un boot sector exFAT
+    ///
+    /// # Safety
+    ///
+    /// data doit contenir au moins 512 octets ; on les lit comme la structure
+    /// packed ExFatBootSector (alignement 1, toutes les valeurs de bits valides).
+    pub unsafe fn from_bytes(data: &[u8]) -> Result<Self, FileSystemError> {
+        if data.len() < 512 {
+            return Err(FileSystemError::InvalidBootSector(
+                "Boot sector must be at least 512 bytes".into()
+            ));
+        }
+
+        let bs = core::ptr::read(data.as_ptr() as *const ExFatBootSector);
+
+        if bs.fs_name != *b"EXFAT   " {
+            return Err(FileSystemError::InvalidBootSector("Not an exFAT filesystem".into()));
+        }
+        if bs.boot_signature != 0xAA55 {
+            return Err(FileSystemError::InvalidBootSector("Invalid boot sector signature".into()));
+        }
+        if bs.must_be_zero.iter().any(|&b| b != 0) {
+            return Err(FileSystemError::InvalidBootSector("MustBeZero region is not zero".into()));
+        }
+        // 512 a 4096 octets par secteur, clusters de 32 Mo max
+        if !(9..=12).contains(&bs.bytes_per_sector_shift)
+            || bs.sectors_per_cluster_shift > 25 - bs.bytes_per_sector_shift
+        {
+            return Err(FileSystemError::InvalidBootSector("Invalid sector or cluster size".into()));
+        }
+        if bs.num_fats != 1 && bs.num_fats != 2 {
+            return Err(FileSystemError::InvalidBootSector("Invalid number of FATs".into()));
+        }
+        // cluster_count vient du disque : pas de `+ 1` qui pourrait deborder
+        if bs.root_cluster < 2 || bs.root_cluster - 2 >= bs.cluster_count {
+            return Err(FileSystemError::InvalidBootSector("Invalid root directory cluster".into()));
+        }
+
+        Ok(bs)
+    }
+
+    /// L'image commence-t-elle par un boot sector exFAT ?
+    pub fn is_exfat(data: &[u8]) -> bool {
+        data.len() >= 11 && &data[3..11] == b"EXFAT   "
+    }
+
+    pub fn bytes_per_sector(&self) -> u32 {
+        1 << self.bytes_per_sector_shift
+    }
+
+    pub fn sectors_per_cluster(&self) -> u32 {
+        1 << self.sectors_per_cluster_shift
+    }
+
+    pub fn cluster_size(&self) -> u32 {
+        self.bytes_per_sector() << self.sectors_per_cluster_shift
+    }
+
+    /// Secteur de la FAT active (la seconde si VolumeFlags.ActiveFat et 2 FATs)
+    pub fn active_fat_start_sector(&self) -> u32 {
+        let fat_offset = self.fat_offset;
+        let fat_length = self.fat_length;
+        if self.num_fats == 2 && (self.volume_flags & 0x0001) != 0 {
+            fat_offset + fat_length
+        } else {
+            fat_offset
+        }
+    }
+
+    pub fn root_cluster(&self) -> u32 {
+        self.root_cluster
+    }
+
+    pub fn cluster_count(&self) -> u32 {
+        self.cluster_count
+    }
+}
+
+/// Checksum de la boot region : sur les 11 premiers secteurs, sans VolumeFlags
+/// (octets 106-107) ni PercentInUse (octet 112) qui changent sans reecrire le checksum
+pub fn boot_checksum(sectors: &[u8], bytes_per_sector: usize) -> u32 {
+    let mut checksum: u32 = 0;
+    for (i, &byte) in sectors[..11 * bytes_per_sector].iter().enumerate() {
+        if i == 106 || i == 107 || i == 112 {
+            continue;
+        }
+        checksum = checksum.rotate_right(1).wrapping_add(byte as u32);
+    }
+    checksum
+}
+
+/// Checksum d'un ensemble d'entrees (File + secondaires), sans le champ SetChecksum lui-meme
+pub fn entry_set_checksum(entries: &[u8]) -> u16 {
+    let mut checksum: u16 = 0;
+    for (i, &byte) in entries.iter().enumerate() {
+        if i == 2 || i == 3 {
+            continue;
+        }
+        checksum = checksum.rotate_right(1).wrapping_add(byte as u16);
+    }
+    checksum
+}
+
+/// Checksum de la table up-case, stocke dans son entree de repertoire
+pub fn upcase_table_checksum(table: &[u8]) -> u32 {
+    table.iter().fold(0u32, |checksum, &byte| checksum.rotate_right(1).wrapping_add(byte as u32))
+}
+
+/// Table up-case : conversion en majuscules des noms pour les comparer sans casse
+pub struct UpcaseTable {
+    /// Seules les correspondances non identite sont gardees, triees par caractere
+    mappings: Vec<(u16, u16)>,
+}
+
+impl UpcaseTable {
+    /// Decode la table (format compresse : 0xFFFF suivi du nombre de caracteres identite)
+    pub fn from_bytes(data: &[u8]) -> Result<Self, FileSystemError> {
+        if !data.len().is_multiple_of(2) {
+            return Err(FileSystemError::InvalidFat("Up-case table size must be even".into()));
+        }
+
+        let mut mappings = Vec::new();
+        let mut ch: u32 = 0;
+        let mut words = data.chunks_exact(2).map(|w| u16::from_le_bytes([w[0], w[1]]));
+        while let Some(word) = words.next() {
+            if ch > 0xFFFF {
+                break;
+            }
+            if word == 0xFFFF {
+                // une plage de caracteres qui se convertissent en eux-memes
+                let count = words.next().ok_or_else(|| {
+                    FileSystemError::InvalidFat("Truncated up-case table".into())
+                })?;
+                ch += count as u32;
+                continue;
+            }
+            if word as u32 != ch {
+                mappings.push((ch as u16, word));
+            }
+            ch += 1;
+        }
+
+        Ok(Self { mappings })
+    }
+
+    /// Majuscule d'un caractere UTF-16
+    pub fn upcase(&self, ch: u16) -> u16 {
+        match self.mappings.binary_search_by_key(&ch, |&(from, _)| from) {
+            Ok(index) => self.mappings[index].1,
+            Err(_) => ch,
+        }
+    }
+
+    /// NameHash d'un nom (sur le nom deja converti en majuscules)
+    pub fn name_hash(&self, name: &[u16]) -> u16 {
+        let mut hash: u16 = 0;
+        for &ch in name {
+            for byte in self.upcase(ch).to_le_bytes() {
+                hash = hash.rotate_right(1).wrapping_add(byte as u16);
+            }
+        }
+        hash
+    }
+
+    /// Comparaison de deux noms sans tenir compte de la casse
+    pub fn names_equal(&self, a: &[u16], b: &[u16]) -> bool {
+        a.len() == b.len() && a.iter().zip(b).all(|(&x, &y)| self.upcase(x) == self.upcase(y))
+    }
+}
+
+/// Bitmap d'allocation : un bit par cluster du cluster heap, a partir du cluster 2
+pub struct AllocationBitmap {
+    bits: Vec<u8>,
+    cluster_count: u32,
+}
+
+impl AllocationBitmap {
+    pub fn new(bits: Vec<u8>, cluster_count: u32) -> Result<Self, FileSystemError> {
+        if (bits.len() as u64) * 8 < cluster_count as u64 {
+            return Err(FileSystemError::InvalidFat("Allocation bitmap too small".into()));
+        }
+        Ok(Self { bits, cluster_count })
+    }
+
+    /// cluster alloue ?
+    pub fn is_allocated(&self, cluster: u32) -> bool {
+        if cluster < 2 || cluster - 2 >= self.cluster_count {
+            return false;
+        }
+        let index = (cluster - 2) as usize;
+        (self.bits[index / 8] >> (index % 8)) & 1 != 0
+    }
+
+    /// Nombre de clusters libres du heap
+    pub fn free_clusters(&self) -> u32 {
+        (2..self.cluster_count + 2).filter(|&c| !self.is_allocated(c)).count() as u32
+    }
+}
+
+/// Ensemble d'entrees decrivant un fichier ou dossier exFAT (File + Stream + File Name)
+pub struct ExFatEntry {
+    /// Nom complet (UTF-16)
+    pub name: Vec<u16>,
+    /// FileAttributes (memes bits que les attributs FAT)
+    pub attributes: u16,
+    /// Premier cluster des donnees (0 si vide)
+    pub first_cluster: u32,
+    /// Taille des donnees en octets
+    pub data_length: u64,
+    /// Octets valides, au dela on lit des zeros
+    pub valid_data_length: u64,
+    /// NoFatChain : donnees contigues, la FAT n'est pas utilisee
+    pub no_fat_chain: bool,
+    /// Timestamps (date << 16 | time, encodage DOS)
+    pub create_timestamp: u32,
+    pub modified_timestamp: u32,
+    pub accessed_timestamp: u32,
+    /// Increments de 10 ms (0-199)
+    pub create_10ms: u8,
+    pub modified_10ms: u8,
+}
+
+impl ExFatEntry {
+    /// Dossier ?
+    pub fn is_directory(&self) -> bool {
+        (self.attributes & 0x10) != 0
+    }
+
+    /// Nom en String (les paires de substitution invalides deviennent U+FFFD)
+    pub fn name_string(&self) -> String {
+        char::decode_utf16(self.name.iter().copied())
+            .map(|r| r.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect()
+    }
+
+    /// Convertit en DirEntry pour le trait FileSystem
+    ///
+    /// Le nom court est un alias 8.3 calcule a partir du nom long, et la taille est
+    /// saturee a u32::MAX pour les fichiers de plus de 4 Go.
+    pub fn to_dir_entry(&self) -> DirEntry {
+        let entry = DirectoryEntry {
+            name: short_alias(&self.name),
+            attributes: (self.attributes & 0x37) as u8,
+            nt_reserved: 0,
+            creation_time_tenths: self.create_10ms,
+            creation_time: self.create_timestamp as u16,
+            creation_date: (self.create_timestamp >> 16) as u16,
+            last_access_date: (self.accessed_timestamp >> 16) as u16,
+            first_cluster_high: (self.first_cluster >> 16) as u16,
+            last_write_time: self.modified_timestamp as u16,
+            last_write_date: (self.modified_timestamp >> 16) as u16,
+            first_cluster_low: self.first_cluster as u16,
+            file_size: self.data_length.min(u32::MAX as u64) as u32,
+        };
+        DirEntry::new(entry).with_long_name(self.name_string())
+    }
+}
+
+/// Alias 8.3 (majuscules ASCII) d'un nom exFAT, qui n'a pas de nom court sur disque
+fn short_alias(name: &[u16]) -> [u8; 11] {
+    let mut alias = [b' '; 11];
+    let dot = name.iter().rposition(|&c| c == b'.' as u16).filter(|&i| i > 0);
+    let (base, ext) = match dot {
+        Some(i) => (&name[..i], &name[i + 1..]),
+        None => (name, &name[name.len()..]),
+    };
+    let valid = |c: &&u16| **c < 0x80 && (**c as u8).is_ascii_alphanumeric();
+    for (slot, &c) in alias[..8].iter_mut().zip(base.iter().filter(valid)) {
+        *slot = (c as u8).to_ascii_uppercase();
+    }
+    for (slot, &c) in alias[8..].iter_mut().zip(ext.iter().filter(valid)) {
+        *slot = (c as u8).to_ascii_uppercase();
+    }
+    if alias[0] == b' ' {
+        alias[0] = b'_';
+    }
+    alias
+}
+
+/// Systeme de fichiers exFAT, en lecture seule
+pub struct ExFatFs {
+    /// Boot sector
+    boot_sector: ExFatBootSector,
+    /// FAT active (entrees 32 bits completes, sans bits reserves)
+    fat_table: FatTable,
+    /// Bitmap d'allocation du cluster heap
+    bitmap: AllocationBitmap,
+    /// Table up-case pour les comparaisons de noms
+    upcase: UpcaseTable,
+    /// Label du volume (entree de label de la racine)
+    volume_label: Option<String>,
+    /// Dossier courant
+    current_path: PathBuf,
+    /// Contenu complet de l'image
+    device_data: Vec<u8>,
+}
+
+impl ExFatFs {
+    /// Monte une image exFAT
+    ///
+    /// # Safety
+    ///
+    /// device_data doit contenir une image exFAT complete, Main Boot Region en tete.
+    pub unsafe fn new(device_data: &[u8]) -> Result<Self, FileSystemError> {
+        let boot_sector = ExFatBootSector::from_bytes(device_data)?;
+        let bps = boot_sector.bytes_per_sector() as usize;
+
+        // les 11 premiers secteurs sont couverts par le checksum repete dans le 12e
+        if device_data.len() < 12 * bps {
+            return Err(FileSystemError::InvalidBootSector("Boot region truncated".into()));
+        }
+        let checksum = boot_checksum(device_data, bps);
+        let stored = &device_data[11 * bps..12 * bps];
+        if stored.chunks_exact(4).any(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]]) != checksum) {
+            return Err(FileSystemError::InvalidBootSector("Boot region checksum mismatch".into()));
+        }
+
+        let fat_start = boot_sector.active_fat_start_sector() as usize * bps;
+        let fat_size = (boot_sector.cluster_count() as usize + 2) * 4;
+        if fat_start + fat_size > device_data.len() || fat_size > boot_sector.fat_length as usize * bps {
+            return Err(FileSystemError::InvalidFat("FAT table out of bounds".into()));
+        }
+        let fat_table = FatTable::from_bytes_with_type(&device_data[fat_start..fat_start + fat_size], FatType::ExFat)?;
+
+        let mut fs = Self {
+            boot_sector,
+            fat_table,
+            bitmap: AllocationBitmap { bits: Vec::new(), cluster_count: 0 },
+            upcase: UpcaseTable { mappings: Vec::new() },
+            volume_label: None,
+            current_path: PathBuf::root(),
+            device_data: device_data.to_vec(),
+        };
+        fs.load_root_metadata()?;
+        Ok(fs)
+    }
+
+    /// Lit le bitmap d'allocation, la table up-case et le label dans la racine
+    fn load_root_metadata(&mut self) -> Result<(), FileSystemError> {
+        let root = self.read_chain(self.boot_sector.root_cluster(), None, false)?;
+        let mut bitmap = None;
+        let mut upcase = None;
+
+        for chunk in root.chunks_exact(32) {
+            match chunk[0] {
+                0x00 => break,
+                0x81 => {
+                    // il y a un bitmap par FAT, on prend celui de la FAT active
+                    let index = chunk[1] & 0x01;
+                    let active = (self.boot_sector.volume_flags & 0x0001) as u8;
+                    if bitmap.is_none() || index == active {
+                        bitmap = Some((le_u32(chunk, 20), le_u64(chunk, 24)));
+                    }
+                }
+                0x82 => upcase = Some((le_u32(chunk, 4), le_u32(chunk, 20), le_u64(chunk, 24))),
+                0x83 => {
+                    let count = (chunk[1] as usize).min(11);
+                    let chars: Vec<u16> = (0..count).map(|i| le_u16(chunk, 2 + i * 2)).collect();
+                    self.volume_label = Some(
+                        char::decode_utf16(chars)
+                            .map(|r| r.unwrap_or(char::REPLACEMENT_CHARACTER))
+                            .collect(),
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        let (bitmap_cluster, bitmap_length) = bitmap
+            .ok_or_else(|| FileSystemError::InvalidFat("Missing allocation bitmap".into()))?;
+        let bits = self.read_chain(bitmap_cluster, Some(bitmap_length), false)?;
+        self.bitmap = AllocationBitmap::new(bits, self.boot_sector.cluster_count())?;
+
+        let (checksum, upcase_cluster, upcase_length) = upcase
+            .ok_or_else(|| FileSystemError::InvalidFat("Missing up-case table".into()))?;
+        let table = self.read_chain(upcase_cluster, Some(upcase_length), false)?;
+        if upcase_table_checksum(&table) != checksum {
+            return Err(FileSystemError::InvalidFat("Up-case table checksum mismatch".into()));
+        }
+        self.upcase = UpcaseTable::from_bytes(&table)?;
+        Ok(())
+    }
+
+    /// lire le contenu d'un cluster
+    pub fn read_cluster(&self, cluster: u32) -> Result<Vec<u8>, FileSystemError> {
+        if cluster < 2 || cluster - 2 >= self.boot_sector.cluster_count() {
+            return Err(FileSystemError::IoError("Invalid cluster number".into()));
+        }
+        let cluster_size = self.boot_sector.cluster_size() as usize;
+        let heap_start = self.boot_sector.cluster_heap_offset as usize * self.boot_sector.bytes_per_sector() as usize;
+        let offset = heap_start + (cluster - 2) as usize * cluster_size;
+
+        if offset + cluster_size > self.device_data.len() {
+            return Err(FileSystemError::IoError("Cluster out of bounds".into()));
+        }
+        Ok(self.device_data[offset..offset + cluster_size].to_vec())
+    }
+
+    /// Lit les donnees a partir d'un cluster, via la FAT ou en contigu si NoFatChain
+    ///
+    /// Sans longueur (dossiers suivant la FAT) on lit toute la chaine.
+    fn read_chain(&self, first_cluster: u32, length: Option<u64>, no_fat_chain: bool) -> Result<Vec<u8>, FileSystemError> {
+        let cluster_size = self.boot_sector.cluster_size() as u64;
+        let clusters: Vec<u32> = if no_fat_chain {
+            let length = length.ok_or_else(|| {
+                FileSystemError::ClusterChainError("Contiguous data without length".into())
+            })?;
+            let count = length.div_ceil(cluster_size);
+            if first_cluster as u64 + count > self.boot_sector.cluster_count() as u64 + 2 {
+                return Err(FileSystemError::ClusterChainError("Contiguous data out of cluster heap".into()));
+            }
+            (first_cluster..first_cluster + count as u32).collect()
+        } else {
+            ClusterChain::new(&self.fat_table, first_cluster)?.clusters().to_vec()
+        };
+
+        let mut data = Vec::new();
+        for cluster in clusters {
+            data.extend_from_slice(&self.read_cluster(cluster)?);
+        }
+        if let Some(length) = length {
+            if (data.len() as u64) < length {
+                return Err(FileSystemError::ClusterChainError("Cluster chain shorter than data length".into()));
+            }
+            data.truncate(length as usize);
+        }
+        Ok(data)
+    }
+
+    /// Parse les ensembles d'entrees fichier d'un dossier
+    ///
+    /// Un ensemble corrompu (taille, checksum, nom) est ignore : les autres
+    /// entrees du dossier restent lisibles.
+    fn parse_directory(&self, data: &[u8]) -> Result<Vec<ExFatEntry>, FileSystemError> {
+        let mut entries = Vec::new();
+        let mut index = 0;
+        let count = data.len() / 32;
+
+        while index < count {
+            let chunk = &data[index * 32..index * 32 + 32];
+            match chunk[0] {
+                0x00 => break,
+                0x85 => {
+                    let secondary_count = chunk[1] as usize;
+                    if secondary_count < 2 || index + secondary_count >= count {
+                        // taille invalide : les entrees secondaires seront sautees une a une
+                        index += 1;
+                        continue;
+                    }
+                    let set = &data[index * 32..(index + 1 + secondary_count) * 32];
+                    if let Ok(entry) = self.parse_entry_set(set) {
+                        entries.push(entry);
+                    }
+                    index += 1 + secondary_count;
+                }
+                // bitmap, up-case, label, entrees supprimees (bit InUse a 0)...
+                _ => index += 1,
+            }
+        }
+
+        Ok(entries)
+    }
+
+    /// Parse un ensemble File / Stream Extension / File Name et verifie son checksum
+    fn parse_entry_set(&self, set: &[u8]) -> Result<ExFatEntry, FileSystemError> {
+        if entry_set_checksum(set) != le_u16(set, 2) {
+            return Err(FileSystemError::DirectoryEntryError("Entry set checksum mismatch".into()));
+        }
+
+        let stream = &set[32..64];
+        if stream[0] != 0xC0 {
+            return Err(FileSystemError::DirectoryEntryError("Missing stream extension entry".into()));
+        }
+        let flags = stream[1];
+        let name_length = stream[3] as usize;
+
+        let mut name = Vec::with_capacity(name_length);
+        for chunk in set[64..].chunks_exact(32) {
+            if chunk[0] != 0xC1 {
+                break;
+            }
+            for i in 0..15 {
+                if name.len() == name_length {
+                    break;
+                }
+                name.push(le_u16(chunk, 2 + i * 2));
+            }
+        }
+        if name.len() != name_length || name_length == 0 {
+            return Err(FileSystemError::DirectoryEntryError("Invalid file name entries".into()));
+        }
+        if self.upcase.name_hash(&name) != le_u16(stream, 4) {
+            return Err(FileSystemError::DirectoryEntryError("Name hash mismatch".into()));
+        }
+
+        let data_length = le_u64(stream, 24);
+        Ok(ExFatEntry {
+            name,
+            attributes: le_u16(set, 4),
+            first_cluster: le_u32(stream, 20),
+            data_length,
+            valid_data_length: le_u64(stream, 8).min(data_length),
+            no_fat_chain: (flags & 0x02) != 0,
+            create_timestamp: le_u32(set, 8),
+            modified_timestamp: le_u32(set, 12),
+            accessed_timestamp: le_u32(set, 16),
+            create_10ms: set[20],
+            modified_10ms: set[21],
+        })
+    }
+
+    /// Contenu d'un dossier (la racine suit toujours la FAT)
+    fn read_directory(&self, dir: Option<&ExFatEntry>) -> Result<Vec<ExFatEntry>, FileSystemError> {
+        let data = match dir {
+            None => self.read_chain(self.boot_sector.root_cluster(), None, false)?,
+            Some(dir) if dir.first_cluster == 0 => Vec::new(),
+            Some(dir) => self.read_chain(dir.first_cluster, Some(dir.data_length), dir.no_fat_chain)?,
+        };
+        self.parse_directory(&data)
+    }
+
+    /// Cherche un nom dans un dossier, sans casse, via NameHash puis comparaison
+    fn find_entry(&self, entries: Vec<ExFatEntry>, name: &str) -> Option<ExFatEntry> {
+        let target: Vec<u16> = name.encode_utf16().collect();
+        let hash = self.upcase.name_hash(&target);
+        entries.into_iter().find(|entry| {
+            self.upcase.name_hash(&entry.name) == hash && self.upcase.names_equal(&entry.name, &target)
+        })
+    }
+
+    /// Resout un chemin absolu ; None designe la racine
+    fn resolve(&self, path: &Path) -> Result<Option<ExFatEntry>, FileSystemError> {
+        let mut stack: Vec<ExFatEntry> = Vec::new();
+        for component in path.components() {
+            if let Some(parent) = stack.last() {
+                if !parent.is_directory() {
+                    let mut msg = String::from("Not a directory: ");
+                    msg.push_str(&parent.name_string());
+                    return Err(FileSystemError::DirectoryNotFound(msg));
+                }
+            }
+            let entries = self.read_directory(stack.last())?;
+            let entry = self.find_entry(entries, component).ok_or_else(|| {
+                FileSystemError::FileNotFound(path.to_string())
+            })?;
+            stack.push(entry);
+        }
+        Ok(stack.pop())
+    }
+
+    /// Chemin absolu a partir d'un chemin absolu ou relatif au dossier courant
+    fn absolute_path(&self, path: &str) -> Result<Path, FileSystemError> {
+        if path.starts_with('/') {
+            Ok(Path::new(path)?)
+        } else {
+            Ok(self.current_path.as_path().join(&Path::new(path)?)?)
+        }
+    }
+
+    /// Get boot sector reference
+    pub fn boot_sector(&self) -> &ExFatBootSector {
+        &self.boot_sector
+    }
+
+    /// Bitmap d'allocation du volume
+    pub fn allocation_bitmap(&self) -> &AllocationBitmap {
+        &self.bitmap
+    }
+
+    /// Label du volume, s'il y en a un
+    pub fn volume_label(&self) -> Option<&str> {
+        self.volume_label.as_deref()
+    }
+}
+
+impl FileSystem for ExFatFs {
+    /// liste un dossier exFAT
+    fn list(&self, path: &str) -> Result<Vec<DirEntry>, FileSystemError> {
+        let target_path = self.absolute_path(path)?;
+        let dir = self.resolve(&target_path)?;
+        if let Some(ref dir) = dir {
+            if !dir.is_directory() {
+                let mut msg = target_path.to_string();
+                msg.push_str(" is not a directory");
+                return Err(FileSystemError::DirectoryNotFound(msg));
+            }
+        }
+        Ok(self.read_directory(dir.as_ref())?.iter().map(ExFatEntry::to_dir_entry).collect())
+    }
+
+    /// lire entierement un fichier
+    fn read_file(&self, path: &str) -> Result<Vec<u8>, FileSystemError> {
+        let target_path = self.absolute_path(path)?;
+        let entry = self.resolve(&target_path)?
+            .ok_or_else(|| FileSystemError::FileNotFound(target_path.to_string()))?;
+        if entry.is_directory() {
+            let mut msg = target_path.to_string();
+            msg.push_str(" is not a file");
+            return Err(FileSystemError::FileNotFound(msg));
+        }
+        if entry.first_cluster == 0 || entry.data_length == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut data = self.read_chain(entry.first_cluster, Some(entry.data_length), entry.no_fat_chain)?;
+        // au dela de ValidDataLength le contenu n'est pas defini : on renvoie des zeros
+        for byte in &mut data[entry.valid_data_length as usize..] {
+            *byte = 0;
+        }
+        Ok(data)
+    }
+
+    /// Change current directory
+    fn cd(&mut self, path: &str) -> Result<(), FileSystemError> {
+        let target_path = self.absolute_path(path)?;
+        if let Some(entry) = self.resolve(&target_path)? {
+            if !entry.is_directory() {
+                let mut msg = target_path.to_string();
+                msg.push_str(" is not a directory");
+                return Err(FileSystemError::DirectoryNotFound(msg));
+            }
+        }
+        // `..` est deja resolu lexicalement par Path (exFAT n'a pas d'entree `..`)
+        self.current_path = PathBuf::from(target_path);
+        Ok(())
+    }
+
+    /// Get current directory path
+    fn pwd(&self) -> String {
+        self.current_path.to_string()
+    }
+
+    fn create_file(&mut self, _path: &str) -> Result<(), FileSystemError> {
+        Err(FileSystemError::Unsupported("exFAT support is read-only".into()))
+    }
+
+    fn write_file(&mut self, _path: &str, _data: &[u8]) -> Result<(), FileSystemError> {
+        Err(FileSystemError::Unsupported("exFAT support is read-only".into()))
+    }
+
+    fn create_dir(&mut self, _path: &str) -> Result<(), FileSystemError> {
+        Err(FileSystemError::Unsupported("exFAT support is read-only".into()))
+    }
+
+    fn remove_file(&mut self, _path: &str) -> Result<(), FileSystemError> {
+        Err(FileSystemError::Unsupported("exFAT support is read-only".into()))
+    }
+
+    /// Metadonnees ; la taille n'est pas saturee a 4 Go comme dans DirEntry
+    fn metadata(&self, path: &str) -> Result<Metadata, FileSystemError> {
+        let target_path = self.absolute_path(path)?;
+        let cluster_size = self.boot_sector.cluster_size() as u64;
+        let entry = match self.resolve(&target_path)? {
+            Some(entry) => entry,
+            None => {
+                let root_cluster = self.boot_sector.root_cluster();
+                let clusters = ClusterChain::new(&self.fat_table, root_cluster)?.len() as u64;
+                return Ok(Metadata::root(root_cluster, clusters * cluster_size));
+            }
+        };
+
+        let allocated_size = if entry.first_cluster == 0 {
+            0
+        } else if entry.no_fat_chain {
+            entry.data_length.div_ceil(cluster_size) * cluster_size
+        } else {
+            ClusterChain::new(&self.fat_table, entry.first_cluster)?.len() as u64 * cluster_size
+        };
+        // pas de nom court ni d'index d'entree 8.3 en exFAT
+        let dir_entry = entry.to_dir_entry();
+        let mut metadata = Metadata::from_entry(&dir_entry.entry, dir_entry.long_name, allocated_size, None)?;
+        metadata.size = if entry.is_directory() { 0 } else { entry.data_length };
+        Ok(metadata)
+    }
+}
+
+fn le_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([data[offset], data[offset + 1]])
+}
+
+fn le_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
+
+fn le_u64(data: &[u8], offset: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&data[offset..offset + 8]);
+    u64::from_le_bytes(bytes)
+}
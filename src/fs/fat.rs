@@ -1,11 +1,20 @@
 use crate::fs::{FileSystem, FileSystemError, DirEntry};
 use crate::fs::boot::BootSector; // on utilise direct BootSector au lieu du chemin fs/boot
-use crate::fs::fat_table::FatTable; 
+use crate::fs::fat_table::{FatTable, FatType};
+use crate::fs::glob;
 use crate::fs::cluster::ClusterChain;
-use crate::fs::directory::Directory;
-use crate::fs::path::{Path, PathBuf};
+use crate::fs::directory::{Directory, DirScanner, DirSlot};
+use crate::fs::fsck::{self, FsckReport};
+use crate::fs::entry::{lfn_checksum, Attributes, DirectoryEntry, LongFileNameEntry};
+use crate::fs::path::{self, Path, PathBuf};
+use crate::fs::metadata::{EntryLocation, Metadata};
+use crate::fs::time::{DateTime, FixedTimeProvider, TimeProvider};
+use crate::fs::volume::{encode_volume_label, FsInfo, VolumeInfo};
+use crate::fs::walk::Walk;
+use alloc::boxed::Box;
 use alloc::vec::Vec; // on fait des allocs vu qu'on est en no_std, si on etait en std on aurait ecrit std::vec::Vec et le alloc est implicite
-use alloc::string::String;
+use alloc::string::{String, ToString};
+use core::cell::{Ref, RefCell};
 
 /// CE FICHIER EST HORRIBLE
 pub struct Fat32Fs {
@@ -13,293 +22,1144 @@ pub struct Fat32Fs {
     boot_sector: BootSector,
     /// FAT table (tableau avec num cluster et son suiv genre (5:6 ou 5: fin ou 5: erreur))
     fat_table: FatTable,
+    /// FAT12, FAT16 ou FAT32, deduit du nombre de clusters
+    fat_type: FatType,
     /// dossier ou l'on est genre quand je crée des dossiers dans la fat ? ou bien autre chose ?
     current_path: PathBuf,
     /// device_data contenu complet de la fat32
-    device_data: Vec<u8>,
+    /// (RefCell : `read_file` prend &self mais peut mettre a jour la date d'acces)
+    device_data: RefCell<Vec<u8>>,
+    /// horloge utilisee pour les dates de creation / modification / acces
+    time_provider: Box<dyn TimeProvider>,
+    /// mettre a jour la date de dernier acces a chaque lecture de fichier
+    update_access_date: bool,
+    /// lister aussi les entrees cachees et systeme
+    show_hidden: bool,
+    /// resoudre `..` via l'entree `..` du dossier sur disque plutot que lexicalement
+    physical_parent: bool,
+    /// ou commencer a chercher un cluster libre
+    next_free_cluster: u32,
 }
 
 impl Fat32Fs { //bloc de fonctions et methodes associés a fat32Fs
 
-    //fonction de creation d'une instance de FAT32
+    /// Monte une image FAT12, FAT16 ou FAT32
+    ///
+    /// L'horloge par defaut est un `FixedTimeProvider` (1980-01-01) et la date
+    /// d'acces n'est pas mise a jour en lecture.
+    ///
+    /// # Safety
+    ///
+    /// device_data doit contenir une image FAT complete, boot sector en tete.
     pub unsafe fn new(device_data: &[u8]) -> Result<Self, FileSystemError> { //on retourne la structure ou une erreur
 
         let boot_sector = BootSector::from_bytes(device_data)?; //lire les 512 premier octet de device data et remplir boot sector
-        
+
         // offset debut fat et taille fat, à partir de boot sector, on multiplie pour avoir la taille en octet
         let fat_start = boot_sector.fat_start_sector() * boot_sector.bytes_per_sector();
         let fat_size = boot_sector.sectors_per_fat() * boot_sector.bytes_per_sector();
-        
+
         // si la taille de la fat est plus grande erreur
         if (fat_start as usize + fat_size as usize) > device_data.len() {
             return Err(FileSystemError::InvalidFat("FAT table out of bounds".into()));
         }
-        
+
         let fat_data = &device_data[fat_start as usize..(fat_start as usize + fat_size as usize)];
         //fat_data contient exactement tout les octet de device_data
-        let fat_table = FatTable::from_bytes(fat_data)?;
+        let fat_type = boot_sector.fat_type();
+        let fat_table = FatTable::from_bytes_with_type(fat_data, fat_type)?;
         // fat_table contient le num du cluster et son contenu / code erreur / code fin et ? erreur si Fat invalide
 
 
-        Ok(Self { // tout est good on a notre structure de FAT32
+        let mut fs = Self { // tout est good on a notre structure de FAT32
             boot_sector,
             fat_table,
+            fat_type,
             current_path: PathBuf::root(),
-            device_data: device_data.to_vec(),
-        })
+            device_data: RefCell::new(device_data.to_vec()),
+            time_provider: Box::new(FixedTimeProvider::default()),
+            update_access_date: false,
+            show_hidden: true,
+            physical_parent: false,
+            next_free_cluster: 2,
+        };
+        // FSInfo indique ou chercher un cluster libre (simple indication)
+        if let Some(next_free) = fs.fs_info().and_then(|info| info.next_free) {
+            if next_free >= 2 && next_free < fs.max_cluster() {
+                fs.next_free_cluster = next_free;
+            }
+        }
+        Ok(fs)
+    }
+
+    /// offset en octets du secteur FSInfo (FAT32)
+    fn fs_info_offset(&self) -> Option<usize> {
+        let sector = self.boot_sector.fs_info_sector()?;
+        Some(sector as usize * self.boot_sector.bytes_per_sector() as usize)
+    }
+
+    /// Secteur FSInfo, None en FAT12/16 ou si ses signatures sont invalides
+    pub fn fs_info(&self) -> Option<FsInfo> {
+        let offset = self.fs_info_offset()?;
+        let device_data = self.device_data.borrow();
+        FsInfo::from_bytes(device_data.get(offset..offset + 512)?)
+    }
+
+    /// met a jour FSInfo apres une allocation (delta < 0) ou une liberation (delta > 0)
+    fn update_fs_info(&self, delta: i64) {
+        let (Some(offset), Some(mut info)) = (self.fs_info_offset(), self.fs_info()) else {
+            return;
+        };
+        // un compteur inconnu reste inconnu
+        info.free_count = info.free_count.map(|count| (count as i64 + delta).max(0) as u32);
+        info.next_free = Some(self.next_free_cluster);
+        info.write_to(&mut self.device_data.borrow_mut()[offset..offset + 512]);
+    }
+
+    /// Statistiques du volume : clusters libres, utilises et defectueux (parcours de toute la FAT)
+    pub fn volume_info(&self) -> VolumeInfo {
+        let mut free_clusters = 0;
+        let mut bad_clusters = 0;
+        for cluster in 2..self.max_cluster() {
+            if self.fat_table.is_free_cluster(cluster) {
+                free_clusters += 1;
+            } else if self.fat_table.is_bad_cluster(cluster) {
+                bad_clusters += 1;
+            }
+        }
+        let total_clusters = self.boot_sector.cluster_count();
+        // clusters au dela de la FAT (FAT trop petite) : inutilisables, comptes comme utilises
+        let used_clusters = total_clusters - free_clusters - bad_clusters;
+
+        VolumeInfo {
+            fat_type: self.fat_type,
+            cluster_size: self.boot_sector.cluster_size(),
+            total_clusters,
+            free_clusters,
+            used_clusters,
+            bad_clusters,
+            fs_info_free_clusters: self.fs_info().and_then(|info| info.free_count),
+            volume_id: self.boot_sector.volume_id(),
+            volume_label: self.label(),
+        }
+    }
+
+    /// entree label de la racine, avec son index
+    fn root_label_slot(&self) -> Result<Option<DirSlot>, FileSystemError> {
+        let directory_data = self.read_directory_data(0)?;
+        Ok(Directory::scan(&directory_data).into_iter().find(|slot| slot.entry.is_volume_label()))
+    }
+
+    /// Label du volume : l'entree label de la racine, sinon celui du BPB (comme Windows)
+    ///
+    /// None si aucun des deux n'est renseigne ("NO NAME" dans le BPB).
+    pub fn label(&self) -> Option<String> {
+        let raw = match self.root_label_slot() {
+            Ok(Some(slot)) => slot.entry.name,
+            _ => self.boot_sector.bpb_volume_label()?,
+        };
+        let label = String::from_utf8_lossy(&raw).trim_end().to_string();
+        if label.is_empty() || label == "NO NAME" {
+            return None;
+        }
+        Some(label)
+    }
+
+    /// Change le label dans la racine et dans le BPB (et sa copie de secours en FAT32)
+    ///
+    /// Le label est mis en majuscules ; une chaine vide supprime le label.
+    pub fn set_label(&mut self, label: &str) -> Result<(), FileSystemError> {
+        let encoded = encode_volume_label(label)?;
+
+        // entree de la racine
+        match (self.root_label_slot()?, encoded) {
+            (Some(slot), None) => {
+                let offset = self.dir_entry_offset(0, slot.index)?;
+                self.device_data.borrow_mut()[offset] = 0xE5;
+            }
+            (Some(slot), Some(name)) => {
+                let mut entry = slot.entry;
+                let now = self.time_provider.now();
+                entry.name = name;
+                entry.last_write_time = now.fat_time();
+                entry.last_write_date = now.fat_date();
+                self.write_dir_entry(0, slot.index, &entry.to_bytes())?;
+            }
+            (None, Some(name)) => {
+                let mut entry = self.new_entry(Attributes::VOLUME_ID);
+                entry.name = name;
+                let index = self.find_free_entries(0, 1)?;
+                self.write_dir_entry(0, index, &entry.to_bytes())?;
+            }
+            (None, None) => {}
+        }
+
+        // BPB : seulement si la signature 0x29 indique que le champ existe
+        let Some(offset) = self.boot_sector.volume_label_offset() else {
+            return Ok(());
+        };
+        let bpb_label = encoded.unwrap_or(*b"NO NAME    ");
+        let bps = self.boot_sector.bytes_per_sector() as usize;
+        let mut sectors = Vec::from([0]);
+        if let Some(backup) = self.boot_sector.backup_boot_sector() {
+            sectors.push(backup as usize * bps);
+        }
+        let mut device_data = self.device_data.borrow_mut();
+        for start in sectors {
+            if let Some(field) = device_data.get_mut(start + offset..start + offset + 11) {
+                field.copy_from_slice(&bpb_label);
+            }
+        }
+        // Safety: le boot sector a deja ete valide au montage, seul le label a change
+        self.boot_sector = unsafe { BootSector::from_bytes(&device_data)? };
+        Ok(())
+    }
+
+    /// Utiliser cette horloge pour horodater les entrees creees ou modifiees
+    pub fn with_time_provider(mut self, time_provider: Box<dyn TimeProvider>) -> Self {
+        self.time_provider = time_provider;
+        self
     }
-    
+
+    /// Mettre a jour la date de dernier acces quand un fichier est lu
+    pub fn with_access_date_updates(mut self, enabled: bool) -> Self {
+        self.update_access_date = enabled;
+        self
+    }
+
+    /// Lister ou non les entrees HIDDEN / SYSTEM (listees par defaut)
+    pub fn with_hidden_entries(mut self, show: bool) -> Self {
+        self.show_hidden = show;
+        self
+    }
+
+    /// Resoudre `..` via l'entree `..` stockee dans chaque dossier (desactive par defaut)
+    ///
+    /// Par defaut `/a/b/..` vaut `/a` (resolution lexicale). Sur disque, le `..`
+    /// d'un dossier atteint par un autre chemin (entree dupliquee, image corrompue)
+    /// peut designer un autre parent : c'est alors celui-ci qui est suivi.
+    pub fn with_physical_parent_resolution(mut self, physical: bool) -> Self {
+        self.physical_parent = physical;
+        self
+    }
+
+    /// Remplace les attributs d'une entree, reecrite sur place
+    ///
+    /// Les bits DIRECTORY et VOLUME_ID decrivent la nature de l'entree : ils sont conserves.
+    pub fn set_attributes(&mut self, path: &str, attributes: Attributes) -> Result<(), FileSystemError> {
+        let target_path = self.resolve_path(path)?;
+        let (parent_cluster, slot) = self.lookup(&target_path)?;
+
+        let kind = Attributes::DIRECTORY | Attributes::VOLUME_ID;
+        let mut new_attributes = attributes;
+        new_attributes.remove(kind);
+        new_attributes.insert(Attributes::from_bits(slot.entry.attributes().bits() & kind.bits()));
+
+        let mut entry = slot.entry;
+        entry.attributes = new_attributes.bits();
+        self.write_dir_entry(parent_cluster, slot.index, &entry.to_bytes())
+    }
+
+    /// Remplace les dates d'une entree (None garde la date actuelle)
+    ///
+    /// Sert a conserver les dates d'un fichier copie depuis l'hote ; les dates
+    /// hors de la plage FAT sont ramenees a 1980-01-01 ou 2107-12-31 (`DateTime::clamp_to_fat`).
+    pub fn set_times(
+        &mut self,
+        path: &str,
+        created: Option<DateTime>,
+        modified: Option<DateTime>,
+        accessed: Option<DateTime>,
+    ) -> Result<(), FileSystemError> {
+        let target_path = self.resolve_path(path)?;
+        let (parent_cluster, slot) = self.lookup(&target_path)?;
+
+        let mut entry = slot.entry;
+        if let Some(created) = created {
+            entry.creation_date = created.fat_date();
+            entry.creation_time = created.fat_time();
+            entry.creation_time_tenths = created.fat_hundredths();
+        }
+        if let Some(modified) = modified {
+            entry.last_write_date = modified.fat_date();
+            entry.last_write_time = modified.fat_time();
+        }
+        if let Some(accessed) = accessed {
+            entry.last_access_date = accessed.fat_date();
+        }
+        self.write_dir_entry(parent_cluster, slot.index, &entry.to_bytes())
+    }
+
+    /// entree visible dans `list` / `read_dir` (HIDDEN et SYSTEM masquables)
+    fn is_listed(&self, entry: &DirEntry) -> bool {
+        self.show_hidden || (!entry.is_hidden() && !entry.is_system())
+    }
+
+    /// erreur ReadOnly si l'entree a l'attribut lecture seule
+    fn check_writable(entry: &DirectoryEntry, path: &Path) -> Result<(), FileSystemError> {
+        if entry.attributes().is_read_only() {
+            let mut msg = path.to_string();
+            msg.push_str(" is read-only");
+            return Err(FileSystemError::ReadOnly(msg));
+        }
+        Ok(())
+    }
+
     /// retourner la chaine complente d'un cluster a partir d'un cluster i (start_cluster)
     pub fn get_cluster_chain(&self, start_cluster: u32) -> Result<ClusterChain, FileSystemError> {
         ClusterChain::new(&self.fat_table, start_cluster)
     }
     //ClusterChain c'est un constructeur on lui donne la fat table et le start cluster
-    
-    ///lire le contenu d'un cluster 
+
+    /// offset en octets d'un cluster dans l'image
+    pub fn cluster_offset(&self, cluster: u32) -> Result<usize, FileSystemError> {
+        if cluster < 2 || cluster - 2 >= self.boot_sector.cluster_count() { // les clusters 0 et 1 sont reserves
+            return Err(FileSystemError::IoError("Invalid cluster number".into()));
+        }
+        let bps = self.boot_sector.bytes_per_sector() as usize;
+        let data_start = self.boot_sector.data_start_sector() as usize * bps; //offset en octet de la zone data_start
+        // chaque cluster commence a partir de 2 et on multiplie pour avoir l'offset
+        let cluster_offset = (cluster - 2) as usize * self.boot_sector.cluster_size() as usize;
+        Ok(data_start + cluster_offset)
+    }
+
+    /// lire un secteur brut de l'image (boot sector, FATs, donnees...)
+    pub fn read_sector(&self, sector: u32) -> Result<Vec<u8>, FileSystemError> {
+        let bps = self.boot_sector.bytes_per_sector() as usize;
+        let offset = sector as usize * bps;
+        let device_data = self.device_data.borrow();
+        device_data.get(offset..offset + bps)
+            .map(<[u8]>::to_vec)
+            .ok_or_else(|| FileSystemError::IoError("Sector out of bounds".into()))
+    }
+
+    ///lire le contenu d'un cluster
     pub fn read_cluster(&self, cluster: u32) -> Result<Vec<u8>, FileSystemError> {
-        let cluster_size = self.boot_sector.cluster_size() as usize; 
-        let data_start = self.boot_sector.data_start_sector() * self.boot_sector.bytes_per_sector(); //offset en octet de la zone data_start
-        let cluster_offset = ((cluster - 2) * self.boot_sector.sectors_per_cluster()) 
-            * self.boot_sector.bytes_per_sector();  // chaque cluster commence a partir de 2 et on multiplie pour avoir l'offset
-        let offset = (data_start + cluster_offset) as usize;
-        
-        if offset + cluster_size > self.device_data.len() { //offset superieur à l'image ERREUR
+        let cluster_size = self.boot_sector.cluster_size() as usize;
+        let offset = self.cluster_offset(cluster)?;
+        let device_data = self.device_data.borrow();
+
+        if offset + cluster_size > device_data.len() { //offset superieur à l'image ERREUR
+            return Err(FileSystemError::IoError("Cluster out of bounds".into()));
+        }
+
+        Ok(device_data[offset..offset + cluster_size].to_vec()) //retourne vecteur d'octet (indexation cluster)
+    }
+
+    /// ecrire un cluster (data plus courte que le cluster : le reste est mis a zero)
+    pub fn write_cluster(&self, cluster: u32, data: &[u8]) -> Result<(), FileSystemError> {
+        let cluster_size = self.boot_sector.cluster_size() as usize;
+        if data.len() > cluster_size {
+            return Err(FileSystemError::IoError("Data larger than a cluster".into()));
+        }
+        let offset = self.cluster_offset(cluster)?;
+        let mut device_data = self.device_data.borrow_mut();
+        if offset + cluster_size > device_data.len() {
             return Err(FileSystemError::IoError("Cluster out of bounds".into()));
         }
-        
-        Ok(self.device_data[offset..offset + cluster_size].to_vec()) //retourne vecteur d'octet (indexation cluster)
+        device_data[offset..offset + data.len()].copy_from_slice(data);
+        device_data[offset + data.len()..offset + cluster_size].fill(0);
+        Ok(())
+    }
+
+    /// Contenu complet de l'image, avec les modifications faites
+    pub fn device_data(&self) -> Ref<'_, [u8]> {
+        Ref::map(self.device_data.borrow(), |data| data.as_slice())
+    }
+
+    /// Recupere l'image, avec les modifications faites
+    pub fn into_device_data(self) -> Vec<u8> {
+        self.device_data.into_inner()
+    }
+
+    /// Cluster de la racine : 0 designe la racine fixe FAT12/16
+    pub(crate) fn root_dir_cluster(&self) -> u32 {
+        match self.fat_type {
+            FatType::Fat32 | FatType::ExFat => self.boot_sector.root_cluster(),
+            FatType::Fat12 | FatType::Fat16 => 0,
+        }
+    }
+
+    /// lire tout le contenu d'un dossier dans un tampon
+    ///
+    /// Le cluster 0 designe la racine (c'est aussi la valeur de `..` dans les
+    /// sous-dossiers de la racine) : en FAT12/16 on lit la zone fixe.
+    pub(crate) fn read_directory_data(&self, cluster: u32) -> Result<Vec<u8>, FileSystemError> {
+        let cluster = if cluster == 0 { self.root_dir_cluster() } else { cluster };
+        if cluster == 0 {
+            let (start, size) = self.fixed_root_region();
+            let device_data = self.device_data.borrow();
+            if start + size > device_data.len() {
+                return Err(FileSystemError::IoError("Root directory out of bounds".into()));
+            }
+            return Ok(device_data[start..start + size].to_vec());
+        }
+
+        let chain = self.get_cluster_chain(cluster)?;
+        let mut directory_data = Vec::new();
+        for &cluster_num in chain.clusters() {
+            let cluster_data = self.read_cluster(cluster_num)?;
+            directory_data.extend_from_slice(&cluster_data);
+        }
+        Ok(directory_data)
+    }
+
+    /// offset et taille en octets de la racine fixe FAT12/16
+    fn fixed_root_region(&self) -> (usize, usize) {
+        let bps = self.boot_sector.bytes_per_sector() as usize;
+        let start = self.boot_sector.root_dir_start_sector() as usize * bps;
+        let size = self.boot_sector.root_dir_sectors() as usize * bps;
+        (start, size)
+    }
+
+    /// offset dans l'image de l'entree numero `index` d'un dossier
+    fn dir_entry_offset(&self, dir_cluster: u32, index: usize) -> Result<usize, FileSystemError> {
+        let dir_cluster = if dir_cluster == 0 { self.root_dir_cluster() } else { dir_cluster };
+        let byte_offset = index * 32;
+        if dir_cluster == 0 {
+            let (start, size) = self.fixed_root_region();
+            if byte_offset + 32 > size {
+                return Err(FileSystemError::DirectoryEntryError("Entry outside root directory".into()));
+            }
+            return Ok(start + byte_offset);
+        }
+
+        let cluster_size = self.boot_sector.cluster_size() as usize;
+        let chain = self.get_cluster_chain(dir_cluster)?;
+        let cluster = chain.clusters().get(byte_offset / cluster_size).ok_or_else(|| {
+            FileSystemError::DirectoryEntryError("Entry outside directory".into())
+        })?;
+        Ok(self.cluster_offset(*cluster)? + byte_offset % cluster_size)
     }
-    
+
+    /// ecrire 32 octets d'entree de repertoire
+    fn write_dir_entry(&self, dir_cluster: u32, index: usize, bytes: &[u8; 32]) -> Result<(), FileSystemError> {
+        let offset = self.dir_entry_offset(dir_cluster, index)?;
+        let mut device_data = self.device_data.borrow_mut();
+        if offset + 32 > device_data.len() {
+            return Err(FileSystemError::IoError("Directory entry out of bounds".into()));
+        }
+        device_data[offset..offset + 32].copy_from_slice(bytes);
+        Ok(())
+    }
+
     // j'ai un chemin d'acces et je veux trouver le cluster correspondant.
     fn get_directory_cluster(&self, path: &Path) -> Result<u32, FileSystemError> { //pk fonction privé ?
         if path.is_root() { //si c'est la racine ya rien à faire
-            return Ok(self.boot_sector.root_cluster());
+            return Ok(self.root_dir_cluster());
         }
-        
+
         // se positionner sur le dossier racine avant de boucler
-        let mut current_cluster = self.boot_sector.root_cluster();
+        let mut current_cluster = self.root_dir_cluster();
 
         // on parcourt chaque element du chemin
         for component in path.components() {
-            // lire contenu dossier courant
-            let chain = self.get_cluster_chain(current_cluster)?;
-            //charger tout le contenu du dossier dans un tampon
-            let mut directory_data = Vec::new(); //pk un tampon ?
-            for &cluster_num in chain.clusters() {
-                let cluster_data = self.read_cluster(cluster_num)?;
-                directory_data.extend_from_slice(&cluster_data);
-            }
-            
+            // charger tout le contenu du dossier courant dans un tampon
+            let directory_data = self.read_directory_data(current_cluster)?;
+
             let entry = Directory::find_entry(&directory_data, component)? //chercher dans le dossier courant un sous dossier avec le nom dans component
                 .ok_or_else(|| {
                     let mut msg = String::from("Directory not found: ");
                     msg.push_str(component);
                     FileSystemError::DirectoryNotFound(msg)
                 })?;
-            
+
             if !entry.is_directory() { // si entrée trouvé mais pas un directory
                 let mut msg = String::from("Not a directory: ");
                 msg.push_str(component); //afficher que le dosiser en entrée n'est pas un directory
                 return Err(FileSystemError::DirectoryNotFound(msg));
             }
-            
-            current_cluster = entry.first_cluster(); // passer au cluster suivant
-            if current_cluster == 0 { //cluster de fin 
-                return Err(FileSystemError::DirectoryNotFound(
-                    "Invalid directory cluster".into()
-                ));
+
+            // passer au cluster suivant, 0 c'est la racine (entree `..`)
+            current_cluster = entry.first_cluster();
+            if current_cluster == 0 {
+                current_cluster = self.root_dir_cluster();
             }
         }
-        
+
         Ok(current_cluster)
     }
-    
-    /// Get boot sector reference
-    pub fn boot_sector(&self) -> &BootSector {
-        &self.boot_sector
+
+    /// chemin absolu a partir d'un chemin absolu ou relatif au dossier courant
+    fn resolve_path(&self, path: &str) -> Result<Path, FileSystemError> {
+        if self.physical_parent {
+            return self.resolve_physical(path);
+        }
+        if path.starts_with('/') { //chemin absolu
+            Ok(Path::new(path)?)
+        } else {
+            Ok(self.current_path.as_path().join(&Path::new(path)?)?) //chemin relatif on le concatene au chemin courant
+        }
     }
-}
 
-impl FileSystem for Fat32Fs {
-    /// fonction qui liste les fichiers dossiers dans un chemin
-    fn list(&self, path: &str) -> Result<Vec<DirEntry>, FileSystemError> {
-        let target_path = if path.starts_with('/') { //chemin absolu
-            Path::new(path)?
+    /// resolution de `..` via l'entree `..` de chaque dossier sur disque
+    ///
+    /// Le parent trouve doit etre un des dossiers deja traverses (ou la racine) pour
+    /// qu'on puisse lui donner un chemin ; sans entree `..` on remonte lexicalement.
+    fn resolve_physical(&self, path: &str) -> Result<Path, FileSystemError> {
+        let raw = Path::new_verbatim(path)?;
+        let mut names: Vec<String> = if raw.is_absolute() {
+            Vec::new()
         } else {
-            self.current_path.as_path().join(&Path::new(path)?)? //chemin relatif on le concatene au chemin courant
+            self.current_path.as_path().components().to_vec()
         };
-        
-        // retrouver le cluster
-        let dir_cluster = self.get_directory_cluster(&target_path)?;
-        
-        // retrouver la chaine a partir du premier cluster genre [5, 6, 7]
-        let chain = self.get_cluster_chain(dir_cluster)?;
-        
-        // mettre tout le contenu dans directory_data
-        let mut directory_data = Vec::new();
-        for &cluster_num in chain.clusters() {
-            let cluster_data = self.read_cluster(cluster_num)?;
-            directory_data.extend_from_slice(&cluster_data);
+        let prefix = |names: &[String]| {
+            let mut prefix = PathBuf::root();
+            for name in names {
+                prefix.push(name)?;
+            }
+            Ok::<Path, FileSystemError>(prefix.as_path().clone())
+        };
+
+        for component in raw.components() {
+            if component != ".." {
+                names.push(component.clone());
+                continue;
+            }
+            if names.is_empty() {
+                // la racine est son propre parent
+                continue;
+            }
+            let dir_cluster = self.get_directory_cluster(&prefix(&names)?)?;
+            let directory_data = self.read_directory_data(dir_cluster)?;
+            let Some(dotdot) = Directory::find_entry(&directory_data, "..")? else {
+                names.pop();
+                continue;
+            };
+            let parent_cluster = match dotdot.first_cluster() {
+                0 => self.root_dir_cluster(),
+                cluster => cluster,
+            };
+            // le dossier traverse le plus profond qui correspond a ce cluster
+            let mut depth = None;
+            for len in (0..names.len()).rev() {
+                if self.get_directory_cluster(&prefix(&names[..len])?)? == parent_cluster {
+                    depth = Some(len);
+                    break;
+                }
+            }
+            match depth {
+                Some(len) => names.truncate(len),
+                None => {
+                    let mut msg = String::from("Parent directory is not reachable from ");
+                    msg.push_str(&prefix(&names)?.to_string());
+                    return Err(FileSystemError::DirectoryNotFound(msg));
+                }
+            }
         }
-        
-        // Parse entries
-        unsafe {
-            Directory::read_entries(&chain, &directory_data) //convertir en structure directory (qui represente un dossier)
+        prefix(&names)
+    }
+
+    /// cherche l'entree d'un chemin : cluster du dossier parent et position de l'entree
+    fn lookup(&self, path: &Path) -> Result<(u32, DirSlot), FileSystemError> {
+        let file_name = path.file_name()
+            .ok_or_else(|| FileSystemError::FileNotFound(path.to_string()))?;
+        let parent_path = path.parent()
+            .ok_or_else(|| FileSystemError::DirectoryNotFound("Root directory".into()))?;
+        let parent_cluster = self.get_directory_cluster(&parent_path)?;
+        let directory_data = self.read_directory_data(parent_cluster)?;
+        let slot = Directory::find_slot(&directory_data, file_name)
+            .ok_or_else(|| FileSystemError::FileNotFound(path.to_string()))?;
+        Ok((parent_cluster, slot))
+    }
+
+    /// nombre de clusters utilisables (limite par la taille de la FAT)
+    pub(crate) fn max_cluster(&self) -> u32 {
+        (self.boot_sector.cluster_count() + 2).min(self.fat_table.len() as u32)
+    }
+
+    /// modifie une entree de la FAT en memoire et dans toutes les copies de l'image
+    fn set_fat_entry(&mut self, cluster: u32, value: u32) -> Result<(), FileSystemError> {
+        self.fat_table.set_entry(cluster, value)?;
+        let bps = self.boot_sector.bytes_per_sector() as usize;
+        let fat_size = self.boot_sector.sectors_per_fat() as usize * bps;
+        let mut device_data = self.device_data.borrow_mut();
+        for copy in 0..self.boot_sector.num_fats() as usize {
+            let start = self.boot_sector.fat_start_sector() as usize * bps + copy * fat_size;
+            let fat = device_data.get_mut(start..start + fat_size)
+                .ok_or_else(|| FileSystemError::InvalidFat("FAT table out of bounds".into()))?;
+            self.fat_table.encode_entry(cluster, fat)?;
         }
+        Ok(())
     }
-    
-    /// lire entierement un fichier
-    fn read_file(&self, path: &str) -> Result<Vec<u8>, FileSystemError> {
-        let target_path = if path.starts_with('/') {
-            Path::new(path)?
-        } else {
-            self.current_path.as_path().join(&Path::new(path)?)?
+
+    /// alloue `count` clusters chaines, renvoie le premier (None si count = 0)
+    fn allocate_chain(&mut self, count: usize) -> Result<Option<u32>, FileSystemError> {
+        if count == 0 {
+            return Ok(None);
+        }
+
+        // on cherche d'abord les clusters libres, pour ne rien modifier si le disque est plein
+        let max_cluster = self.max_cluster();
+        let mut clusters = Vec::with_capacity(count);
+        let mut candidate = self.next_free_cluster.max(2);
+        for _ in 2..max_cluster {
+            if candidate >= max_cluster {
+                candidate = 2;
+            }
+            if self.fat_table.is_free_cluster(candidate) {
+                clusters.push(candidate);
+                if clusters.len() == count {
+                    break;
+                }
+            }
+            candidate += 1;
+        }
+        if clusters.len() < count {
+            return Err(FileSystemError::IoError("No free cluster left on the volume".into()));
+        }
+
+        let end_of_chain = self.fat_type.end_of_chain_marker();
+        for (i, &cluster) in clusters.iter().enumerate() {
+            let next = clusters.get(i + 1).copied().unwrap_or(end_of_chain);
+            self.set_fat_entry(cluster, next)?;
+        }
+        self.next_free_cluster = clusters[count - 1] + 1;
+        self.update_fs_info(-(count as i64));
+        Ok(Some(clusters[0]))
+    }
+
+    /// libere une chaine de clusters
+    fn free_chain(&mut self, first_cluster: u32) -> Result<(), FileSystemError> {
+        if first_cluster < 2 {
+            return Ok(());
+        }
+        let chain = self.get_cluster_chain(first_cluster)?;
+        for &cluster in chain.clusters() {
+            self.set_fat_entry(cluster, 0)?;
+        }
+        self.next_free_cluster = self.next_free_cluster.min(first_cluster);
+        self.update_fs_info(chain.len() as i64);
+        Ok(())
+    }
+
+    /// agrandit un dossier d'un cluster vide (impossible pour la racine fixe FAT12/16)
+    fn extend_directory(&mut self, dir_cluster: u32) -> Result<(), FileSystemError> {
+        let dir_cluster = if dir_cluster == 0 { self.root_dir_cluster() } else { dir_cluster };
+        if dir_cluster == 0 {
+            return Err(FileSystemError::IoError("Root directory is full".into()));
+        }
+        let last = *self.get_cluster_chain(dir_cluster)?.clusters().last()
+            .ok_or_else(|| FileSystemError::ClusterChainError("Empty directory chain".into()))?;
+        let new_cluster = self.allocate_chain(1)?
+            .ok_or_else(|| FileSystemError::IoError("No free cluster left on the volume".into()))?;
+        self.write_cluster(new_cluster, &[])?;
+        self.set_fat_entry(last, new_cluster)
+    }
+
+    /// index de `count` entrees libres consecutives, en agrandissant le dossier si besoin
+    fn find_free_entries(&mut self, dir_cluster: u32, count: usize) -> Result<usize, FileSystemError> {
+        loop {
+            let directory_data = self.read_directory_data(dir_cluster)?;
+            match Directory::find_free_run(&directory_data, count) {
+                Some(index) => return Ok(index),
+                None => self.extend_directory(dir_cluster)?,
+            }
+        }
+    }
+
+    /// cree une entree (avec ses entrees LFN si le nom n'est pas un nom 8.3 majuscule)
+    ///
+    /// Le nom court de `entry` est remplace ; renvoie l'index de l'entree courte.
+    fn add_entry(&mut self, dir_cluster: u32, name: &str, mut entry: DirectoryEntry) -> Result<usize, FileSystemError> {
+        path::validate_component(name)?;
+        let directory_data = self.read_directory_data(dir_cluster)?;
+        let slots = Directory::scan(&directory_data);
+        if slots.iter().any(|slot| !slot.entry.is_volume_label() && slot.matches(name)) {
+            let mut msg = String::from(name);
+            msg.push_str(" already exists");
+            return Err(FileSystemError::DirectoryEntryError(msg));
+        }
+
+        let (short_name, lfn_entries) = match DirectoryEntry::encode_short_name(name) {
+            Some(short_name) => (short_name, Vec::new()),
+            None => {
+                // seule la casse differe : le nom en majuscules sert d'alias, sinon BASIS~N
+                let basis = DirectoryEntry::short_name_basis(name);
+                let taken = |candidate: &[u8; 11]| slots.iter().any(|slot| slot.entry.name == *candidate);
+                let lossless = DirectoryEntry::encode_short_name(&name.to_ascii_uppercase());
+                let short_name = lossless.into_iter()
+                    .chain((1..1_000_000).filter_map(|n| DirectoryEntry::with_numeric_tail(&basis, n)))
+                    .find(|candidate| !taken(candidate))
+                    .ok_or_else(|| FileSystemError::DirectoryEntryError("No short name alias available".into()))?;
+                let lfn_entries = LongFileNameEntry::entries_for(name, lfn_checksum(&short_name))?;
+                (short_name, lfn_entries)
+            }
         };
-        
-        // Get file name
+        entry.name = short_name;
+
+        let first_index = self.find_free_entries(dir_cluster, lfn_entries.len() + 1)?;
+        for (i, lfn) in lfn_entries.iter().enumerate() {
+            self.write_dir_entry(dir_cluster, first_index + i, lfn)?;
+        }
+        let index = first_index + lfn_entries.len();
+        self.write_dir_entry(dir_cluster, index, &entry.to_bytes())?;
+        Ok(index)
+    }
+
+    /// entree vide horodatee avec l'heure courante
+    fn new_entry(&self, attributes: Attributes) -> DirectoryEntry {
+        let now = self.time_provider.now();
+        DirectoryEntry {
+            name: [b' '; 11],
+            attributes: attributes.bits(),
+            nt_reserved: 0,
+            creation_time_tenths: now.fat_hundredths(),
+            creation_time: now.fat_time(),
+            creation_date: now.fat_date(),
+            last_access_date: now.fat_date(),
+            first_cluster_high: 0,
+            last_write_time: now.fat_time(),
+            last_write_date: now.fat_date(),
+            first_cluster_low: 0,
+            file_size: 0,
+        }
+    }
+
+    /// nouveau fichier horodate : les donnees sont ecrites avant l'entree, pour ne pas
+    /// laisser d'entree vide derriere une ecriture qui echoue (disque plein)
+    fn add_file(&mut self, target_path: &Path, data: &[u8]) -> Result<(), FileSystemError> {
         let file_name = target_path.file_name()
-            .ok_or_else(|| {
-                let path_str = target_path.to_string();
-                FileSystemError::FileNotFound(path_str)
-            })?;
-        
-        // Get parent directory
+            .ok_or_else(|| FileSystemError::InvalidPath("Cannot create the root directory".into()))?;
         let parent_path = target_path.parent()
-            .ok_or_else(|| FileSystemError::DirectoryNotFound("Root directory".into()))?;
-        
-        // Get parent directory cluster
+            .ok_or_else(|| FileSystemError::InvalidPath("Cannot create the root directory".into()))?;
         let parent_cluster = self.get_directory_cluster(&parent_path)?;
-        
-        // Read parent directory
-        let chain = self.get_cluster_chain(parent_cluster)?;
-        let mut directory_data = Vec::new();
-        for &cluster_num in chain.clusters() {
-            let cluster_data = self.read_cluster(cluster_num)?;
-            directory_data.extend_from_slice(&cluster_data);
+
+        let first_cluster = self.write_new_chain(data)?;
+        // ARCHIVE : fichier nouveau ou modifie depuis la derniere sauvegarde
+        let mut entry = self.new_entry(Attributes::ARCHIVE);
+        entry.set_first_cluster(first_cluster);
+        entry.file_size = data.len() as u32;
+        if let Err(e) = self.add_entry(parent_cluster, file_name, entry) {
+            self.free_chain(first_cluster)?;
+            return Err(e);
         }
-        
-        // Find file entry
-        let path_str = target_path.to_string();
-        let entry = Directory::find_entry(&directory_data, file_name)?
-            .ok_or_else(|| FileSystemError::FileNotFound(path_str.clone()))?;
-        
+        Ok(())
+    }
+
+    /// ecrit des donnees dans une nouvelle chaine, renvoie le premier cluster (0 si vide)
+    fn write_new_chain(&mut self, data: &[u8]) -> Result<u32, FileSystemError> {
+        let cluster_size = self.boot_sector.cluster_size() as usize;
+        let first = match self.allocate_chain(data.len().div_ceil(cluster_size))? {
+            Some(first) => first,
+            None => return Ok(0),
+        };
+        let chain = self.get_cluster_chain(first)?;
+        for (&cluster, chunk) in chain.clusters().iter().zip(data.chunks(cluster_size)) {
+            if let Err(e) = self.write_cluster(cluster, chunk) {
+                self.free_chain(first)?;
+                return Err(e);
+            }
+        }
+        Ok(first)
+    }
+
+    /// Iterateur sur les entrees d'un dossier, lues un cluster a la fois
+    ///
+    /// Contrairement a `list`, le dossier n'est jamais charge en entier : seul le
+    /// cluster courant (ou un secteur de la racine fixe FAT12/16) est en memoire.
+    pub fn read_dir(&self, path: &str) -> Result<ReadDir<'_>, FileSystemError> {
+        let target_path = self.resolve_path(path)?;
+        let dir_cluster = self.get_directory_cluster(&target_path)?;
+        Ok(self.read_dir_at(dir_cluster))
+    }
+
+    /// `read_dir` d'un dossier deja resolu (0 = racine fixe FAT12/16)
+    pub(crate) fn read_dir_at(&self, dir_cluster: u32) -> ReadDir<'_> {
+        let dir_cluster = if dir_cluster == 0 { self.root_dir_cluster() } else { dir_cluster };
+        let next_block = if dir_cluster == 0 {
+            DirBlock::RootSector(0)
+        } else {
+            DirBlock::Cluster(dir_cluster)
+        };
+        ReadDir {
+            fs: self,
+            next_block: Some(next_block),
+            blocks_read: 0,
+            buffer: Vec::new(),
+            position: 0,
+            scanner: DirScanner::new(),
+            finished: false,
+        }
+    }
+
+    /// Verifie le volume sans le modifier, comme `fsck -n`
+    ///
+    /// Chaines cassees ou partagees, tailles incoherentes, clusters perdus, copies
+    /// de la FAT differentes et compteur FSInfo faux : voir `FsckIssue`.
+    pub fn check(&self) -> Result<FsckReport, FileSystemError> {
+        fsck::check(self)
+    }
+
+    /// Parcours recursif a partir d'un dossier, en profondeur par defaut
+    ///
+    /// Voir `Walk` pour l'ordre, la profondeur maximale et les filtres.
+    pub fn walk(&self, path: &str) -> Result<Walk<'_>, FileSystemError> {
+        let target_path = self.resolve_path(path)?;
+        let dir_cluster = self.get_directory_cluster(&target_path)?;
+        Ok(Walk::new(self, PathBuf::from(target_path), dir_cluster))
+    }
+
+    /// Type de FAT detecte au montage
+    pub fn fat_type(&self) -> FatType {
+        self.fat_type
+    }
+
+    /// Get boot sector reference
+    pub fn boot_sector(&self) -> &BootSector {
+        &self.boot_sector
+    }
+
+    /// FAT chargee au montage (tenue a jour par les ecritures)
+    pub fn fat_table(&self) -> &FatTable {
+        &self.fat_table
+    }
+}
+
+impl FileSystem for Fat32Fs {
+    /// fonction qui liste les fichiers dossiers dans un chemin
+    ///
+    /// Construit tout le vecteur : pour un gros dossier, preferer `read_dir`.
+    fn list(&self, path: &str) -> Result<Vec<DirEntry>, FileSystemError> {
+        self.read_dir(path)?.collect()
+    }
+
+    /// lire entierement un fichier
+    fn read_file(&self, path: &str) -> Result<Vec<u8>, FileSystemError> {
+        let target_path = self.resolve_path(path)?;
+
+        // Find file entry in the parent directory
+        let (parent_cluster, slot) = self.lookup(&target_path)?;
+        let entry = slot.entry;
+
         if !entry.is_file() {
-            let mut msg = path_str;
+            let mut msg = target_path.to_string();
             msg.push_str(" is not a file");
             return Err(FileSystemError::FileNotFound(msg));
         }
-        
+
+        // date de dernier acces, si active (seul le jour est stocke)
+        if self.update_access_date {
+            let today = self.time_provider.now().fat_date();
+            if { entry.last_access_date } != today {
+                let mut updated = entry;
+                updated.last_access_date = today;
+                self.write_dir_entry(parent_cluster, slot.index, &updated.to_bytes())?;
+            }
+        }
+
         // Get first cluster
         let first_cluster = entry.first_cluster();
         if first_cluster == 0 {
             return Ok(Vec::new());
         }
-        
+
         // Get cluster chain
         let chain = self.get_cluster_chain(first_cluster)?;
-        
+
         // Read all file data
         let mut file_data = Vec::new();
         for &cluster_num in chain.clusters() {
             let cluster_data = self.read_cluster(cluster_num)?;
             file_data.extend_from_slice(&cluster_data);
         }
-        
+
         // Truncate to file size
         let file_size = entry.file_size() as usize;
         if file_data.len() > file_size {
             file_data.truncate(file_size);
         }
-        
+
         Ok(file_data)
     }
-    
+
     /// Change current directory
     fn cd(&mut self, path: &str) -> Result<(), FileSystemError> {
-        let target_path = if path.starts_with('/') {
-            Path::new(path)?
-        } else {
-            self.current_path.as_path().join(&Path::new(path)?)?
-        };
-        
+        let target_path = self.resolve_path(path)?;
+
         if target_path.is_root() {
             self.current_path = PathBuf::from(target_path);
             return Ok(());
         }
-        
+
         // Verify directory exists
         let dir_cluster = self.get_directory_cluster(&target_path)?;
-        let chain = self.get_cluster_chain(dir_cluster)?;
-        let mut directory_data = Vec::new();
-        for &cluster_num in chain.clusters() {
-            let cluster_data = self.read_cluster(cluster_num)?;
-            directory_data.extend_from_slice(&cluster_data);
-        }
-        
-        // Get directory name
+        self.read_directory_data(dir_cluster)?;
+
         let path_str = target_path.to_string();
-        let dir_name = target_path.file_name()
-            .ok_or_else(|| FileSystemError::DirectoryNotFound(path_str.clone()))?;
-        
-        // Get parent directory
-        let parent_path = target_path.parent()
-            .ok_or_else(|| FileSystemError::DirectoryNotFound("Root directory".into()))?;
-        
-        let parent_cluster = self.get_directory_cluster(&parent_path)?;
-        let parent_chain = self.get_cluster_chain(parent_cluster)?;
-        let mut parent_data = Vec::new();
-        for &cluster_num in parent_chain.clusters() {
-            let cluster_data = self.read_cluster(cluster_num)?;
-            parent_data.extend_from_slice(&cluster_data);
-        }
-        
-        let entry = Directory::find_entry(&parent_data, dir_name)?
-            .ok_or_else(|| FileSystemError::DirectoryNotFound(path_str.clone()))?;
-        
-        if !entry.is_directory() {
+        let (_, slot) = self.lookup(&target_path)
+            .map_err(|_| FileSystemError::DirectoryNotFound(path_str.clone()))?;
+
+        if !slot.entry.is_directory() {
             let mut msg = path_str;
             msg.push_str(" is not a directory");
             return Err(FileSystemError::DirectoryNotFound(msg));
         }
-        
+
         // Update current path
         self.current_path = PathBuf::from(target_path);
-        
+
         Ok(())
     }
-    
+
     /// Get current directory path
     fn pwd(&self) -> String {
         self.current_path.to_string()
     }
-    
-    /// Create a new file at the given path
+
+    /// Create a new empty file, stamped with the current time
     fn create_file(&mut self, path: &str) -> Result<(), FileSystemError> {
-        // Note: Full implementation requires FAT modification
-        // This is a placeholder that validates the path
-        let _target_path = if path.starts_with('/') {
-            Path::new(path)?
+        let target_path = self.resolve_path(path)?;
+        self.add_file(&target_path, &[])
+    }
+
+    /// Create an empty directory: one zeroed cluster holding its `.` and `..` entries
+    fn create_dir(&mut self, path: &str) -> Result<(), FileSystemError> {
+        let target_path = self.resolve_path(path)?;
+        let file_name = target_path.file_name()
+            .ok_or_else(|| FileSystemError::InvalidPath("Cannot create the root directory".into()))?;
+        let parent_path = target_path.parent()
+            .ok_or_else(|| FileSystemError::InvalidPath("Cannot create the root directory".into()))?;
+        let parent_cluster = self.get_directory_cluster(&parent_path)?;
+
+        let cluster = self.allocate_chain(1)?
+            .ok_or_else(|| FileSystemError::IoError("No free cluster left on the volume".into()))?;
+        self.write_cluster(cluster, &[])?;
+
+        let mut entry = self.new_entry(Attributes::DIRECTORY);
+        entry.set_first_cluster(cluster);
+        if let Err(e) = self.add_entry(parent_cluster, file_name, entry) {
+            self.free_chain(cluster)?;
+            return Err(e);
+        }
+
+        // `.` pointe sur le dossier, `..` sur le parent (0 si c'est la racine)
+        let parent = if parent_cluster == self.root_dir_cluster() { 0 } else { parent_cluster };
+        for (index, (name, first_cluster)) in [(*b".          ", cluster), (*b"..         ", parent)].into_iter().enumerate() {
+            let mut dot = entry;
+            dot.name = name;
+            dot.set_first_cluster(first_cluster);
+            self.write_dir_entry(cluster, index, &dot.to_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Replace the contents of a file (created if missing), stamping its modification time
+    fn write_file(&mut self, path: &str, data: &[u8]) -> Result<(), FileSystemError> {
+        let target_path = self.resolve_path(path)?;
+        if data.len() > u32::MAX as usize {
+            return Err(FileSystemError::Unsupported("FAT files are limited to 4 GiB".into()));
+        }
+
+        let (parent_cluster, slot) = match self.lookup(&target_path) {
+            Ok(found) => found,
+            Err(FileSystemError::FileNotFound(_)) => return self.add_file(&target_path, data),
+            Err(e) => return Err(e),
+        };
+        if !slot.entry.is_file() {
+            let mut msg = target_path.to_string();
+            msg.push_str(" is not a file");
+            return Err(FileSystemError::FileNotFound(msg));
+        }
+        Self::check_writable(&slot.entry, &target_path)?;
+
+        // nouvelle chaine d'abord, puis l'entree, et seulement ensuite on libere l'ancienne :
+        // en cas d'erreur (disque plein) le fichier garde son contenu
+        let mut entry = slot.entry;
+        let old_cluster = entry.first_cluster();
+        let first_cluster = self.write_new_chain(data)?;
+
+        let now = self.time_provider.now();
+        entry.set_first_cluster(first_cluster);
+        entry.file_size = data.len() as u32;
+        entry.attributes |= Attributes::ARCHIVE.bits();
+        entry.last_write_time = now.fat_time();
+        entry.last_write_date = now.fat_date();
+        entry.last_access_date = now.fat_date();
+        if let Err(e) = self.write_dir_entry(parent_cluster, slot.index, &entry.to_bytes()) {
+            self.free_chain(first_cluster)?;
+            return Err(e);
+        }
+        self.free_chain(old_cluster)
+    }
+
+    /// Delete a file: its entries are marked deleted (0xE5) and its clusters freed
+    fn remove_file(&mut self, path: &str) -> Result<(), FileSystemError> {
+        let target_path = self.resolve_path(path)?;
+        let (parent_cluster, slot) = self.lookup(&target_path)?;
+        if !slot.entry.is_file() {
+            let mut msg = target_path.to_string();
+            msg.push_str(" is not a file");
+            return Err(FileSystemError::FileNotFound(msg));
+        }
+        Self::check_writable(&slot.entry, &target_path)?;
+
+        // entree courte et entrees LFN qui la precedent
+        for index in slot.first_index..=slot.index {
+            let offset = self.dir_entry_offset(parent_cluster, index)?;
+            self.device_data.borrow_mut()[offset] = 0xE5;
+        }
+        self.free_chain(slot.entry.first_cluster())
+    }
+
+    /// Metadata from a single lookup of the path
+    fn metadata(&self, path: &str) -> Result<Metadata, FileSystemError> {
+        let target_path = self.resolve_path(path)?;
+        let cluster_size = self.boot_sector.cluster_size() as u64;
+
+        if target_path.is_root() {
+            let root_cluster = self.root_dir_cluster();
+            let allocated_size = if root_cluster == 0 {
+                self.fixed_root_region().1 as u64
+            } else {
+                self.get_cluster_chain(root_cluster)?.len() as u64 * cluster_size
+            };
+            return Ok(Metadata::root(root_cluster, allocated_size));
+        }
+
+        let (parent_cluster, slot) = self.lookup(&target_path)?;
+        let first_cluster = slot.entry.first_cluster();
+        let allocated_size = if first_cluster == 0 {
+            0
         } else {
-            self.current_path.as_path().join(&Path::new(path)?)?
+            self.get_cluster_chain(first_cluster)?.len() as u64 * cluster_size
         };
-        
-        Err(FileSystemError::Unsupported(
-            "File creation requires FAT modification which is not yet implemented".into()
-        ))
+        let location = EntryLocation { dir_cluster: parent_cluster, index: slot.index };
+        Metadata::from_entry(&slot.entry, slot.long_name, allocated_size, Some(location))
     }
-    
-    /// Write data to a file at the given path
-    fn write_file(&mut self, path: &str, data: &[u8]) -> Result<(), FileSystemError> {
-        // Note: Full implementation requires FAT and cluster modification
-        let _target_path = if path.starts_with('/') {
-            Path::new(path)?
+
+    /// Cherche dans les entrees brutes du dossier, sans construire toute la liste
+    ///
+    /// Un nom simple passe par `Directory::find_slot` comme `lookup` (entrees cachees
+    /// comprises), un motif par `Directory::find_matching` filtre comme `list`.
+    fn find_in_dir(&self, dir: &str, component: &str) -> Result<Vec<DirEntry>, FileSystemError> {
+        let target_path = self.resolve_path(dir)?;
+        let dir_cluster = self.get_directory_cluster(&target_path)?;
+        let directory_data = self.read_directory_data(dir_cluster)?;
+        let pattern = glob::is_pattern(component);
+        let slots = if pattern {
+            Directory::find_matching(&directory_data, component)
         } else {
-            self.current_path.as_path().join(&Path::new(path)?)?
+            Directory::find_slot(&directory_data, component).into_iter().collect()
         };
-        
-        let _ = data; // Suppress unused warning
-        Err(FileSystemError::Unsupported(
-            "File writing requires FAT and cluster modification which is not yet implemented".into()
-        ))
+
+        let mut entries = Vec::new();
+        for slot in slots {
+            let mut entry = DirEntry::new(slot.entry);
+            if let Some(long_name) = slot.long_name {
+                entry = entry.with_long_name(long_name);
+            }
+            if !pattern || self.is_listed(&entry) {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+}
+
+/// Prochain bloc d'un dossier a lire
+enum DirBlock {
+    /// cluster d'une chaine
+    Cluster(u32),
+    /// secteur numero n de la racine fixe FAT12/16
+    RootSector(usize),
+}
+
+/// Iterateur renvoye par `Fat32Fs::read_dir`
+///
+/// Une erreur de lecture (chaine cassee, cluster hors image) est renvoyee une
+/// fois, puis l'iteration s'arrete.
+pub struct ReadDir<'a> {
+    /// systeme de fichiers lu
+    fs: &'a Fat32Fs,
+    /// bloc suivant, None apres le dernier
+    next_block: Option<DirBlock>,
+    /// nombre de blocs deja lus, pour s'arreter sur une chaine en boucle
+    blocks_read: usize,
+    /// bloc courant
+    buffer: Vec<u8>,
+    /// position dans le bloc courant
+    position: usize,
+    /// garde les suites LFN a cheval sur deux blocs
+    scanner: DirScanner,
+    /// fin du dossier ou erreur
+    finished: bool,
+}
+
+impl ReadDir<'_> {
+    /// charge le bloc suivant, false s'il n'y en a plus
+    fn load_next_block(&mut self) -> Result<bool, FileSystemError> {
+        let fs = self.fs;
+        let block = match self.next_block.take() {
+            Some(block) => block,
+            None => return Ok(false),
+        };
+        if self.blocks_read >= fs.fat_table.len() {
+            return Err(FileSystemError::ClusterChainError("Cluster chain too long or circular".into()));
+        }
+        self.blocks_read += 1;
+
+        match block {
+            DirBlock::Cluster(cluster) => {
+                self.buffer = fs.read_cluster(cluster)?;
+                self.next_block = ClusterChain::next_cluster(&fs.fat_table, cluster)?.map(DirBlock::Cluster);
+            }
+            DirBlock::RootSector(sector) => {
+                let bps = fs.boot_sector.bytes_per_sector() as usize;
+                let (start, size) = fs.fixed_root_region();
+                let offset = start + sector * bps;
+                let device_data = fs.device_data.borrow();
+                if offset + bps > device_data.len() {
+                    return Err(FileSystemError::IoError("Root directory out of bounds".into()));
+                }
+                self.buffer = device_data[offset..offset + bps].to_vec();
+                if (sector + 1) * bps < size {
+                    self.next_block = Some(DirBlock::RootSector(sector + 1));
+                }
+            }
+        }
+        self.position = 0;
+        Ok(true)
+    }
+}
+
+impl Iterator for ReadDir<'_> {
+    type Item = Result<DirEntry, FileSystemError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.finished {
+            if self.position + 32 > self.buffer.len() {
+                match self.load_next_block() {
+                    Ok(true) => continue,
+                    Ok(false) => self.finished = true,
+                    Err(e) => {
+                        self.finished = true;
+                        return Some(Err(e));
+                    }
+                }
+                continue;
+            }
+
+            let chunk = &self.buffer[self.position..self.position + 32];
+            self.position += 32;
+            let slot = self.scanner.push(chunk);
+            if self.scanner.is_done() {
+                self.finished = true;
+            }
+            if let Some(slot) = slot {
+                // Skip volume labels
+                if slot.entry.is_volume_label() {
+                    continue;
+                }
+                let mut entry = DirEntry::new(slot.entry);
+                if let Some(long_name) = slot.long_name {
+                    entry = entry.with_long_name(long_name);
+                }
+                if self.fs.is_listed(&entry) {
+                    return Some(Ok(entry));
+                }
+            }
+        }
+        None
     }
 }
@@ -1,10 +1,79 @@
 use crate::fs::FileSystemError;
 use alloc::vec::Vec;
 
-/// FAT32 File Allocation Table
+/// Type de FAT, determine par le nombre de clusters de la zone de donnees
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FatType {
+    /// Entrees de 12 bits (disquettes, petits volumes)
+    Fat12,
+    /// Entrees de 16 bits
+    Fat16,
+    /// Entrees de 32 bits dont 28 utilises
+    Fat32,
+    /// Entrees de 32 bits completes (exFAT, jamais deduit du nombre de clusters)
+    ExFat,
+}
+
+impl FatType {
+    /// Seuils de la spec Microsoft : < 4085 clusters FAT12, < 65525 FAT16, sinon FAT32
+    pub fn from_cluster_count(cluster_count: u32) -> Self {
+        if cluster_count < 4085 {
+            FatType::Fat12
+        } else if cluster_count < 65525 {
+            FatType::Fat16
+        } else {
+            FatType::Fat32
+        }
+    }
+
+    /// Valeur ecrite pour marquer la fin d'une chaine
+    pub fn end_of_chain_marker(self) -> u32 {
+        match self {
+            FatType::Fat12 => 0x0FFF,
+            FatType::Fat16 => 0xFFFF,
+            FatType::Fat32 => 0x0FFF_FFFF,
+            FatType::ExFat => 0xFFFF_FFFF,
+        }
+    }
+
+    /// Valeur d'un cluster defectueux
+    pub fn bad_cluster_marker(self) -> u32 {
+        match self {
+            FatType::Fat12 => 0x0FF7,
+            FatType::Fat16 => 0xFFF7,
+            FatType::Fat32 => 0x0FFF_FFF7,
+            FatType::ExFat => 0xFFFF_FFF7,
+        }
+    }
+
+    /// valeur de FAT = fin de chaine ?
+    pub fn is_end_of_chain(self, value: u32) -> bool {
+        value > self.bad_cluster_marker()
+    }
+
+    /// valeur de FAT = cluster defectueux ?
+    pub fn is_bad_cluster(self, value: u32) -> bool {
+        value == self.bad_cluster_marker()
+    }
+}
+
+impl core::fmt::Display for FatType {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            FatType::Fat12 => write!(f, "FAT12"),
+            FatType::Fat16 => write!(f, "FAT16"),
+            FatType::Fat32 => write!(f, "FAT32"),
+            FatType::ExFat => write!(f, "exFAT"),
+        }
+    }
+}
+
+/// File Allocation Table (FAT12, FAT16 ou FAT32)
 pub struct FatTable {
     /// FAT entries (each entry is 32-bit, but only 28 bits are used, 4 reserves restent les bits hauts)
-    entries: Vec<u32>, // un tableau 
+    entries: Vec<u32>, // un tableau
+    /// Type de FAT, donne la taille des entrees et les valeurs speciales
+    fat_type: FatType,
 }
 
 // elf.entries → un tableau avec une case par cluster du disque.
@@ -13,55 +82,135 @@ pub struct FatTable {
 
 impl FatTable {
     /// Parse FAT table from raw bytes
-    /// 
-    /// # Documentation d'une fonction Unsafe
-    /// 
-    /// La data doit etre valide FAT32 data de 32bit. 
+    ///
+    /// # Safety
+    ///
+    /// La data doit etre valide FAT32 data de 32bit.
     /// on utilise pas les 4 premiers bits et on doit s'assurer de l'alignement avec de call cette fonction
     pub unsafe fn from_bytes(data: &[u8]) -> Result<Self, FileSystemError> { //remplir le tableau FatTable à partir de bits bruts
-        if data.len() % 4 != 0 {
-            return Err(FileSystemError::InvalidFat("FAT table size must be multiple of 4".into()));
-        }
-        
-        let mut entries = Vec::new();
-        entries.reserve(data.len() / 4);
-        
-        for chunk in data.chunks_exact(4) {
-            // FAT32 32 bits mais on utilise 24 donc masquer les 4 premiers
-            let entry = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]) & 0x0FFF_FFFF;
-            entries.push(entry);
-        }
-        
-        Ok(Self { entries })
+        Self::from_bytes_with_type(data, FatType::Fat32)
+    }
+
+    /// Parse FAT table from raw bytes for the given FAT type
+    ///
+    /// # Safety
+    ///
+    /// La data doit etre une copie de FAT valide du type donne : entrees de 12 bits
+    /// empaquetees deux par trois octets, de 16 bits ou de 32 bits little endian.
+    pub unsafe fn from_bytes_with_type(data: &[u8], fat_type: FatType) -> Result<Self, FileSystemError> {
+        let entries = match fat_type {
+            FatType::Fat12 => {
+                // deux entrees de 12 bits sont empaquetees dans 3 octets
+                let count = data.len() * 2 / 3;
+                let mut entries = Vec::with_capacity(count);
+                for n in 0..count {
+                    let offset = n + n / 2;
+                    let pair = u16::from_le_bytes([data[offset], data[offset + 1]]);
+                    let entry = if n % 2 == 1 { pair >> 4 } else { pair & 0x0FFF };
+                    entries.push(entry as u32);
+                }
+                entries
+            }
+            FatType::Fat16 => {
+                if !data.len().is_multiple_of(2) {
+                    return Err(FileSystemError::InvalidFat("FAT16 table size must be multiple of 2".into()));
+                }
+                data.chunks_exact(2)
+                    .map(|chunk| u16::from_le_bytes([chunk[0], chunk[1]]) as u32)
+                    .collect()
+            }
+            FatType::Fat32 | FatType::ExFat => {
+                if !data.len().is_multiple_of(4) {
+                    return Err(FileSystemError::InvalidFat("FAT table size must be multiple of 4".into()));
+                }
+
+                let mut entries = Vec::with_capacity(data.len() / 4);
+
+                for chunk in data.chunks_exact(4) {
+                    // FAT32 32 bits mais on utilise 24 donc masquer les 4 premiers (rien a masquer en exFAT)
+                    let entry = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]) & fat_type.end_of_chain_marker();
+                    entries.push(entry);
+                }
+                entries
+            }
+        };
+
+        Ok(Self { entries, fat_type })
     }
-    
-   
+
+
     /// prochain cluster ou valeur de fin de chaine
     pub fn get_entry(&self, cluster: u32) -> Result<u32, FileSystemError> {
         if cluster as usize >= self.entries.len() { //numero du cluster qu'on veut tester, self.entries vecteur de toutes les entrées FAT
             return Err(FileSystemError::InvalidFat("Cluster out of FAT bounds".into()));
         }
-        
+
         Ok(self.entries[cluster as usize])
     }
-    
+
+    /// Modifie une entree en memoire (les copies sur disque sont ecrites par `encode_entry`)
+    pub fn set_entry(&mut self, cluster: u32, value: u32) -> Result<(), FileSystemError> {
+        if cluster as usize >= self.entries.len() {
+            return Err(FileSystemError::InvalidFat("Cluster out of FAT bounds".into()));
+        }
+        self.entries[cluster as usize] = value & self.fat_type.end_of_chain_marker();
+        Ok(())
+    }
+
+    /// Ecrit l'entree d'un cluster dans une copie brute de la FAT
+    ///
+    /// Les 4 bits hauts d'une entree FAT32 sont reserves et gardes tels quels,
+    /// l'autre demi-octet d'une paire FAT12 aussi.
+    pub fn encode_entry(&self, cluster: u32, fat: &mut [u8]) -> Result<(), FileSystemError> {
+        let value = self.get_entry(cluster)?;
+        let n = cluster as usize;
+        let out_of_bounds = || FileSystemError::InvalidFat("Cluster out of FAT bounds".into());
+        match self.fat_type {
+            FatType::Fat12 => {
+                let offset = n + n / 2;
+                let bytes = fat.get_mut(offset..offset + 2).ok_or_else(out_of_bounds)?;
+                let mut pair = u16::from_le_bytes([bytes[0], bytes[1]]);
+                if n % 2 == 1 {
+                    pair = (pair & 0x000F) | ((value as u16) << 4);
+                } else {
+                    pair = (pair & 0xF000) | (value as u16 & 0x0FFF);
+                }
+                bytes.copy_from_slice(&pair.to_le_bytes());
+            }
+            FatType::Fat16 => {
+                let bytes = fat.get_mut(n * 2..n * 2 + 2).ok_or_else(out_of_bounds)?;
+                bytes.copy_from_slice(&(value as u16).to_le_bytes());
+            }
+            FatType::Fat32 => {
+                let bytes = fat.get_mut(n * 4..n * 4 + 4).ok_or_else(out_of_bounds)?;
+                let reserved = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) & 0xF000_0000;
+                bytes.copy_from_slice(&(reserved | value).to_le_bytes());
+            }
+            FatType::ExFat => {
+                let bytes = fat.get_mut(n * 4..n * 4 + 4).ok_or_else(out_of_bounds)?;
+                bytes.copy_from_slice(&value.to_le_bytes());
+            }
+        }
+        Ok(())
+    }
+
     /// cluster = fin de chaine ?
-    pub fn is_end_of_chain(&self, cluster: u32) -> bool { 
+    pub fn is_end_of_chain(&self, cluster: u32) -> bool {
         if cluster as usize >= self.entries.len() { // usize pour pouvoir l'utiliser en index
             return true;
         }
         let entry = self.entries[cluster as usize];
-        entry >= 0x0FFFFFF8
+        self.fat_type.is_end_of_chain(entry)
     }
-    
+
     /// Check if cluster is bad
     pub fn is_bad_cluster(&self, cluster: u32) -> bool {
         if cluster as usize >= self.entries.len() {
             return true;
         }
-        self.entries[cluster as usize] == 0x0FFFFFF7
+        self.fat_type.is_bad_cluster(self.entries[cluster as usize])
     }
-    
+
     /// cluster = free ?
     pub fn is_free_cluster(&self, cluster: u32) -> bool {
         if cluster as usize >= self.entries.len() {
@@ -69,11 +218,19 @@ impl FatTable {
         }
         self.entries[cluster as usize] == 0
     }
-    
+
     /// nb entrée en FAT
     pub fn len(&self) -> usize {
         self.entries.len()
     }
-}
 
+    /// FAT vide ?
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
 
+    /// Type de FAT de la table
+    pub fn fat_type(&self) -> FatType {
+        self.fat_type
+    }
+}
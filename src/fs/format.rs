@@ -0,0 +1,160 @@
+//! Formatage d'un volume FAT32 vide, sans std
+//!
+//! La geometrie suit la spec Microsoft (32 secteurs reserves, 2 FATs, racine au
+//! cluster 2, FSInfo au secteur 1, copie du boot sector au secteur 6). Aucune
+//! horloge ni hasard : a options identiques, image identique octet pour octet.
+
+use crate::fs::{Fat32Fs, FileSystemError};
+use crate::fs::fat_table::FatType;
+use crate::fs::time::{DateTime, FixedTimeProvider};
+use crate::fs::volume::{encode_volume_label, FsInfo};
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+const BYTES_PER_SECTOR: u32 = 512;
+const RESERVED_SECTORS: u32 = 32;
+const NUM_FATS: u32 = 2;
+const FS_INFO_SECTOR: u32 = 1;
+const BACKUP_BOOT_SECTOR: u32 = 6;
+const ROOT_CLUSTER: u32 = 2;
+
+/// Options de `format`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// Taille de l'image en octets (arrondie au secteur inferieur)
+    pub size: u64,
+    /// Label du volume, None pour "NO NAME"
+    pub label: Option<String>,
+    /// Numero de serie du volume
+    pub volume_id: u32,
+    /// Secteurs par cluster, None pour la valeur de la spec selon la taille
+    pub sectors_per_cluster: Option<u8>,
+    /// Date de l'entree label de la racine
+    pub timestamp: DateTime,
+}
+
+impl FormatOptions {
+    pub fn new(size: u64) -> Self {
+        Self {
+            size,
+            label: None,
+            volume_id: 0,
+            sectors_per_cluster: None,
+            timestamp: DateTime::FAT_EPOCH,
+        }
+    }
+
+    pub fn with_label(mut self, label: &str) -> Self {
+        self.label = Some(String::from(label));
+        self
+    }
+
+    pub fn with_volume_id(mut self, volume_id: u32) -> Self {
+        self.volume_id = volume_id;
+        self
+    }
+
+    pub fn with_sectors_per_cluster(mut self, sectors_per_cluster: u8) -> Self {
+        self.sectors_per_cluster = Some(sectors_per_cluster);
+        self
+    }
+
+    pub fn with_timestamp(mut self, timestamp: DateTime) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+}
+
+/// secteurs par cluster recommandes par Microsoft pour FAT32 (512 o/secteur)
+fn default_sectors_per_cluster(total_sectors: u32) -> u8 {
+    match total_sectors {
+        0..=532_480 => 1,           // 260 Mo
+        532_481..=16_777_216 => 8,  // 8 Go
+        16_777_217..=33_554_432 => 16,
+        33_554_433..=67_108_864 => 32,
+        _ => 64,
+    }
+}
+
+/// Image FAT32 vide (racine vide, label eventuel dans la racine et le BPB)
+pub fn format(options: &FormatOptions) -> Result<Vec<u8>, FileSystemError> {
+    let total_sectors = u32::try_from(options.size / BYTES_PER_SECTOR as u64)
+        .map_err(|_| FileSystemError::Unsupported("FAT32 volumes are limited to 2 TiB".into()))?;
+    let sectors_per_cluster = options.sectors_per_cluster.unwrap_or_else(|| default_sectors_per_cluster(total_sectors));
+    if sectors_per_cluster == 0 || !sectors_per_cluster.is_power_of_two() || sectors_per_cluster > 128 {
+        return Err(FileSystemError::InvalidBootSector("Invalid sectors per cluster".into()));
+    }
+    // valide avant d'ecrire quoi que ce soit
+    if let Some(label) = &options.label {
+        encode_volume_label(label)?;
+    }
+
+    // taille d'une FAT (formule de la spec : un peu plus que necessaire)
+    let spc = sectors_per_cluster as u32;
+    let data_and_fats = total_sectors.saturating_sub(RESERVED_SECTORS);
+    let per_fat_sector = (256 * spc + NUM_FATS) / 2;
+    let sectors_per_fat = data_and_fats.div_ceil(per_fat_sector);
+    let data_start = RESERVED_SECTORS + NUM_FATS * sectors_per_fat;
+    let cluster_count = total_sectors.saturating_sub(data_start) / spc;
+    if FatType::from_cluster_count(cluster_count) != FatType::Fat32 {
+        return Err(FileSystemError::Unsupported(
+            "Volume too small for FAT32 with this cluster size (65525 clusters minimum)".into(),
+        ));
+    }
+
+    let bps = BYTES_PER_SECTOR as usize;
+    let mut image = vec![0u8; total_sectors as usize * bps];
+
+    // boot sector
+    let boot = &mut image[..bps];
+    boot[0..3].copy_from_slice(&[0xEB, 0x58, 0x90]);
+    boot[3..11].copy_from_slice(b"MSWIN4.1");
+    boot[11..13].copy_from_slice(&(BYTES_PER_SECTOR as u16).to_le_bytes());
+    boot[13] = sectors_per_cluster;
+    boot[14..16].copy_from_slice(&(RESERVED_SECTORS as u16).to_le_bytes());
+    boot[16] = NUM_FATS as u8;
+    boot[21] = 0xF8; // disque fixe
+    boot[24..26].copy_from_slice(&63u16.to_le_bytes()); // secteurs par piste
+    boot[26..28].copy_from_slice(&255u16.to_le_bytes()); // tetes
+    boot[32..36].copy_from_slice(&total_sectors.to_le_bytes());
+    boot[36..40].copy_from_slice(&sectors_per_fat.to_le_bytes());
+    boot[44..48].copy_from_slice(&ROOT_CLUSTER.to_le_bytes());
+    boot[48..50].copy_from_slice(&(FS_INFO_SECTOR as u16).to_le_bytes());
+    boot[50..52].copy_from_slice(&(BACKUP_BOOT_SECTOR as u16).to_le_bytes());
+    boot[64] = 0x80;
+    boot[66] = 0x29;
+    boot[67..71].copy_from_slice(&options.volume_id.to_le_bytes());
+    boot[71..82].copy_from_slice(b"NO NAME    ");
+    boot[82..90].copy_from_slice(b"FAT32   ");
+    boot[510..512].copy_from_slice(&0xAA55u16.to_le_bytes());
+
+    // FSInfo : tous les clusters libres sauf la racine
+    let fs_info = &mut image[FS_INFO_SECTOR as usize * bps..][..bps];
+    fs_info[0..4].copy_from_slice(&FsInfo::LEAD_SIGNATURE.to_le_bytes());
+    fs_info[484..488].copy_from_slice(&FsInfo::STRUCT_SIGNATURE.to_le_bytes());
+    fs_info[508..512].copy_from_slice(&FsInfo::TRAIL_SIGNATURE.to_le_bytes());
+    FsInfo { free_count: Some(cluster_count - 1), next_free: Some(ROOT_CLUSTER + 1) }.write_to(fs_info);
+
+    // copies de secours du boot sector et de FSInfo
+    image.copy_within(0..2 * bps, BACKUP_BOOT_SECTOR as usize * bps);
+
+    // FATs : media, entree reservee, fin de chaine pour la racine
+    for copy in 0..NUM_FATS {
+        let start = (RESERVED_SECTORS + copy * sectors_per_fat) as usize * bps;
+        let fat = &mut image[start..start + 12];
+        fat[0..4].copy_from_slice(&0x0FFF_FFF8u32.to_le_bytes());
+        fat[4..8].copy_from_slice(&0x0FFF_FFFFu32.to_le_bytes());
+        fat[8..12].copy_from_slice(&0x0FFF_FFFFu32.to_le_bytes());
+    }
+
+    let Some(label) = &options.label else {
+        return Ok(image);
+    };
+    // Safety: le boot sector vient d'etre ecrit avec une geometrie FAT32 valide
+    let mut fs = unsafe { Fat32Fs::new(&image)? }
+        .with_time_provider(Box::new(FixedTimeProvider::new(options.timestamp)));
+    fs.set_label(label)?;
+    Ok(fs.into_device_data())
+}
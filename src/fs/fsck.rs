@@ -0,0 +1,241 @@
+use crate::fs::FileSystemError;
+use crate::fs::directory::Directory;
+use crate::fs::fat::Fat32Fs;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Probleme trouve par `Fat32Fs::check`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FsckIssue {
+    /// Une copie de la FAT differe de la premiere
+    FatCopyMismatch { copy: u8 },
+    /// Chaine de clusters illisible : boucle, cluster libre, defectueux ou hors limites
+    BrokenChain { path: String, reason: String },
+    /// Cluster deja utilise par une autre entree
+    CrossLinked { path: String, cluster: u32, other: String },
+    /// Taille du fichier incoherente avec la longueur de sa chaine
+    SizeMismatch { path: String, size: u32, clusters: usize },
+    /// Clusters alloues qu'aucune entree n'atteint
+    LostClusters { count: u32, first: u32 },
+    /// Compteur de clusters libres de FSInfo faux
+    FreeCountMismatch { stored: u32, actual: u32 },
+}
+
+impl FsckIssue {
+    /// Identifiant stable du type de probleme (`broken_chain`, `cross_linked`...)
+    pub fn kind(&self) -> &'static str {
+        match self {
+            FsckIssue::FatCopyMismatch { .. } => "fat_copy_mismatch",
+            FsckIssue::BrokenChain { .. } => "broken_chain",
+            FsckIssue::CrossLinked { .. } => "cross_linked",
+            FsckIssue::SizeMismatch { .. } => "size_mismatch",
+            FsckIssue::LostClusters { .. } => "lost_clusters",
+            FsckIssue::FreeCountMismatch { .. } => "free_count_mismatch",
+        }
+    }
+
+    /// Chemin de l'entree concernee, None pour les problemes du volume
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            FsckIssue::BrokenChain { path, .. }
+            | FsckIssue::CrossLinked { path, .. }
+            | FsckIssue::SizeMismatch { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for FsckIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FsckIssue::FatCopyMismatch { copy } => write!(f, "FAT copy {} differs from the first FAT", copy),
+            FsckIssue::BrokenChain { path, reason } => write!(f, "{}: {}", path, reason),
+            FsckIssue::CrossLinked { path, cluster, other } => {
+                write!(f, "{}: cluster {} is also used by {}", path, cluster, other)
+            }
+            FsckIssue::SizeMismatch { path, size, clusters } => {
+                let plural = if *clusters == 1 { "" } else { "s" };
+                write!(f, "{}: size is {} bytes but {} cluster{} allocated", path, size, clusters, plural)
+            }
+            FsckIssue::LostClusters { count, first } => {
+                let plural = if *count == 1 { "" } else { "s" };
+                write!(f, "{} lost cluster{} (first: {})", count, plural, first)
+            }
+            FsckIssue::FreeCountMismatch { stored, actual } => {
+                write!(f, "FSInfo free count is {}, actual {}", stored, actual)
+            }
+        }
+    }
+}
+
+/// Resultat de `Fat32Fs::check`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FsckReport {
+    /// Dossiers parcourus, racine comprise
+    pub directories: u32,
+    /// Fichiers trouves
+    pub files: u32,
+    /// Clusters atteints depuis la racine
+    pub reachable_clusters: u32,
+    /// Problemes, dans l'ordre ou ils ont ete trouves
+    pub issues: Vec<FsckIssue>,
+}
+
+impl FsckReport {
+    /// Aucun probleme trouve
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Verifie le volume sans rien modifier (voir `Fat32Fs::check`)
+pub(crate) fn check(fs: &Fat32Fs) -> Result<FsckReport, FileSystemError> {
+    let mut report = FsckReport::default();
+    check_fat_copies(fs, &mut report);
+
+    // cluster -> chemin de l'entree qui l'utilise
+    let mut owners: BTreeMap<u32, String> = BTreeMap::new();
+    let mut pending: Vec<(String, u32)> = Vec::new();
+    let root = fs.root_dir_cluster();
+    if root == 0 || claim_chain(fs, "/", root, &mut owners, &mut report).is_some() {
+        pending.push((String::from("/"), root));
+    }
+
+    let cluster_size = fs.boot_sector().cluster_size();
+    while let Some((dir_path, dir_cluster)) = pending.pop() {
+        // la chaine a deja ete verifiee par claim_chain, mais le dossier peut deborder de l'image
+        let data = match fs.read_directory_data(dir_cluster) {
+            Ok(data) => data,
+            Err(error) => {
+                report.issues.push(FsckIssue::BrokenChain { path: dir_path, reason: error.to_string() });
+                continue;
+            }
+        };
+        report.directories += 1;
+
+        for slot in Directory::scan(&data) {
+            let entry = &slot.entry;
+            if entry.is_volume_label() || &entry.name == b".          " || &entry.name == b"..         " {
+                continue;
+            }
+            let name = match slot.long_name {
+                Some(ref long_name) => long_name.clone(),
+                None => entry.short_name().unwrap_or_else(|_| String::from("?")),
+            };
+            let path = if dir_path == "/" { format!("/{}", name) } else { format!("{}/{}", dir_path, name) };
+            let first_cluster = entry.first_cluster();
+
+            if entry.is_directory() {
+                if first_cluster < 2 {
+                    report.issues.push(FsckIssue::BrokenChain { path, reason: "directory has no cluster".into() });
+                } else if claim_chain(fs, &path, first_cluster, &mut owners, &mut report).is_some() {
+                    pending.push((path, first_cluster));
+                }
+                continue;
+            }
+
+            report.files += 1;
+            let size = entry.file_size();
+            let expected = size.div_ceil(cluster_size) as usize;
+            let clusters = if first_cluster < 2 {
+                0
+            } else {
+                match claim_chain(fs, &path, first_cluster, &mut owners, &mut report) {
+                    Some(clusters) => clusters,
+                    None => continue,
+                }
+            };
+            if clusters != expected {
+                report.issues.push(FsckIssue::SizeMismatch { path, size, clusters });
+            }
+        }
+    }
+    report.reachable_clusters = owners.len() as u32;
+
+    // clusters alloues mais jamais atteints
+    let fat_table = fs.fat_table();
+    let mut lost = 0;
+    let mut first_lost = 0;
+    for cluster in 2..fs.max_cluster() {
+        if !fat_table.is_free_cluster(cluster) && !fat_table.is_bad_cluster(cluster) && !owners.contains_key(&cluster) {
+            if lost == 0 {
+                first_lost = cluster;
+            }
+            lost += 1;
+        }
+    }
+    if lost > 0 {
+        report.issues.push(FsckIssue::LostClusters { count: lost, first: first_lost });
+    }
+
+    let info = fs.volume_info();
+    if let Some(stored) = info.fs_info_free_clusters {
+        if stored != info.free_clusters {
+            report.issues.push(FsckIssue::FreeCountMismatch { stored, actual: info.free_clusters });
+        }
+    }
+    Ok(report)
+}
+
+/// compare chaque copie de la FAT a la premiere
+fn check_fat_copies(fs: &Fat32Fs, report: &mut FsckReport) {
+    let boot_sector = fs.boot_sector();
+    let bps = boot_sector.bytes_per_sector() as usize;
+    let fat_size = boot_sector.sectors_per_fat() as usize * bps;
+    let fat_start = boot_sector.fat_start_sector() as usize * bps;
+    let device_data = fs.device_data();
+    let Some(first) = device_data.get(fat_start..fat_start + fat_size) else {
+        return;
+    };
+    for copy in 1..boot_sector.num_fats() {
+        let start = fat_start + copy as usize * fat_size;
+        if device_data.get(start..start + fat_size) != Some(first) {
+            report.issues.push(FsckIssue::FatCopyMismatch { copy });
+        }
+    }
+}
+
+/// suit la chaine d'une entree et note ses clusters, renvoie sa longueur
+///
+/// None si la chaine est cassee ou partagee avec une autre entree (le probleme est
+/// alors ajoute au rapport et on ne descend pas dans le dossier).
+fn claim_chain(
+    fs: &Fat32Fs,
+    path: &str,
+    first_cluster: u32,
+    owners: &mut BTreeMap<u32, String>,
+    report: &mut FsckReport,
+) -> Option<usize> {
+    let broken = |reason: String| FsckIssue::BrokenChain { path: path.to_string(), reason };
+    let max_cluster = fs.max_cluster();
+    if first_cluster >= max_cluster {
+        report.issues.push(broken(format!("cluster {} out of range", first_cluster)));
+        return None;
+    }
+    if fs.fat_table().is_free_cluster(first_cluster) {
+        report.issues.push(broken(format!("starts on free cluster {}", first_cluster)));
+        return None;
+    }
+    let chain = match fs.get_cluster_chain(first_cluster) {
+        Ok(chain) => chain,
+        Err(error) => {
+            report.issues.push(broken(error.to_string()));
+            return None;
+        }
+    };
+    if let Some(&cluster) = chain.clusters().iter().find(|&&cluster| cluster >= max_cluster) {
+        report.issues.push(broken(format!("cluster {} out of range", cluster)));
+        return None;
+    }
+    if let Some((&cluster, other)) = chain.clusters().iter().find_map(|cluster| owners.get_key_value(cluster)) {
+        report.issues.push(FsckIssue::CrossLinked { path: path.to_string(), cluster, other: other.clone() });
+        return None;
+    }
+    for &cluster in chain.clusters() {
+        owners.insert(cluster, path.to_string());
+    }
+    Some(chain.len())
+}
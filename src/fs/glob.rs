@@ -0,0 +1,220 @@
+use crate::fs::{DirEntry, FileSystem, FileSystemError};
+use crate::fs::path::{Path, PathBuf};
+use alloc::collections::BTreeSet;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// Le texte contient-il un caractere joker (`*`, `?` ou `[`) ?
+pub fn is_pattern(text: &str) -> bool {
+    text.contains(['*', '?', '['])
+}
+
+/// Un nom correspond-il au motif, sans tenir compte de la casse (ASCII) comme FAT
+///
+/// `*` : n'importe quelle suite, `?` : un caractere, `[abc]`, `[a-z]`, `[!abc]` ou
+/// `[^abc]` : un caractere parmi (ou hors de) l'ensemble. `**` vaut `*` dans un nom.
+pub fn matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    matches_chars(&pattern, &name)
+}
+
+/// Un chemin correspond-il au motif ? `**` seul dans un composant couvre zero ou plusieurs dossiers
+pub fn matches_path(pattern: &Path, path: &Path) -> bool {
+    matches_components(pattern.components(), path.components())
+}
+
+fn matches_components(pattern: &[String], path: &[String]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((first, rest)) if first == "**" => {
+            (0..=path.len()).any(|skip| matches_components(rest, &path[skip..]))
+        }
+        Some((first, rest)) => match path.split_first() {
+            Some((component, path_rest)) => matches(first, component) && matches_components(rest, path_rest),
+            None => false,
+        },
+    }
+}
+
+/// comparaison avec retour arriere sur le dernier `*`
+fn matches_chars(pattern: &[char], name: &[char]) -> bool {
+    let (mut p, mut n) = (0, 0);
+    // position apres le dernier `*` et position du nom a ce moment
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        if p < pattern.len() {
+            match pattern[p] {
+                '*' => {
+                    while p < pattern.len() && pattern[p] == '*' {
+                        p += 1;
+                    }
+                    backtrack = Some((p, n));
+                    continue;
+                }
+                '?' => {
+                    p += 1;
+                    n += 1;
+                    continue;
+                }
+                '[' => {
+                    if let Some((matched, next)) = match_class(pattern, p, name[n]) {
+                        if matched {
+                            p = next;
+                            n += 1;
+                            continue;
+                        }
+                    } else if name[n] == '[' {
+                        // crochet non ferme : caractere ordinaire
+                        p += 1;
+                        n += 1;
+                        continue;
+                    }
+                }
+                c if c.eq_ignore_ascii_case(&name[n]) => {
+                    p += 1;
+                    n += 1;
+                    continue;
+                }
+                _ => {}
+            }
+        }
+        // echec : le dernier `*` absorbe un caractere de plus
+        match backtrack {
+            Some((star_p, star_n)) => {
+                p = star_p;
+                n = star_n + 1;
+                backtrack = Some((star_p, star_n + 1));
+            }
+            None => return false,
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// classe `[...]` a partir de `start` : (correspond, index apres `]`), None si pas de `]`
+fn match_class(pattern: &[char], start: usize, c: char) -> Option<(bool, usize)> {
+    let mut i = start + 1;
+    let negated = matches!(pattern.get(i), Some('!') | Some('^'));
+    if negated {
+        i += 1;
+    }
+    let c = c.to_ascii_uppercase();
+    let mut matched = false;
+    let mut first = true;
+    while i < pattern.len() {
+        // `]` juste apres `[` fait partie de l'ensemble
+        if pattern[i] == ']' && !first {
+            return Some((matched != negated, i + 1));
+        }
+        first = false;
+        let low = pattern[i].to_ascii_uppercase();
+        if pattern.get(i + 1) == Some(&'-') && pattern.get(i + 2).is_some_and(|&high| high != ']') {
+            let high = pattern[i + 2].to_ascii_uppercase();
+            matched |= low <= c && c <= high;
+            i += 3;
+        } else {
+            matched |= low == c;
+            i += 1;
+        }
+    }
+    None
+}
+
+/// Entrees d'un dossier dont le nom (long ou court) correspond a un composant
+///
+/// Implementation par defaut de `FileSystem::find_in_dir`, a partir de `list`.
+pub fn find_in_list<F: FileSystem + ?Sized>(fs: &F, dir: &str, component: &str) -> Result<Vec<DirEntry>, FileSystemError> {
+    let mut found = Vec::new();
+    for entry in fs.list(dir)? {
+        let name = entry.name()?;
+        let short_name = entry.entry.short_name()?;
+        let matched = if is_pattern(component) {
+            matches(component, &name) || matches(component, &short_name)
+        } else {
+            name.eq_ignore_ascii_case(component) || short_name.eq_ignore_ascii_case(component)
+        };
+        if matched {
+            found.push(entry);
+        }
+    }
+    Ok(found)
+}
+
+/// Developpe un motif de chemin (absolu ou relatif au dossier courant)
+///
+/// Chaque composant est cherche avec `FileSystem::find_in_dir` (par nom s'il n'a pas
+/// de joker), `**` parcourt les sous-dossiers. Un dossier deja visite via `**` (cycle)
+/// n'est pas reparcouru, et un chemin atteint plusieurs fois (`**/**`) n'est garde qu'une fois.
+pub fn expand<F: FileSystem + ?Sized>(fs: &F, pattern: &str) -> Result<Vec<(PathBuf, DirEntry)>, FileSystemError> {
+    let pattern = if pattern.starts_with('/') {
+        Path::new(pattern)?
+    } else {
+        Path::new(&fs.pwd())?.join(&Path::new(pattern)?)?
+    };
+    let components = pattern.components();
+
+    // dossiers en cours : chemin et entree (None pour le dossier de depart)
+    let mut current: Vec<(PathBuf, Option<DirEntry>)> = Vec::from([(PathBuf::root(), None)]);
+    for (i, component) in components.iter().enumerate() {
+        let last = i + 1 == components.len();
+        let mut next = Vec::new();
+
+        if component == "**" {
+            // un seul ensemble pour tous les dossiers de depart : un cycle n'est pas
+            // reparcouru depuis un autre depart (`**/**`)
+            let mut visited: BTreeSet<u32> = current.iter()
+                .filter_map(|(_, entry)| entry.as_ref().map(DirEntry::first_cluster))
+                .collect();
+            for (path, entry) in current {
+                collect_dirs(fs, path, entry, &mut visited, &mut next)?;
+            }
+        } else {
+            for (dir, _) in &current {
+                for entry in fs.find_in_dir(&dir.to_string(), component)? {
+                    let name = entry.name()?;
+                    if name == "." || name == ".." {
+                        continue;
+                    }
+                    if last || entry.is_directory() {
+                        let mut path = dir.clone();
+                        path.push(&name)?;
+                        next.push((path, Some(entry)));
+                    }
+                }
+            }
+        }
+        // `**` suivi de `**` (ou de dossiers deja couverts) atteint les memes chemins
+        let mut seen = BTreeSet::new();
+        next.retain(|(path, _)| seen.insert(path.to_string()));
+        current = next;
+    }
+
+    // `/` ou `**` en dernier peuvent laisser le dossier de depart, sans entree
+    Ok(current.into_iter().filter_map(|(path, entry)| entry.map(|entry| (path, entry))).collect())
+}
+
+/// un dossier et tous ses sous-dossiers, pour `**`
+///
+/// `visited` contient deja le dossier de depart ; un sous-dossier deja visite est saute.
+fn collect_dirs<F: FileSystem + ?Sized>(
+    fs: &F,
+    path: PathBuf,
+    entry: Option<DirEntry>,
+    visited: &mut BTreeSet<u32>,
+    out: &mut Vec<(PathBuf, Option<DirEntry>)>,
+) -> Result<(), FileSystemError> {
+    let children = fs.list(&path.to_string())?;
+    out.push((path.clone(), entry));
+    for child in children {
+        let name = child.name()?;
+        if !child.is_directory() || name == "." || name == ".." || !visited.insert(child.first_cluster()) {
+            continue;
+        }
+        let mut child_path = path.clone();
+        child_path.push(&name)?;
+        collect_dirs(fs, child_path, Some(child), visited, out)?;
+    }
+    Ok(())
+}
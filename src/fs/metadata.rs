@@ -0,0 +1,114 @@
+use crate::fs::FileSystemError;
+use crate::fs::entry::{Attributes, DirectoryEntry};
+use crate::fs::time::DateTime;
+use alloc::string::String;
+
+/// Nature d'une entree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileKind {
+    /// Fichier ordinaire
+    File,
+    /// Dossier (y compris la racine)
+    Directory,
+    /// Label de volume
+    VolumeLabel,
+}
+
+/// Position de l'entree courte sur disque
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EntryLocation {
+    /// Premier cluster du dossier parent (0 = racine fixe FAT12/16)
+    pub dir_cluster: u32,
+    /// Index de l'entree de 32 octets dans ce dossier
+    pub index: usize,
+}
+
+/// Informations sur un fichier ou un dossier, comme `stat`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Metadata {
+    /// Fichier, dossier ou label
+    pub kind: FileKind,
+    /// Taille en octets (0 pour un dossier)
+    pub size: u64,
+    /// Place occupee sur disque : nombre de clusters de la chaine * taille d'un cluster
+    pub allocated_size: u64,
+    /// Attributs de l'entree
+    pub attributes: Attributes,
+    /// Date de creation
+    pub created: Option<DateTime>,
+    /// Date de derniere modification
+    pub modified: Option<DateTime>,
+    /// Date de dernier acces
+    pub accessed: Option<DateTime>,
+    /// Premier cluster (0 pour un fichier vide)
+    pub first_cluster: u32,
+    /// Nom court 8.3 (`/` pour la racine)
+    pub short_name: String,
+    /// Nom long, s'il y en a un
+    pub long_name: Option<String>,
+    /// Position de l'entree, None pour la racine qui n'a pas d'entree
+    pub location: Option<EntryLocation>,
+}
+
+impl Metadata {
+    /// Construit les metadonnees d'une entree courte
+    pub fn from_entry(
+        entry: &DirectoryEntry,
+        long_name: Option<String>,
+        allocated_size: u64,
+        location: Option<EntryLocation>,
+    ) -> Result<Self, FileSystemError> {
+        let kind = if entry.is_volume_label() {
+            FileKind::VolumeLabel
+        } else if entry.is_directory() {
+            FileKind::Directory
+        } else {
+            FileKind::File
+        };
+        Ok(Self {
+            kind,
+            size: entry.file_size() as u64,
+            allocated_size,
+            attributes: entry.attributes(),
+            created: entry.created(),
+            modified: entry.modified(),
+            accessed: entry.accessed(),
+            first_cluster: entry.first_cluster(),
+            short_name: entry.short_name()?,
+            long_name,
+            location,
+        })
+    }
+
+    /// Metadonnees de la racine, qui n'a pas d'entree de repertoire
+    pub fn root(first_cluster: u32, allocated_size: u64) -> Self {
+        Self {
+            kind: FileKind::Directory,
+            size: 0,
+            allocated_size,
+            attributes: Attributes::DIRECTORY,
+            created: None,
+            modified: None,
+            accessed: None,
+            first_cluster,
+            short_name: String::from("/"),
+            long_name: None,
+            location: None,
+        }
+    }
+
+    /// Nom affiche : nom long si disponible, sinon nom court
+    pub fn name(&self) -> &str {
+        self.long_name.as_deref().unwrap_or(&self.short_name)
+    }
+
+    /// Fichier ordinaire ?
+    pub fn is_file(&self) -> bool {
+        self.kind == FileKind::File
+    }
+
+    /// Dossier ?
+    pub fn is_directory(&self) -> bool {
+        self.kind == FileKind::Directory
+    }
+}
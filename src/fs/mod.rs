@@ -5,14 +5,33 @@ pub mod cluster;
 pub mod directory;
 pub mod entry;
 pub mod path;
+pub mod exfat;
+pub mod time;
+pub mod metadata;
+pub mod walk;
+pub mod volume;
+pub mod glob;
+pub mod format;
+pub mod fsck;
+#[cfg(feature = "tar")]
+pub mod tar;
 
 pub use boot::BootSector;
-pub use fat_table::FatTable;
-pub use fat::Fat32Fs;
+pub use fat_table::{FatTable, FatType};
+pub use fat::{Fat32Fs, ReadDir};
 pub use cluster::ClusterChain;
-pub use directory::Directory;
-pub use entry::{DirEntry, DirectoryEntry, LongFileNameEntry};
-pub use path::{Path, PathBuf, PathError};
+pub use directory::{DirScanner, Directory};
+pub use entry::{Attributes, DirEntry, DirectoryEntry, LongFileNameEntry};
+pub use path::{Ancestors, Path, PathBuf, PathError};
+pub use exfat::ExFatFs;
+pub use metadata::{EntryLocation, FileKind, Metadata};
+pub use volume::{FsInfo, VolumeInfo};
+pub use format::FormatOptions;
+pub use fsck::{FsckIssue, FsckReport};
+pub use walk::{Walk, WalkEntry, WalkOrder};
+pub use time::{DateTime, FixedTimeProvider, TimeProvider};
+#[cfg(feature = "std")]
+pub use time::SystemTimeProvider;
 
 use alloc::vec::Vec;
 use alloc::string::String;
@@ -36,6 +55,32 @@ pub trait FileSystem {
     
     /// Write data to a file at the given path
     fn write_file(&mut self, path: &str, data: &[u8]) -> Result<(), FileSystemError>;
+
+    /// Create an empty directory (with its `.` and `..` entries)
+    fn create_dir(&mut self, path: &str) -> Result<(), FileSystemError>;
+
+    /// Delete a file and free its clusters
+    fn remove_file(&mut self, path: &str) -> Result<(), FileSystemError>;
+
+    /// Get kind, sizes, attributes, timestamps and names of a file or directory
+    fn metadata(&self, path: &str) -> Result<Metadata, FileSystemError>;
+
+    /// Check whether a path exists
+    fn exists(&self, path: &str) -> bool {
+        self.metadata(path).is_ok()
+    }
+
+    /// Entries of a directory matching one path component: a glob pattern or a plain name
+    ///
+    /// The default filters `list`; `Fat32Fs` matches the raw directory entries instead.
+    fn find_in_dir(&self, dir: &str, component: &str) -> Result<Vec<DirEntry>, FileSystemError> {
+        glob::find_in_list(self, dir, component)
+    }
+
+    /// Expand a path pattern (`*`, `?`, `[...]`, `**`), case-insensitively
+    fn glob(&self, pattern: &str) -> Result<Vec<(PathBuf, DirEntry)>, FileSystemError> {
+        glob::expand(self, pattern)
+    }
 }
 
 /// Filesystem errors
@@ -61,6 +106,8 @@ pub enum FileSystemError {
     OutOfMemory,
     /// Unsupported feature
     Unsupported(String),
+    /// Entry is read-only
+    ReadOnly(String),
 }
 
 impl core::fmt::Display for FileSystemError {
@@ -76,6 +123,26 @@ impl core::fmt::Display for FileSystemError {
             FileSystemError::IoError(msg) => write!(f, "I/O error: {}", msg),
             FileSystemError::OutOfMemory => write!(f, "Out of memory"),
             FileSystemError::Unsupported(msg) => write!(f, "Unsupported: {}", msg),
+            FileSystemError::ReadOnly(msg) => write!(f, "Read-only: {}", msg),
+        }
+    }
+}
+
+impl FileSystemError {
+    /// Code stable de la variante, pour les sorties machine (`--json`)
+    pub fn code(&self) -> &'static str {
+        match self {
+            FileSystemError::InvalidPath(_) => "invalid_path",
+            FileSystemError::FileNotFound(_) => "file_not_found",
+            FileSystemError::DirectoryNotFound(_) => "directory_not_found",
+            FileSystemError::InvalidFat(_) => "invalid_fat",
+            FileSystemError::InvalidBootSector(_) => "invalid_boot_sector",
+            FileSystemError::ClusterChainError(_) => "cluster_chain_error",
+            FileSystemError::DirectoryEntryError(_) => "directory_entry_error",
+            FileSystemError::IoError(_) => "io_error",
+            FileSystemError::OutOfMemory => "out_of_memory",
+            FileSystemError::Unsupported(_) => "unsupported",
+            FileSystemError::ReadOnly(_) => "read_only",
         }
     }
 }
@@ -86,6 +153,7 @@ impl From<PathError> for FileSystemError {
             PathError::InvalidFormat(s) => s,
             PathError::Empty => "Empty path".into(),
             PathError::ComponentTooLong => "Path component too long".into(),
+            PathError::IllegalCharacter(c) => alloc::format!("Illegal character in name: {:?}", c),
         };
         FileSystemError::InvalidPath(msg)
     }
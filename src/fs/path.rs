@@ -10,6 +10,8 @@ pub enum PathError {
     Empty,
     /// Path component too long
     ComponentTooLong,
+    /// Caractere interdit par FAT dans un nom (`"*/:<>?\|` ou caractere de controle)
+    IllegalCharacter(char),
 }
 
 impl core::fmt::Display for PathError {
@@ -18,10 +20,28 @@ impl core::fmt::Display for PathError {
             PathError::InvalidFormat(msg) => write!(f, "Invalid path format: {}", msg),
             PathError::Empty => write!(f, "Empty path"),
             PathError::ComponentTooLong => write!(f, "Path component too long"),
+            PathError::IllegalCharacter(c) => write!(f, "Illegal character in path: {:?}", c),
         }
     }
 }
 
+/// Caracteres interdits dans un nom long FAT, en plus des caracteres de controle
+const ILLEGAL_CHARS: &str = "\"*/:<>?\\|";
+
+/// Verifie qu'un nom de fichier ou de dossier est accepte par FAT
+pub fn validate_component(name: &str) -> Result<(), PathError> {
+    if name.is_empty() {
+        return Err(PathError::InvalidFormat("Empty component".into()));
+    }
+    if name.len() > 255 {
+        return Err(PathError::ComponentTooLong);
+    }
+    match name.chars().find(|&c| c.is_ascii_control() || ILLEGAL_CHARS.contains(c)) {
+        Some(c) => Err(PathError::IllegalCharacter(c)),
+        None => Ok(()),
+    }
+}
+
 /// Path representation for FAT32 filesystem
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Path {
@@ -33,7 +53,14 @@ pub struct Path {
 
 impl Path {
     /// Create a new path from a string
+    ///
+    /// Les `..` sont resolus lexicalement (voir `normalize`) : `/a/../b` donne `/b`.
     pub fn new(path_str: &str) -> Result<Self, PathError> {
+        Ok(Self::new_verbatim(path_str)?.normalize())
+    }
+
+    /// Comme `new` mais garde les `..` tels quels, pour les resoudre sur disque
+    pub fn new_verbatim(path_str: &str) -> Result<Self, PathError> {
         if path_str.is_empty() {
             return Err(PathError::Empty);
         }
@@ -106,23 +133,12 @@ impl Path {
         
         let mut new_components = self.components.clone();
         new_components.extend_from_slice(other.components());
-        
-        // Resolve ".." components
-        let mut resolved = Vec::new();
-        for component in new_components {
-            if component == ".." {
-                if !resolved.is_empty() {
-                    resolved.pop();
-                }
-            } else {
-                resolved.push(component);
-            }
-        }
-        
+
+        // meme regle que new : un chemin absolu ne remonte pas au-dessus de la racine
         Ok(Self {
-            components: resolved,
+            components: new_components,
             absolute: self.absolute,
-        })
+        }.normalize())
     }
     
     /// Get parent path
@@ -144,20 +160,160 @@ impl Path {
     pub fn file_name(&self) -> Option<&String> {
         self.components.last()
     }
-    
-    /// Convert to string representation
-    pub fn to_string(&self) -> String {
-        let mut result = String::new();
-        if self.absolute {
-            result.push('/');
+
+    /// Un composant contient-il un joker (`*`, `?`, `[`) ?
+    pub fn is_pattern(&self) -> bool {
+        self.components.iter().any(|component| crate::fs::glob::is_pattern(component))
+    }
+
+    /// Le chemin correspond-il au motif (`**` couvre plusieurs dossiers) ?
+    pub fn matches(&self, pattern: &Path) -> bool {
+        crate::fs::glob::matches_path(pattern, self)
+    }
+
+    /// Nom sans la derniere extension (`notes.tar` pour `notes.tar.gz`)
+    ///
+    /// Comme `std::path` : un nom qui commence par `.` sans autre point n'a pas d'extension.
+    pub fn file_stem(&self) -> Option<&str> {
+        let name = self.file_name()?;
+        match name.rfind('.') {
+            Some(0) | None => Some(name),
+            Some(dot) => Some(&name[..dot]),
         }
-        for (i, component) in self.components.iter().enumerate() {
-            if i > 0 {
-                result.push('/');
+    }
+
+    /// Extension du nom, sans le point
+    pub fn extension(&self) -> Option<&str> {
+        let name = self.file_name()?;
+        match name.rfind('.') {
+            Some(0) | None => None,
+            Some(dot) => Some(&name[dot + 1..]),
+        }
+    }
+
+    /// Meme chemin avec une autre extension (supprimee si `extension` est vide)
+    pub fn with_extension(&self, extension: &str) -> Self {
+        let mut path = self.clone();
+        let Some(stem) = self.file_stem() else {
+            return path;
+        };
+        let mut name = String::from(stem);
+        if !extension.is_empty() {
+            name.push('.');
+            name.push_str(extension);
+        }
+        if let Some(last) = path.components.last_mut() {
+            *last = name;
+        }
+        path
+    }
+
+    /// `base` est-il un prefixe de ce chemin, composant par composant ?
+    ///
+    /// La casse est ignoree comme sur FAT : `/DOCS/a.txt` commence par `/docs`.
+    pub fn starts_with(&self, base: &Path) -> bool {
+        self.absolute == base.absolute
+            && base.components.len() <= self.components.len()
+            && base.components.iter().zip(&self.components).all(|(a, b)| a.eq_ignore_ascii_case(b))
+    }
+
+    /// Chemin relatif restant apres le prefixe `base`
+    pub fn strip_prefix(&self, base: &Path) -> Result<Self, PathError> {
+        if !self.starts_with(base) {
+            return Err(PathError::InvalidFormat("Not a prefix of this path".into()));
+        }
+        Ok(Self {
+            components: self.components[base.components.len()..].to_vec(),
+            absolute: false,
+        })
+    }
+
+    /// Ce chemin puis chacun de ses parents, jusqu'a la racine
+    pub fn ancestors(&self) -> Ancestors {
+        Ancestors { next: Some(self.clone()) }
+    }
+
+    /// Composants du chemin, sous forme de `&str`
+    pub fn iter(&self) -> Iter<'_> {
+        Iter { inner: self.components.iter() }
+    }
+
+    /// Resout `..` lexicalement : il retire le composant precedent
+    ///
+    /// Un chemin absolu ne remonte jamais au-dessus de la racine ; un chemin
+    /// relatif garde les `..` de tete, qui dependent du dossier de depart.
+    pub fn normalize(&self) -> Self {
+        let mut components: Vec<String> = Vec::with_capacity(self.components.len());
+        for component in &self.components {
+            if component != ".." {
+                components.push(component.clone());
+            } else if components.last().is_some_and(|last| last != "..") {
+                components.pop();
+            } else if !self.absolute {
+                components.push(component.clone());
             }
-            result.push_str(component);
         }
-        result
+        Self {
+            components,
+            absolute: self.absolute,
+        }
+    }
+
+    /// Verifie que chaque nom (hors `..`) est accepte par FAT
+    pub fn validate(&self) -> Result<(), PathError> {
+        self.components
+            .iter()
+            .filter(|component| *component != "..")
+            .try_for_each(|component| validate_component(component))
+    }
+}
+
+/// Iterateur sur les composants d'un chemin, voir [`Path::iter`]
+pub struct Iter<'a> {
+    inner: core::slice::Iter<'a, String>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        self.inner.next().map(String::as_str)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Iter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(String::as_str)
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl<'a> IntoIterator for &'a Path {
+    type Item = &'a str;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+/// Iterateur sur un chemin et ses parents, voir [`Path::ancestors`]
+pub struct Ancestors {
+    next: Option<Path>,
+}
+
+impl Iterator for Ancestors {
+    type Item = Path;
+
+    fn next(&mut self) -> Option<Path> {
+        let current = self.next.take()?;
+        self.next = current.parent();
+        Some(current)
     }
 }
 
@@ -203,11 +359,6 @@ impl PathBuf {
             false
         }
     }
-    
-    /// Convert to string
-    pub fn to_string(&self) -> String {
-        self.path.to_string()
-    }
 }
 
 impl From<Path> for PathBuf {
@@ -216,14 +367,24 @@ impl From<Path> for PathBuf {
     }
 }
 
+/// Representation texte (`/` separateur, `/` en tete si absolu)
 impl core::fmt::Display for Path {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "{}", self.to_string())
+        if self.absolute {
+            f.write_str("/")?;
+        }
+        for (i, component) in self.components.iter().enumerate() {
+            if i > 0 {
+                f.write_str("/")?;
+            }
+            f.write_str(component)?;
+        }
+        Ok(())
     }
 }
 
 impl core::fmt::Display for PathBuf {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "{}", self.path.to_string())
+        write!(f, "{}", self.path)
     }
 }
@@ -0,0 +1,254 @@
+//! Archive tar (POSIX ustar, en-tetes pax pour les noms longs), sans std
+//!
+//! `export` parcourt une arborescence de n'importe quel `FileSystem` et renvoie
+//! l'archive : dossiers puis leur contenu, dates de modification decodees des
+//! entrees, noms longs (LFN) conserves.
+
+use crate::fs::{FileSystem, FileSystemError};
+use crate::fs::path::{Path, PathBuf};
+use crate::fs::time::DateTime;
+use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// Taille d'un bloc tar
+pub const BLOCK_SIZE: usize = 512;
+
+/// Type d'une entree de l'archive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TarEntryKind {
+    File,
+    Directory,
+}
+
+/// Construit une archive tar en memoire
+#[derive(Debug, Default)]
+pub struct TarWriter {
+    data: Vec<u8>,
+    entries: usize,
+}
+
+impl TarWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ajoute un fichier ; `path` est relatif (`DCIM/IMG_0001.JPG`)
+    pub fn append_file(&mut self, path: &str, data: &[u8], mode: u32, mtime: u64) -> Result<(), FileSystemError> {
+        self.append(path, TarEntryKind::File, data, mode, mtime)
+    }
+
+    /// Ajoute un dossier (le `/` final est ajoute si besoin)
+    pub fn append_dir(&mut self, path: &str, mode: u32, mtime: u64) -> Result<(), FileSystemError> {
+        let mut path = String::from(path.trim_end_matches('/'));
+        path.push('/');
+        self.append(&path, TarEntryKind::Directory, &[], mode, mtime)
+    }
+
+    fn append(&mut self, path: &str, kind: TarEntryKind, data: &[u8], mode: u32, mtime: u64) -> Result<(), FileSystemError> {
+        if path.is_empty() || path.starts_with('/') {
+            return Err(FileSystemError::InvalidPath(format!("Invalid archive path: {:?}", path)));
+        }
+        if data.len() as u64 > 0o77777777777 {
+            return Err(FileSystemError::Unsupported("tar entries are limited to 8 GiB".into()));
+        }
+
+        // nom trop long pour ustar (ou pas en ASCII) : en-tete pax avec le chemin complet
+        let (prefix, name) = match split_ustar_name(path) {
+            Some(split) => split,
+            None => {
+                let record = pax_record("path", path);
+                let mut pax_name = String::from("PaxHeaders/");
+                pax_name.extend(path.trim_end_matches('/').rsplit('/').next().unwrap_or("").chars().filter(char::is_ascii).take(80));
+                self.write_header(&pax_name, "", b'x', record.len() as u64, 0o644, mtime);
+                self.write_data(record.as_bytes());
+                ("", truncate_ascii(path, 100))
+            }
+        };
+
+        let typeflag = match kind {
+            TarEntryKind::File => b'0',
+            TarEntryKind::Directory => b'5',
+        };
+        self.write_header(name, prefix, typeflag, data.len() as u64, mode, mtime);
+        self.write_data(data);
+        self.entries += 1;
+        Ok(())
+    }
+
+    fn write_header(&mut self, name: &str, prefix: &str, typeflag: u8, size: u64, mode: u32, mtime: u64) {
+        let mut header = [0u8; BLOCK_SIZE];
+        header[..name.len()].copy_from_slice(name.as_bytes());
+        write_octal(&mut header[100..108], mode as u64);
+        write_octal(&mut header[108..116], 0); // uid
+        write_octal(&mut header[116..124], 0); // gid
+        write_octal(&mut header[124..136], size);
+        write_octal(&mut header[136..148], mtime.min(0o77777777777));
+        header[156] = typeflag;
+        header[257..263].copy_from_slice(b"ustar\0");
+        header[263..265].copy_from_slice(b"00");
+        header[345..345 + prefix.len()].copy_from_slice(prefix.as_bytes());
+
+        // somme des octets, le champ checksum compte comme 8 espaces
+        header[148..156].fill(b' ');
+        let checksum: u32 = header.iter().map(|&b| b as u32).sum();
+        write_octal(&mut header[148..155], checksum as u64);
+        header[155] = b' ';
+        self.data.extend_from_slice(&header);
+    }
+
+    /// donnees completees a un multiple de 512 octets
+    fn write_data(&mut self, data: &[u8]) {
+        self.data.extend_from_slice(data);
+        let padding = (BLOCK_SIZE - data.len() % BLOCK_SIZE) % BLOCK_SIZE;
+        self.data.resize(self.data.len() + padding, 0);
+    }
+
+    /// Nombre d'entrees ajoutees (sans les en-tetes pax)
+    pub fn entries(&self) -> usize {
+        self.entries
+    }
+
+    /// Termine l'archive (deux blocs vides) et la renvoie
+    pub fn finish(mut self) -> Vec<u8> {
+        self.data.resize(self.data.len() + 2 * BLOCK_SIZE, 0);
+        self.data
+    }
+}
+
+/// nombre en octal sur tout le champ sauf le dernier octet (NUL)
+fn write_octal(field: &mut [u8], value: u64) {
+    let digits = field.len() - 1;
+    for (i, byte) in field[..digits].iter_mut().enumerate() {
+        let shift = 3 * (digits - 1 - i);
+        *byte = b'0' + ((value >> shift) & 7) as u8;
+    }
+    field[digits] = 0;
+}
+
+/// decoupe un chemin ASCII en (prefix <= 155, name <= 100) pour ustar
+fn split_ustar_name(path: &str) -> Option<(&str, &str)> {
+    if !path.is_ascii() {
+        return None;
+    }
+    if path.len() <= 100 {
+        return Some(("", path));
+    }
+    // le `/` final d'un dossier reste dans name
+    let body = path.trim_end_matches('/');
+    body.match_indices('/')
+        .map(|(i, _)| (&path[..i], &path[i + 1..]))
+        .find(|(prefix, name)| prefix.len() <= 155 && name.len() <= 100 && !name.is_empty())
+}
+
+/// les `max` premiers octets ASCII (nom de repli pour les lecteurs sans pax)
+fn truncate_ascii(path: &str, max: usize) -> &str {
+    let mut end = 0;
+    for (i, c) in path.char_indices() {
+        if !c.is_ascii() || i + 1 > max {
+            break;
+        }
+        end = i + 1;
+    }
+    &path[..end]
+}
+
+/// enregistrement pax `"<longueur> <cle>=<valeur>\n"`, la longueur se comptant elle-meme
+fn pax_record(key: &str, value: &str) -> String {
+    let base = key.len() + value.len() + 3;
+    let mut length = base;
+    loop {
+        let total = base + decimal_digits(length);
+        if total == length {
+            return format!("{} {}={}\n", length, key, value);
+        }
+        length = total;
+    }
+}
+
+fn decimal_digits(mut n: usize) -> usize {
+    let mut digits = 1;
+    while n >= 10 {
+        n /= 10;
+        digits += 1;
+    }
+    digits
+}
+
+/// date tar (Unix) d'une date FAT, 0 si absente
+fn mtime(date: Option<DateTime>) -> u64 {
+    date.map(|date| date.to_unix_timestamp()).unwrap_or(0)
+}
+
+/// droits : 0755 pour un dossier, 0644 pour un fichier (0444 en lecture seule)
+fn mode(directory: bool, read_only: bool) -> u32 {
+    match (directory, read_only) {
+        (true, _) => 0o755,
+        (false, true) => 0o444,
+        (false, false) => 0o644,
+    }
+}
+
+/// Archive tar d'un fichier ou d'un dossier et de tout son contenu
+///
+/// Les chemins de l'archive commencent par le nom de `root` (`LOGS/boot.txt`),
+/// ou directement par son contenu si `root` est la racine.
+pub fn export<F: FileSystem + ?Sized>(fs: &F, root: &str) -> Result<Vec<u8>, FileSystemError> {
+    let root_path = if root.starts_with('/') {
+        Path::new(root)?
+    } else {
+        Path::new(&fs.pwd())?.join(&Path::new(root)?)?
+    };
+    let mut writer = TarWriter::new();
+
+    if root_path.is_root() {
+        let mut visited = BTreeSet::new();
+        append_children(fs, &PathBuf::root(), "", &mut visited, &mut writer)?;
+        return Ok(writer.finish());
+    }
+
+    let meta = fs.metadata(&root_path.to_string())?;
+    let name = meta.name();
+    if meta.is_directory() {
+        let mut visited = BTreeSet::from([meta.first_cluster]);
+        writer.append_dir(name, mode(true, false), mtime(meta.modified))?;
+        append_children(fs, &PathBuf::from(root_path.clone()), name, &mut visited, &mut writer)?;
+    } else {
+        let data = fs.read_file(&root_path.to_string())?;
+        writer.append_file(name, &data, mode(false, meta.attributes.is_read_only()), mtime(meta.modified))?;
+    }
+    Ok(writer.finish())
+}
+
+/// ajoute le contenu d'un dossier, en profondeur (un dossier avant son contenu)
+fn append_children<F: FileSystem + ?Sized>(
+    fs: &F,
+    dir: &PathBuf,
+    archive_dir: &str,
+    visited: &mut BTreeSet<u32>,
+    writer: &mut TarWriter,
+) -> Result<(), FileSystemError> {
+    for entry in fs.list(&dir.to_string())? {
+        let name = entry.name()?;
+        if name == "." || name == ".." || entry.is_volume_label() {
+            continue;
+        }
+        let mut path = dir.clone();
+        path.push(&name)?;
+        let archive_path = if archive_dir.is_empty() { name } else { format!("{}/{}", archive_dir, name) };
+
+        if entry.is_directory() {
+            // entree dupliquee ou image corrompue : un dossier deja archive n'est pas repris
+            if !visited.insert(entry.first_cluster()) {
+                continue;
+            }
+            writer.append_dir(&archive_path, mode(entry.is_directory(), entry.is_read_only()), mtime(entry.modified()))?;
+            append_children(fs, &path, &archive_path, visited, writer)?;
+        } else {
+            let data = fs.read_file(&path.to_string())?;
+            writer.append_file(&archive_path, &data, mode(entry.is_directory(), entry.is_read_only()), mtime(entry.modified()))?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,223 @@
+/// Date et heure decodees d'un champ FAT (heure locale, pas de fuseau)
+///
+/// Date FAT : bits 15-9 annee depuis 1980, 8-5 mois, 4-0 jour.
+/// Heure FAT : bits 15-11 heures, 10-5 minutes, 4-0 secondes / 2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateTime {
+    /// Annee (1980-2107)
+    pub year: u16,
+    /// Mois (1-12)
+    pub month: u8,
+    /// Jour (1-31)
+    pub day: u8,
+    /// Heures (0-23)
+    pub hour: u8,
+    /// Minutes (0-59)
+    pub minute: u8,
+    /// Secondes (0-59)
+    pub second: u8,
+    /// Millisecondes, resolution 10 ms (seulement pour la creation)
+    pub millisecond: u16,
+}
+
+impl DateTime {
+    /// Premiere date representable : 1980-01-01 00:00:00
+    pub const FAT_EPOCH: DateTime = DateTime {
+        year: 1980,
+        month: 1,
+        day: 1,
+        hour: 0,
+        minute: 0,
+        second: 0,
+        millisecond: 0,
+    };
+
+    /// Derniere date representable : 2107-12-31 23:59:59.99 (avec le champ 10 ms)
+    pub const FAT_MAX: DateTime = DateTime {
+        year: 2107,
+        month: 12,
+        day: 31,
+        hour: 23,
+        minute: 59,
+        second: 59,
+        millisecond: 990,
+    };
+
+    /// Decode une date, une heure et le champ 10 ms (0-199) de la creation
+    ///
+    /// Renvoie None si la date vaut 0 (non renseignee) ou si un champ est hors limites.
+    pub fn from_fat(date: u16, time: u16, hundredths: u8) -> Option<Self> {
+        let mut datetime = Self::from_fat_date(date)?;
+        datetime.hour = (time >> 11) as u8;
+        datetime.minute = ((time >> 5) & 0x3F) as u8;
+        datetime.second = ((time & 0x1F) * 2) as u8 + hundredths / 100;
+        datetime.millisecond = (hundredths % 100) as u16 * 10;
+
+        if datetime.hour > 23 || datetime.minute > 59 || datetime.second > 59 || hundredths > 199 {
+            return None;
+        }
+        Some(datetime)
+    }
+
+    /// Decode une date seule (date de dernier acces), heure a minuit
+    pub fn from_fat_date(date: u16) -> Option<Self> {
+        if date == 0 {
+            return None;
+        }
+        let datetime = DateTime {
+            year: 1980 + (date >> 9),
+            month: ((date >> 5) & 0x0F) as u8,
+            day: (date & 0x1F) as u8,
+            ..Self::FAT_EPOCH
+        };
+        if datetime.month == 0 || datetime.month > 12 || datetime.day == 0 || datetime.day > 31 {
+            return None;
+        }
+        Some(datetime)
+    }
+
+    /// Date ramenee dans la plage FAT : avant 1980 -> `FAT_EPOCH`, apres 2107 -> `FAT_MAX`
+    pub fn clamp_to_fat(&self) -> Self {
+        if self.year < 1980 {
+            Self::FAT_EPOCH
+        } else if self.year > 2107 {
+            Self::FAT_MAX
+        } else {
+            *self
+        }
+    }
+
+    /// Date au format FAT (hors 1980-2107, ramenee a 1980-01-01 ou 2107-12-31)
+    pub fn fat_date(&self) -> u16 {
+        let dt = self.clamp_to_fat();
+        ((dt.year - 1980) << 9) | ((dt.month as u16) << 5) | dt.day as u16
+    }
+
+    /// Heure au format FAT (secondes paires), avec la meme limite que `fat_date`
+    pub fn fat_time(&self) -> u16 {
+        let dt = self.clamp_to_fat();
+        ((dt.hour as u16) << 11) | ((dt.minute as u16) << 5) | (dt.second as u16 / 2)
+    }
+
+    /// Champ 10 ms de la creation : seconde impaire et centiemes (0-199)
+    pub fn fat_hundredths(&self) -> u8 {
+        let dt = self.clamp_to_fat();
+        ((dt.second % 2) * 100) + (dt.millisecond / 10) as u8
+    }
+
+    /// Convertit un timestamp Unix (secondes depuis 1970, UTC) en date civile
+    pub fn from_unix_timestamp(seconds: u64) -> Self {
+        let days = (seconds / 86_400) as i64;
+        let secs_of_day = seconds % 86_400;
+
+        // algorithme "civil from days" (calendrier gregorien proleptique)
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+        let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+        DateTime {
+            year: year.clamp(0, u16::MAX as i64) as u16,
+            month,
+            day,
+            hour: (secs_of_day / 3600) as u8,
+            minute: ((secs_of_day / 60) % 60) as u8,
+            second: (secs_of_day % 60) as u8,
+            millisecond: 0,
+        }
+    }
+
+    /// Timestamp Unix (secondes depuis 1970, UTC), 0 pour une date anterieure
+    pub fn to_unix_timestamp(&self) -> u64 {
+        // algorithme "days from civil", inverse de from_unix_timestamp
+        let month = self.month as i64;
+        let year = self.year as i64 - if month <= 2 { 1 } else { 0 };
+        let era = year.div_euclid(400);
+        let yoe = year - era * 400;
+        let mp = if month > 2 { month - 3 } else { month + 9 };
+        let doy = (153 * mp + 2) / 5 + self.day as i64 - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        let days = era * 146_097 + doe - 719_468;
+
+        let seconds = days * 86_400 + self.hour as i64 * 3600 + self.minute as i64 * 60 + self.second as i64;
+        seconds.max(0) as u64
+    }
+
+    /// Date d'un `SystemTime` (UTC), a 10 ms pres
+    #[cfg(feature = "std")]
+    pub fn from_system_time(time: std::time::SystemTime) -> Self {
+        let since_epoch = time.duration_since(std::time::UNIX_EPOCH).unwrap_or_default();
+        let mut datetime = DateTime::from_unix_timestamp(since_epoch.as_secs());
+        datetime.millisecond = (since_epoch.subsec_millis() / 10 * 10) as u16;
+        datetime
+    }
+
+    /// `SystemTime` correspondant (la date FAT est prise comme UTC)
+    #[cfg(feature = "std")]
+    pub fn to_system_time(&self) -> std::time::SystemTime {
+        std::time::UNIX_EPOCH
+            + std::time::Duration::from_secs(self.to_unix_timestamp())
+            + std::time::Duration::from_millis(self.millisecond as u64)
+    }
+}
+
+impl core::fmt::Display for DateTime {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+}
+
+/// Source de l'heure courante pour horodater les entrees creees ou modifiees
+///
+/// Une bibliotheque no_std n'a pas d'horloge : c'est l'appelant qui la fournit.
+pub trait TimeProvider {
+    /// Date et heure courantes
+    fn now(&self) -> DateTime;
+}
+
+/// Heure fixe, pour des images reproductibles (et par defaut sans horloge)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedTimeProvider {
+    time: DateTime,
+}
+
+impl FixedTimeProvider {
+    /// Toujours renvoyer `time`
+    pub fn new(time: DateTime) -> Self {
+        Self { time }
+    }
+}
+
+impl Default for FixedTimeProvider {
+    /// 1980-01-01 00:00:00, premiere date FAT
+    fn default() -> Self {
+        Self::new(DateTime::FAT_EPOCH)
+    }
+}
+
+impl TimeProvider for FixedTimeProvider {
+    fn now(&self) -> DateTime {
+        self.time
+    }
+}
+
+/// Heure systeme via `std::time::SystemTime`, en UTC (FAT ne stocke pas de fuseau)
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemTimeProvider;
+
+#[cfg(feature = "std")]
+impl TimeProvider for SystemTimeProvider {
+    fn now(&self) -> DateTime {
+        DateTime::from_system_time(std::time::SystemTime::now())
+    }
+}
@@ -0,0 +1,118 @@
+use crate::fs::FileSystemError;
+use crate::fs::entry::is_short_name_char;
+use crate::fs::fat_table::FatType;
+use alloc::string::String;
+
+/// Secteur FSInfo FAT32 : compteur de clusters libres et indice du prochain libre
+///
+/// Ces valeurs ne sont que des indications, elles peuvent etre fausses ou inconnues.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FsInfo {
+    /// Clusters libres selon le secteur, None si inconnu (0xFFFFFFFF)
+    pub free_count: Option<u32>,
+    /// Ou chercher un cluster libre, None si inconnu
+    pub next_free: Option<u32>,
+}
+
+impl FsInfo {
+    /// Signature en tete du secteur
+    pub const LEAD_SIGNATURE: u32 = 0x4161_5252;
+    /// Signature avant les compteurs
+    pub const STRUCT_SIGNATURE: u32 = 0x6141_7272;
+    /// Signature en fin de secteur
+    pub const TRAIL_SIGNATURE: u32 = 0xAA55_0000;
+
+    /// Lit un secteur FSInfo, None si les signatures sont absentes
+    pub fn from_bytes(sector: &[u8]) -> Option<Self> {
+        if sector.len() < 512
+            || le_u32(sector, 0) != Self::LEAD_SIGNATURE
+            || le_u32(sector, 484) != Self::STRUCT_SIGNATURE
+            || le_u32(sector, 508) != Self::TRAIL_SIGNATURE
+        {
+            return None;
+        }
+        let known = |value: u32| if value == 0xFFFF_FFFF { None } else { Some(value) };
+        Some(Self {
+            free_count: known(le_u32(sector, 488)),
+            next_free: known(le_u32(sector, 492)),
+        })
+    }
+
+    /// Ecrit les compteurs dans un secteur FSInfo existant
+    pub fn write_to(&self, sector: &mut [u8]) {
+        let free_count = self.free_count.unwrap_or(0xFFFF_FFFF);
+        let next_free = self.next_free.unwrap_or(0xFFFF_FFFF);
+        sector[488..492].copy_from_slice(&free_count.to_le_bytes());
+        sector[492..496].copy_from_slice(&next_free.to_le_bytes());
+    }
+}
+
+/// Statistiques d'un volume, comme `statfs`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VolumeInfo {
+    /// FAT12, FAT16 ou FAT32
+    pub fat_type: FatType,
+    /// Taille d'un cluster en octets
+    pub cluster_size: u32,
+    /// Clusters de la zone de donnees
+    pub total_clusters: u32,
+    /// Clusters libres (parcours complet de la FAT)
+    pub free_clusters: u32,
+    /// Clusters alloues
+    pub used_clusters: u32,
+    /// Clusters marques defectueux
+    pub bad_clusters: u32,
+    /// Clusters libres selon FSInfo (FAT32), None si absent ou inconnu
+    pub fs_info_free_clusters: Option<u32>,
+    /// Numero de serie du volume
+    pub volume_id: Option<u32>,
+    /// Label du volume
+    pub volume_label: Option<String>,
+}
+
+impl VolumeInfo {
+    /// Taille de la zone de donnees en octets
+    pub fn total_bytes(&self) -> u64 {
+        self.total_clusters as u64 * self.cluster_size as u64
+    }
+
+    /// Octets libres
+    pub fn free_bytes(&self) -> u64 {
+        self.free_clusters as u64 * self.cluster_size as u64
+    }
+
+    /// Octets alloues
+    pub fn used_bytes(&self) -> u64 {
+        self.used_clusters as u64 * self.cluster_size as u64
+    }
+}
+
+/// Encode un label de volume : 11 caracteres max, mis en majuscules, complete par des espaces
+///
+/// Memes caracteres qu'un nom court, plus l'espace (pas en tete). None pour un label vide.
+pub fn encode_volume_label(label: &str) -> Result<Option<[u8; 11]>, FileSystemError> {
+    if label.is_empty() {
+        return Ok(None);
+    }
+    if label.len() > 11 {
+        return Err(FileSystemError::InvalidPath("Volume label is limited to 11 characters".into()));
+    }
+    if label.starts_with(' ') {
+        return Err(FileSystemError::InvalidPath("Volume label cannot start with a space".into()));
+    }
+    let mut encoded = [b' '; 11];
+    for (slot, c) in encoded.iter_mut().zip(label.chars()) {
+        let upper = c.to_ascii_uppercase();
+        if !upper.is_ascii() || (upper != ' ' && !is_short_name_char(upper as u8)) {
+            let mut msg = String::from("Invalid character in volume label: ");
+            msg.push(c);
+            return Err(FileSystemError::InvalidPath(msg));
+        }
+        *slot = upper as u8;
+    }
+    Ok(Some(encoded))
+}
+
+fn le_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
@@ -0,0 +1,218 @@
+use crate::fs::{DirEntry, FileSystemError};
+use crate::fs::entry::Attributes;
+use crate::fs::fat::{Fat32Fs, ReadDir};
+use crate::fs::path::PathBuf;
+use alloc::boxed::Box;
+use alloc::collections::{BTreeSet, VecDeque};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// Ordre de parcours
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WalkOrder {
+    /// Un dossier puis tout son contenu avant son voisin (pre-ordre)
+    #[default]
+    DepthFirst,
+    /// Tout un niveau avant le suivant
+    BreadthFirst,
+}
+
+/// Entree renvoyee par `Walk`
+pub struct WalkEntry {
+    /// Chemin absolu de l'entree
+    pub path: PathBuf,
+    /// Entree de repertoire
+    pub entry: DirEntry,
+    /// Profondeur : 1 pour le contenu direct du dossier de depart
+    pub depth: usize,
+}
+
+/// Filtre sur le nom d'une entree
+type NameFilter<'a> = Box<dyn Fn(&str) -> bool + 'a>;
+
+/// Dossier ouvert : son chemin, la profondeur de son contenu et son lecteur
+struct OpenDir<'a> {
+    path: PathBuf,
+    depth: usize,
+    reader: ReadDir<'a>,
+}
+
+/// Dossier en attente (parcours en largeur)
+struct PendingDir {
+    path: PathBuf,
+    cluster: u32,
+    depth: usize,
+}
+
+/// Parcours recursif renvoye par `Fat32Fs::walk`
+///
+/// Les entrees `.` et `..` sont ignorees. Un dossier deja visite (meme premier
+/// cluster, image corrompue) n'est pas reparcouru : une erreur est renvoyee a sa
+/// place et le parcours continue. Les filtres choisissent les entrees renvoyees,
+/// ils n'empechent pas de descendre dans les dossiers.
+pub struct Walk<'a> {
+    /// systeme de fichiers parcouru
+    fs: &'a Fat32Fs,
+    /// profondeur d'abord ou largeur d'abord
+    order: WalkOrder,
+    /// ne pas descendre plus bas (None : pas de limite)
+    max_depth: Option<usize>,
+    /// filtre sur le nom (long ou court)
+    name_filter: Option<NameFilter<'a>>,
+    /// attributs qui doivent tous etre presents
+    required: Attributes,
+    /// attributs qui doivent tous etre absents
+    excluded: Attributes,
+    /// pile des dossiers ouverts (un seul en largeur d'abord)
+    open: Vec<OpenDir<'a>>,
+    /// file des dossiers a lire en largeur d'abord
+    pending: VecDeque<PendingDir>,
+    /// premiers clusters des dossiers deja ouverts
+    visited: BTreeSet<u32>,
+}
+
+impl<'a> Walk<'a> {
+    /// Parcours du dossier `path`, deja resolu au cluster `cluster`
+    pub(crate) fn new(fs: &'a Fat32Fs, path: PathBuf, cluster: u32) -> Self {
+        let cluster = if cluster == 0 { fs.root_dir_cluster() } else { cluster };
+        let mut pending = VecDeque::new();
+        pending.push_back(PendingDir { path, cluster, depth: 1 });
+        let mut visited = BTreeSet::new();
+        visited.insert(cluster);
+        Self {
+            fs,
+            order: WalkOrder::default(),
+            max_depth: None,
+            name_filter: None,
+            required: Attributes::empty(),
+            excluded: Attributes::empty(),
+            open: Vec::new(),
+            pending,
+            visited,
+        }
+    }
+
+    /// Choisir l'ordre de parcours
+    pub fn order(mut self, order: WalkOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Parcours en largeur d'abord
+    pub fn breadth_first(self) -> Self {
+        self.order(WalkOrder::BreadthFirst)
+    }
+
+    /// Profondeur maximale : 1 ne renvoie que le contenu direct du dossier
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Ne renvoyer que les entrees dont le nom satisfait `filter`
+    pub fn filter_name(mut self, filter: impl Fn(&str) -> bool + 'a) -> Self {
+        self.name_filter = Some(Box::new(filter));
+        self
+    }
+
+    /// Ne renvoyer que les entrees qui ont tous ces attributs
+    pub fn with_attributes(mut self, attributes: Attributes) -> Self {
+        self.required.insert(attributes);
+        self
+    }
+
+    /// Ne pas renvoyer les entrees qui ont l'un de ces attributs
+    pub fn without_attributes(mut self, attributes: Attributes) -> Self {
+        self.excluded.insert(attributes);
+        self
+    }
+
+    /// l'entree passe-t-elle les filtres ?
+    fn accepts(&self, name: &str, entry: &DirEntry) -> bool {
+        let attributes = entry.attributes();
+        if !attributes.contains(self.required) || attributes.bits() & self.excluded.bits() != 0 {
+            return false;
+        }
+        self.name_filter.as_ref().is_none_or(|filter| filter(name))
+    }
+
+    /// programme la lecture d'un sous-dossier, erreur si deja visite
+    fn schedule(&mut self, path: &PathBuf, entry: &DirEntry, depth: usize) -> Result<(), FileSystemError> {
+        let cluster = match entry.first_cluster() {
+            0 => self.fs.root_dir_cluster(),
+            cluster => cluster,
+        };
+        if !self.visited.insert(cluster) {
+            let mut msg = String::from("Directory cycle at ");
+            msg.push_str(&path.to_string());
+            return Err(FileSystemError::DirectoryEntryError(msg));
+        }
+        match self.order {
+            WalkOrder::DepthFirst => self.open.push(OpenDir {
+                path: path.clone(),
+                depth: depth + 1,
+                reader: self.fs.read_dir_at(cluster),
+            }),
+            WalkOrder::BreadthFirst => self.pending.push_back(PendingDir {
+                path: path.clone(),
+                cluster,
+                depth: depth + 1,
+            }),
+        }
+        Ok(())
+    }
+}
+
+impl Iterator for Walk<'_> {
+    type Item = Result<WalkEntry, FileSystemError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let current = match self.open.last_mut() {
+                Some(current) => current,
+                None => {
+                    let pending = self.pending.pop_front()?;
+                    if self.max_depth.is_some_and(|max| pending.depth > max) {
+                        continue;
+                    }
+                    self.open.push(OpenDir {
+                        path: pending.path,
+                        depth: pending.depth,
+                        reader: self.fs.read_dir_at(pending.cluster),
+                    });
+                    continue;
+                }
+            };
+
+            let entry = match current.reader.next() {
+                Some(Ok(entry)) => entry,
+                Some(Err(e)) => return Some(Err(e)),
+                None => {
+                    self.open.pop();
+                    continue;
+                }
+            };
+            let depth = current.depth;
+            let name = match entry.name() {
+                Ok(name) => name,
+                Err(e) => return Some(Err(e)),
+            };
+            if name == "." || name == ".." {
+                continue;
+            }
+            let mut path = current.path.clone();
+            if let Err(e) = path.push(&name) {
+                return Some(Err(e.into()));
+            }
+
+            if entry.is_directory() && self.max_depth.is_none_or(|max| depth < max) {
+                if let Err(e) = self.schedule(&path, &entry, depth) {
+                    return Some(Err(e));
+                }
+            }
+            if self.accepts(&name, &entry) {
+                return Some(Ok(WalkEntry { path, entry, depth }));
+            }
+        }
+    }
+}
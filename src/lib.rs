@@ -1,8 +1,11 @@
 #![no_std]
 
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 pub mod fs;
+pub mod cli;
 
-pub use fs::{Fat32Fs, FileSystem, FileSystemError, DirEntry};
+pub use fs::{Fat32Fs, ExFatFs, FileSystem, FileSystemError, DirEntry, Attributes, DateTime, FatType, Metadata, FileKind, TimeProvider};
 pub use fs::path::{Path, PathBuf};
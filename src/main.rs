@@ -3,133 +3,362 @@
 
 #[cfg(feature = "std")]
 fn main() {
-    use std::io::{self, Write};
     use std::fs;
-    use mini_fat32::{Fat32Fs, FileSystem, FileSystemError};
+    use mini_fat32::cli::editor::LineEditor;
+    use mini_fat32::cli::{Cli, Command, OutputFormat, Volume};
+    use mini_fat32::fs::SystemTimeProvider;
     
-    println!("Mini-FAT32 CLI");
-    println!("==============");
-    
-    // Load filesystem image
-    let args: Vec<String> = std::env::args().collect();
+    // mini-fat32 <image>                   : shell interactif
+    // mini-fat32 <image> <commande..>      : une seule commande, code de sortie 0 / 1 / 2
+    // mini-fat32 <image> [-e] -f <script|-> : une commande par ligne du script (ou de stdin)
+    // mini-fat32 build --size 64M <dir> <out.img> : nouvelle image a partir d'un dossier
+    // --json (avant ou apres l'image) : un document JSON par commande, erreurs comprises
+    let mut args: Vec<String> = std::env::args().collect();
+    let json = match args.iter().skip(1).take(2).position(|arg| arg == "--json") {
+        Some(i) => {
+            args.remove(i + 1);
+            true
+        }
+        None => false,
+    };
     if args.len() < 2 {
-        eprintln!("Usage: {} <fat32_image>", args[0]);
-        std::process::exit(1);
+        eprintln!("Usage: {} [--json] <fat_or_exfat_image> [command [args...] | [-e] -f <script|->]", args[0]);
+        eprintln!("       {} {}", args[0], BUILD_USAGE);
+        std::process::exit(EXIT_USAGE);
+    }
+    if args[1] == "build" {
+        std::process::exit(run_build(&args[2..]));
     }
     
     let image_path = &args[1];
     let device_data = match fs::read(image_path) {
         Ok(data) => data,
         Err(e) => {
-            eprintln!("Error reading image: {}", e);
-            std::process::exit(1);
+            report_error(json, "io_error", "Error reading image: ", &e.to_string());
+            std::process::exit(EXIT_FAILURE);
         }
     };
     
-    unsafe {
-        let mut fs = match Fat32Fs::new(&device_data) {
-            Ok(fs) => fs,
-            Err(e) => {
-                eprintln!("Error initializing filesystem: {}", e);
-                std::process::exit(1);
-            }
+    // Safety: l'image est lue en entier, Volume::mount verifie le boot sector
+    let mounted = unsafe { Volume::mount(&device_data) };
+    let mut cli = match mounted {
+        Ok(Volume::Fat(fs)) => Cli::from_volume(Volume::Fat(fs.with_time_provider(Box::new(SystemTimeProvider)))),
+        Ok(volume) => Cli::from_volume(volume),
+        Err(e) => {
+            report_error(json, e.code(), "Error initializing filesystem: ", &e.to_string());
+            std::process::exit(EXIT_FAILURE);
+        }
+    };
+    if json {
+        cli = cli.with_output_format(OutputFormat::Json);
+    }
+
+    if args.len() > 2 {
+        let code = if matches!(args[2].as_str(), "-f" | "-e") {
+            run_script_file(&mut cli, image_path, &args[2..])
+        } else {
+            run_subcommand(&mut cli, image_path, &args[2..])
         };
-        
-        println!("Filesystem loaded successfully!");
-        println!("Current directory: {}", fs.pwd());
-        println!("\nCommands: ls <path>, cat <path>, cd <path>, pwd, exit");
-        println!("Type 'help' for more information\n");
-        
-        loop {
-            print!("fat32> ");
-            io::stdout().flush().unwrap();
-            
-            let mut input = String::new();
-            match io::stdin().read_line(&mut input) {
-                Ok(_) => {
-                    let input = input.trim();
-                    if input.is_empty() {
-                        continue;
-                    }
-                    
-                    let parts: Vec<&str> = input.split_whitespace().collect();
-                    if parts.is_empty() {
-                        continue;
-                    }
-                    
-                    match parts[0] {
-                        "ls" => {
-                            let path = if parts.len() > 1 { parts[1] } else { "." };
-                            match fs.list(path) {
-                                Ok(entries) => {
-                                    if entries.is_empty() {
-                                        println!("(empty)");
-                                    } else {
-                                        for entry in entries {
-                                            match entry.name() {
-                                                Ok(name) => {
-                                                    let marker = if entry.is_directory() { "/" } else { "" };
-                                                    println!("{}{}", name, marker);
-                                                }
-                                                Err(e) => println!("<error: {}>", e),
-                                            }
-                                        }
-                                    }
-                                }
-                                Err(e) => eprintln!("Error: {}", e),
-                            }
-                        }
-                        "cat" => {
-                            if parts.len() < 2 {
-                                eprintln!("Usage: cat <file>");
-                                continue;
-                            }
-                            match fs.read_file(parts[1]) {
-                                Ok(data) => {
-                                    let data_len = data.len();
-                                    match String::from_utf8(data) {
-                                        Ok(text) => print!("{}", text),
-                                        Err(_) => println!("<binary data, {} bytes>", data_len),
-                                    }
-                                }
-                                Err(e) => eprintln!("Error: {}", e),
-                            }
-                        }
-                        "cd" => {
-                            let path = if parts.len() > 1 { parts[1] } else { "/" };
-                            match fs.cd(path) {
-                                Ok(_) => {
-                                    // Success
-                                }
-                                Err(e) => eprintln!("Error: {}", e),
+        std::process::exit(code);
+    }
+    
+    println!("Mini-FAT32 CLI");
+    println!("==============");
+    println!("Filesystem loaded successfully!");
+    println!("Current directory: {}", cli.filesystem().pwd());
+    println!("\nCommands: ls [-laStr] <path>, cat <path>, find [path] -name <pattern>, cd <path>, pwd, stat <path>, info, exit");
+    println!("Type 'help' for more information (Tab completes, Up/Down and Ctrl-R search the history)\n");
+    
+    let mut editor = LineEditor::new();
+    loop {
+        match read_command(&mut editor, &cli) {
+            Ok(None) => break,
+            Ok(Some(input)) => {
+                if input.trim().is_empty() {
+                    continue;
+                }
+                let command = Command::parse(&input);
+                let exit = command == Command::Exit;
+                let modifies = command.modifies();
+                match command {
+                    // ligne de commentaire
+                    Command::Unknown(msg) if msg.is_empty() => {}
+                    Command::Unknown(msg) => report_error(json, "usage", "", &msg),
+                    command => match cli.execute(command) {
+                        Ok(output) => {
+                            print!("{}", output);
+                            // comme en mode commande : l'image est reecrite apres chaque modification
+                            if modifies {
+                                save_image(&cli, image_path);
                             }
                         }
-                        "pwd" => {
-                            println!("{}", fs.pwd());
-                        }
-                        "exit" | "quit" | "q" => {
-                            println!("Goodbye!");
-                            break;
-                        }
-                        "help" => {
-                            println!("Available commands:");
-                            println!("  ls [path]     - List directory contents");
-                            println!("  cat <file>    - Read and display file");
-                            println!("  cd [path]     - Change directory");
-                            println!("  pwd           - Print current directory");
-                            println!("  exit/quit/q   - Exit CLI");
-                            println!("  help          - Show this help");
-                        }
-                        _ => {
-                            eprintln!("Unknown command: {}. Type 'help' for help.", parts[0]);
-                        }
-                    }
+                        Err(e) => report_error(json, e.code(), "Error: ", &e.to_string()),
+                    },
                 }
-                Err(e) => {
-                    eprintln!("Error reading input: {}", e);
+                if exit {
                     break;
                 }
             }
+            Err(e) => {
+                eprintln!("Error reading input: {}", e);
+                break;
+            }
+        }
+    }
+}
+
+/// Ligne du shell interactif ; None en fin d'entree
+///
+/// Sur un terminal, edition avec historique, Ctrl-R et completion (Tab) ; sinon
+/// (stdin redirige, pas de `stty`) simple `read_line`.
+#[cfg(feature = "std")]
+fn read_command(editor: &mut mini_fat32::cli::editor::LineEditor, cli: &mini_fat32::cli::Cli) -> std::io::Result<Option<String>> {
+    use std::io::{self, BufRead, Write};
+    use mini_fat32::cli::complete::complete;
+    use mini_fat32::cli::editor::RawMode;
+
+    const PROMPT: &str = "fat32> ";
+    // mode brut seulement pendant la saisie : la sortie des commandes reste normale
+    if let Some(_raw) = RawMode::enable() {
+        return editor.read_line(PROMPT, &mut io::stdin().lock(), &mut io::stdout().lock(), |line| complete(cli.filesystem(), line));
+    }
+
+    print!("{}", PROMPT);
+    io::stdout().flush()?;
+    let mut input = String::new();
+    match io::stdin().lock().read_line(&mut input)? {
+        0 => Ok(None),
+        _ => Ok(Some(input)),
+    }
+}
+
+/// Erreur sur stderr (precedee de `prefix`), ou document JSON sur stdout en `--json`
+/// pour garder un document par commande
+#[cfg(feature = "std")]
+fn report_error(json: bool, code: &str, prefix: &str, message: &str) {
+    if json {
+        print!("{}", mini_fat32::cli::json::error_document(code, message));
+    } else {
+        eprintln!("{}{}", prefix, message);
+    }
+}
+
+/// code de sortie : commande reussie
+#[cfg(feature = "std")]
+const EXIT_SUCCESS: i32 = 0;
+/// code de sortie : la commande a echoue (fichier absent, image invalide...)
+#[cfg(feature = "std")]
+const EXIT_FAILURE: i32 = 1;
+/// code de sortie : commande inconnue ou arguments manquants
+#[cfg(feature = "std")]
+const EXIT_USAGE: i32 = 2;
+
+/// Mode non interactif : une commande, sa sortie brute sur stdout, les erreurs sur stderr
+///
+/// Une commande qui modifie l'image (create, write, label) la reecrit sur le disque.
+#[cfg(feature = "std")]
+fn run_subcommand(cli: &mut mini_fat32::cli::Cli, image_path: &str, args: &[String]) -> i32 {
+    use std::io::Write;
+    use mini_fat32::cli::{Command, OutputFormat};
+
+    let json = cli.output_format() == OutputFormat::Json;
+    let command = Command::from_args(args);
+    let modifies = command.modifies();
+    let output = match command {
+        Command::Unknown(msg) => {
+            report_error(json, "usage", "", &msg);
+            return EXIT_USAGE;
+        }
+        command => cli.execute_raw(command),
+    };
+    let output = match output {
+        Ok(output) => output,
+        Err(e) => {
+            report_error(json, e.code(), "Error: ", &e.to_string());
+            return EXIT_FAILURE;
+        }
+    };
+
+    if modifies && !save_image(cli, image_path) {
+        return EXIT_FAILURE;
+    }
+
+    // stdout ferme (`| head`) : pas une erreur de la commande
+    let mut stdout = std::io::stdout().lock();
+    let _ = stdout.write_all(&output).and_then(|_| stdout.flush());
+    EXIT_SUCCESS
+}
+
+/// Mode script : `-f fichier` (ou `-f -` pour stdin), `-e` pour s'arreter a la premiere erreur
+///
+/// La sortie des commandes va sur stdout, les erreurs et le bilan sur stderr.
+#[cfg(feature = "std")]
+fn run_script_file(cli: &mut mini_fat32::cli::Cli, image_path: &str, args: &[String]) -> i32 {
+    use std::io::{Read, Write};
+
+    let mut stop_on_error = false;
+    let mut script_path = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-e" => stop_on_error = true,
+            "-f" => script_path = args.next(),
+            other => {
+                eprintln!("Unexpected argument: {}", other);
+                return EXIT_USAGE;
+            }
+        }
+    }
+    let Some(script_path) = script_path else {
+        eprintln!("Usage: -f <script|->");
+        return EXIT_USAGE;
+    };
+
+    let script = if script_path == "-" {
+        let mut script = String::new();
+        std::io::stdin().read_to_string(&mut script).map(|_| script)
+    } else {
+        std::fs::read_to_string(script_path)
+    };
+    let script = match script {
+        Ok(script) => script,
+        Err(e) => {
+            eprintln!("Error reading script: {}", e);
+            return EXIT_FAILURE;
+        }
+    };
+
+    let json = cli.output_format() == mini_fat32::cli::OutputFormat::Json;
+    let report = cli.run_script(&script, stop_on_error, |step, output| {
+        let mut stdout = std::io::stdout().lock();
+        let _ = stdout.write_all(output).and_then(|_| stdout.flush());
+        if let Some(e) = &step.error {
+            report_error(json, e.code(), &format!("line {}: Error: ", step.line), &e.to_string());
+        }
+    });
+    eprint!("{}", report.summary());
+
+    if report.modified && !save_image(cli, image_path) {
+        return EXIT_FAILURE;
+    }
+    if report.is_success() { EXIT_SUCCESS } else { EXIT_FAILURE }
+}
+
+#[cfg(feature = "std")]
+const BUILD_USAGE: &str = "build --size <64M> [--label NAME] [--reproducible] [--volume-id XXXX-XXXX] <dir> <out.img>";
+
+/// `build` : formate une image FAT32 et y copie un dossier de l'hote
+///
+/// `--reproducible` fixe les dates (SOURCE_DATE_EPOCH, sinon 1980-01-01) et le
+/// numero de serie (1234-5678 sauf `--volume-id`) : memes entrees, meme image.
+#[cfg(feature = "std")]
+fn run_build(args: &[String]) -> i32 {
+    use mini_fat32::cli::build_image;
+    use mini_fat32::fs::FormatOptions;
+    use mini_fat32::DateTime;
+
+    let mut size = None;
+    let mut label = None;
+    let mut volume_id = None;
+    let mut reproducible = false;
+    let mut paths = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--size" => match args.next().and_then(|text| parse_size(text)) {
+                Some(bytes) => size = Some(bytes),
+                None => {
+                    eprintln!("Invalid size (examples: 64M, 1G, 33554432)");
+                    return EXIT_USAGE;
+                }
+            },
+            "--label" => label = args.next().cloned(),
+            "--volume-id" => match args.next().and_then(|text| u32::from_str_radix(&text.replace('-', ""), 16).ok()) {
+                Some(id) => volume_id = Some(id),
+                None => {
+                    eprintln!("Invalid volume ID (example: 1234-ABCD)");
+                    return EXIT_USAGE;
+                }
+            },
+            "--reproducible" => reproducible = true,
+            other if other.starts_with("--") => {
+                eprintln!("Unexpected argument: {}", other);
+                return EXIT_USAGE;
+            }
+            path => paths.push(path),
+        }
+    }
+    let (Some(size), [dir, out]) = (size, paths.as_slice()) else {
+        eprintln!("Usage: mini-fat32 {}", BUILD_USAGE);
+        return EXIT_USAGE;
+    };
+
+    let now = std::time::SystemTime::now();
+    let timestamp = if reproducible {
+        match std::env::var("SOURCE_DATE_EPOCH").ok().and_then(|epoch| epoch.parse().ok()) {
+            Some(seconds) => DateTime::from_unix_timestamp(seconds),
+            None => DateTime::FAT_EPOCH,
+        }
+    } else {
+        DateTime::from_system_time(now)
+    };
+    // comme mkfs : numero de serie tire de l'heure, fixe en mode reproductible
+    let volume_id = volume_id.unwrap_or_else(|| {
+        if reproducible {
+            0x1234_5678
+        } else {
+            let since_epoch = now.duration_since(std::time::UNIX_EPOCH).unwrap_or_default();
+            since_epoch.as_secs() as u32 ^ since_epoch.subsec_nanos()
+        }
+    });
+
+    let mut options = FormatOptions::new(size).with_volume_id(volume_id).with_timestamp(timestamp);
+    if let Some(label) = &label {
+        options = options.with_label(label);
+    }
+    let mut output = String::new();
+    let image = match build_image(std::path::Path::new(dir), &options, reproducible, &mut output) {
+        Ok(image) => image,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return EXIT_FAILURE;
+        }
+    };
+    print!("{}", output);
+    match std::fs::write(out, image) {
+        Ok(()) => EXIT_SUCCESS,
+        Err(e) => {
+            eprintln!("Error writing image: {}", e);
+            EXIT_FAILURE
+        }
+    }
+}
+
+/// taille avec suffixe K, M ou G (puissances de 1024)
+#[cfg(feature = "std")]
+fn parse_size(text: &str) -> Option<u64> {
+    let upper = text.to_ascii_uppercase();
+    let digits = upper.trim_end_matches("IB").trim_end_matches('B');
+    let (number, unit) = match digits.char_indices().last()? {
+        (i, 'K') => (&digits[..i], 1 << 10),
+        (i, 'M') => (&digits[..i], 1 << 20),
+        (i, 'G') => (&digits[..i], 1 << 30),
+        _ => (digits, 1),
+    };
+    number.parse::<u64>().ok()?.checked_mul(unit)
+}
+
+/// reecrit l'image modifiee (rien a faire en exFAT, en lecture seule)
+#[cfg(feature = "std")]
+fn save_image(cli: &mini_fat32::cli::Cli, image_path: &str) -> bool {
+    let Some(data) = cli.volume().device_data() else {
+        return true;
+    };
+    match std::fs::write(image_path, data) {
+        Ok(()) => true,
+        Err(e) => {
+            eprintln!("Error writing image: {}", e);
+            false
         }
     }
 }
@@ -0,0 +1,619 @@
+// Tests du moteur de commandes (Command::parse et Cli::execute)
+
+use mini_fat32::cli::{Cli, Command, ListSort, Volume};
+use mini_fat32::{FileSystemError, Fat32Fs};
+
+mod common;
+
+use common::create_test_filesystem;
+
+/// Helper: execute une ligne
+fn run(cli: &mut Cli, line: &str) -> Result<String, FileSystemError> {
+    cli.execute(Command::parse(line))
+}
+
+#[test]
+fn test_parse_commands() {
+    assert_eq!(
+        Command::parse("ls"),
+        Command::List { path: None, long: false, all: false, sort: ListSort::Directory, reverse: false }
+    );
+    assert_eq!(
+        Command::parse("  ls -l /DCIM "),
+        Command::List { path: Some("/DCIM".into()), long: true, all: false, sort: ListSort::Directory, reverse: false }
+    );
+    assert_eq!(Command::parse("cd"), Command::ChangeDirectory("/".into()));
+    assert_eq!(
+        Command::parse("find / -name '*.log'"),
+        Command::Find { root: "/".into(), name: "*.log".into() }
+    );
+    assert_eq!(Command::parse("label My Disk"), Command::Label(Some("My Disk".into())));
+    assert_eq!(Command::parse("write /a.txt hello  world"), Command::Write("/a.txt".into(), b"hello  world".to_vec()));
+    assert_eq!(Command::parse("df"), Command::Info);
+    assert_eq!(Command::parse("q"), Command::Exit);
+    assert!(matches!(Command::parse("cat"), Command::Unknown(_)));
+    assert!(matches!(Command::parse("find -name"), Command::Unknown(_)));
+    assert!(matches!(Command::parse("frobnicate"), Command::Unknown(_)));
+}
+
+#[test]
+fn test_execute_file_commands() {
+    let img = create_test_filesystem();
+    let mut cli = unsafe { Cli::new(&img).unwrap() };
+
+    assert_eq!(run(&mut cli, "ls").unwrap(), "(empty)\n");
+    run(&mut cli, "create /EMPTY.TXT").unwrap();
+    assert_eq!(run(&mut cli, "write /notes.txt hello world").unwrap(), "Wrote 11 bytes to /notes.txt\n");
+    assert_eq!(run(&mut cli, "ls /").unwrap(), "EMPTY.TXT\nnotes.txt\n");
+    assert_eq!(run(&mut cli, "cat notes.txt").unwrap(), "hello world");
+    assert_eq!(run(&mut cli, "cat /*.TXT").unwrap(), "hello world");
+    assert_eq!(run(&mut cli, "ls *.txt").unwrap(), "/EMPTY.TXT\n/notes.txt\n");
+    assert_eq!(run(&mut cli, "find / -name NOTES*").unwrap(), "/notes.txt\n");
+    assert!(run(&mut cli, "stat /notes.txt").unwrap().contains("Size:       11 (512 allocated)"));
+
+    assert!(matches!(run(&mut cli, "cat /missing.txt"), Err(FileSystemError::FileNotFound(_))));
+    assert!(run(&mut cli, "nope").is_err());
+}
+
+#[test]
+fn test_execute_directory_and_volume_commands() {
+    let img = create_test_filesystem();
+    let mut cli = unsafe { Cli::new(&img).unwrap() };
+
+    assert_eq!(run(&mut cli, "pwd").unwrap(), "/\n");
+    assert!(run(&mut cli, "cd /nowhere").is_err());
+    assert_eq!(run(&mut cli, "label").unwrap(), "(no label)\n");
+    run(&mut cli, "label backup").unwrap();
+    assert_eq!(run(&mut cli, "label").unwrap(), "BACKUP\n");
+
+    let info = run(&mut cli, "info").unwrap();
+    assert!(info.contains("Type:           FAT32"));
+    assert!(info.contains("Label:          BACKUP"));
+    assert!(run(&mut cli, "help").unwrap().starts_with("Available commands:"));
+}
+
+#[test]
+fn test_from_volume_keeps_filesystem_options() {
+    let img = create_test_filesystem();
+    let fs = unsafe { Fat32Fs::new(&img).unwrap() }.with_hidden_entries(false);
+    let mut cli = Cli::from_volume(Volume::Fat(fs));
+
+    run(&mut cli, "create /a.txt").unwrap();
+    assert!(matches!(cli.volume(), Volume::Fat(_)));
+    assert!(cli.filesystem().exists("/A.TXT"));
+}
+
+#[test]
+fn test_from_args_and_raw_output() {
+    let img = create_test_filesystem();
+    let mut cli = unsafe { Cli::new(&img).unwrap() };
+
+    let command = Command::from_args(&["write", "/data.bin", "x"]);
+    assert!(command.modifies());
+    cli.execute_raw(command).unwrap();
+    assert!(!Command::from_args(&["cat", "/data.bin"]).modifies());
+    assert_eq!(Command::from_args(&["label", "My", "Disk"]), Command::Label(Some("My Disk".into())));
+
+    // octets non UTF-8 : rendus tels quels par execute_raw, resumes par execute
+    cli.filesystem_mut().write_file("/data.bin", &[0xFF, 0x00, 0xFE]).unwrap();
+    assert_eq!(cli.execute_raw(Command::Read("/data.bin".into())).unwrap(), vec![0xFF, 0x00, 0xFE]);
+    assert_eq!(cli.execute(Command::Read("/data.bin".into())).unwrap(), "<binary data, 3 bytes>\n");
+}
+
+#[test]
+fn test_binary_subcommands_exit_codes() {
+    use std::process::Command as Process;
+
+    let image = std::env::temp_dir().join(format!("mini-fat32-cli-{}.img", std::process::id()));
+    std::fs::write(&image, create_test_filesystem()).unwrap();
+    let run = |args: &[&str]| Process::new(env!("CARGO_BIN_EXE_mini-fat32")).arg(&image).args(args).output().unwrap();
+
+    // la modification est reecrite dans l'image
+    let write = run(&["write", "/notes.txt", "hello"]);
+    assert_eq!(write.status.code(), Some(0));
+    let cat = run(&["cat", "/notes.txt"]);
+    assert_eq!(cat.status.code(), Some(0));
+    assert_eq!(cat.stdout, b"hello");
+    assert!(cat.stderr.is_empty());
+
+    let missing = run(&["cat", "/missing.txt"]);
+    assert_eq!(missing.status.code(), Some(1));
+    assert!(missing.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&missing.stderr).starts_with("Error: "));
+
+    assert_eq!(run(&["frobnicate"]).status.code(), Some(2));
+    assert_eq!(run(&["cat"]).status.code(), Some(2));
+
+    std::fs::remove_file(&image).unwrap();
+}
+
+#[test]
+fn test_interactive_shell_saves_modifications() {
+    use std::io::Write;
+    use std::process::{Command as Process, Stdio};
+
+    let image = std::env::temp_dir().join(format!("mini-fat32-repl-{}.img", std::process::id()));
+    std::fs::write(&image, create_test_filesystem()).unwrap();
+
+    // stdin redirige : le shell lit les lignes une a une
+    let mut shell = Process::new(env!("CARGO_BIN_EXE_mini-fat32"))
+        .arg(&image)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .unwrap();
+    shell.stdin.take().unwrap().write_all(b"write /notes.txt hello\nlabel FIRMWARE\nexit\n").unwrap();
+    assert!(shell.wait().unwrap().success());
+
+    let saved = std::fs::read(&image).unwrap();
+    let mut cli = unsafe { Cli::new(&saved).unwrap() };
+    assert_eq!(run(&mut cli, "cat /notes.txt").unwrap(), "hello");
+    assert_eq!(run(&mut cli, "label").unwrap(), "FIRMWARE\n");
+
+    std::fs::remove_file(&image).unwrap();
+}
+
+#[test]
+fn test_split_args_quotes_and_comments() {
+    use mini_fat32::cli::tokenize::split_args;
+
+    assert_eq!(split_args("cd \"Program Files\"").unwrap(), vec!["cd", "Program Files"]);
+    assert_eq!(split_args("cat '/My Docs/a b.txt'  # read it").unwrap(), vec!["cat", "/My Docs/a b.txt"]);
+    assert_eq!(split_args("write /a.txt ''").unwrap(), vec!["write", "/a.txt", ""]);
+    assert_eq!(split_args("ls /a#b").unwrap(), vec!["ls", "/a#b"]);
+    assert!(split_args("   # only a comment").unwrap().is_empty());
+    assert!(split_args("cat \"unterminated").is_err());
+}
+
+#[test]
+fn test_run_script_continues_and_reports() {
+    let img = create_test_filesystem();
+    let mut cli = unsafe { Cli::new(&img).unwrap() };
+    let script = "# provisioning\n\nwrite \"/My Notes.txt\" hello\ncat /missing.txt\ncat \"/my notes.txt\"\n";
+
+    let mut outputs = Vec::new();
+    let report = cli.run_script(script, false, |step, output| outputs.push((step.line, output.to_vec())));
+
+    assert_eq!(report.steps.len(), 3);
+    assert_eq!(report.failures(), 1);
+    assert!(report.modified);
+    assert!(!report.stopped);
+    assert!(matches!(report.steps[1].error, Some(FileSystemError::FileNotFound(_))));
+    assert_eq!(outputs[2], (5, b"hello".to_vec()));
+    let summary = report.summary();
+    assert!(summary.contains("  FAILED     4: cat /missing.txt"));
+    assert!(summary.ends_with("3 commands, 2 ok, 1 failed\n"));
+}
+
+#[test]
+fn test_run_script_stop_on_error_and_exit() {
+    let img = create_test_filesystem();
+    let mut cli = unsafe { Cli::new(&img).unwrap() };
+
+    let report = cli.run_script("cat /missing\nset -e\nbogus\ncreate /late.txt\n", false, |_, _| {});
+    assert_eq!(report.steps.len(), 2);
+    assert!(report.stopped);
+    assert!(!report.modified);
+    assert!(!cli.filesystem().exists("/late.txt"));
+
+    let report = cli.run_script("create /a.txt\nexit\ncreate /b.txt\n", true, |_, _| {});
+    assert!(report.is_success());
+    assert!(cli.filesystem().exists("/a.txt"));
+    assert!(!cli.filesystem().exists("/b.txt"));
+}
+
+#[test]
+fn test_tokenize_escapes() {
+    use mini_fat32::cli::tokenize::{split_args, tokenize};
+
+    assert_eq!(split_args(r"cat my\ file.txt").unwrap(), vec!["cat", "my file.txt"]);
+    assert_eq!(split_args(r#"write /a "say \"hi\" \\ \n""#).unwrap(), vec!["write", "/a", r#"say "hi" \ \n"#]);
+    assert_eq!(split_args(r"cat 'no \escape'").unwrap(), vec!["cat", r"no \escape"]);
+    assert_eq!(split_args(r"ls \#tag").unwrap(), vec!["ls", "#tag"]);
+    assert_eq!(split_args(r"cat a\'b").unwrap(), vec!["cat", "a'b"]);
+    assert!(split_args("cat trailing\\").is_err());
+
+    let tokens = tokenize("write  /a.txt\thello").unwrap();
+    assert_eq!(tokens[1].separator, "  ");
+    assert_eq!(tokens[2].separator, "\t");
+}
+
+#[test]
+fn test_parse_uses_tokenizer() {
+    assert_eq!(Command::parse("cd \"Program Files\""), Command::ChangeDirectory("Program Files".into()));
+    assert_eq!(Command::parse(r"cat my\ file.txt"), Command::Read("my file.txt".into()));
+    assert_eq!(Command::parse("find / -name '*.log'"), Command::Find { root: "/".into(), name: "*.log".into() });
+    // les espaces d'origine sont gardes entre les mots de write
+    assert_eq!(
+        Command::parse("write /a.txt hello   world\t!  "),
+        Command::Write("/a.txt".into(), b"hello   world\t!".to_vec())
+    );
+    assert_eq!(Command::parse("write /a.txt '  padded  '"), Command::Write("/a.txt".into(), b"  padded  ".to_vec()));
+    assert_eq!(Command::parse("cat \"open"), Command::Unknown("Unterminated quote".into()));
+    assert_eq!(Command::parse("# comment only"), Command::Unknown(String::new()));
+}
+
+#[test]
+fn test_quoted_paths_with_spaces() {
+    let img = create_test_filesystem();
+    let mut cli = unsafe { Cli::new(&img).unwrap() };
+
+    run(&mut cli, "write 'Program Files.txt' a  b").unwrap();
+    assert_eq!(run(&mut cli, r"cat Program\ Files.txt").unwrap(), "a  b");
+    assert_eq!(run(&mut cli, "stat \"program files.txt\"").map(|out| out.contains("Size:       4")), Ok(true));
+}
+
+#[test]
+fn test_get_put_round_trip() {
+    let host = std::env::temp_dir().join(format!("mini-fat32-getput-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&host);
+    std::fs::create_dir_all(host.join("fw/boot")).unwrap();
+    std::fs::write(host.join("fw/app.bin"), [0u8, 1, 2, 0xFF]).unwrap();
+    std::fs::write(host.join("fw/boot/config.txt"), "speed=1").unwrap();
+    let modified = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_600_000_000);
+    std::fs::File::options().write(true).open(host.join("fw/app.bin")).unwrap()
+        .set_modified(modified).unwrap();
+
+    let img = create_test_filesystem();
+    let mut cli = unsafe { Cli::new(&img).unwrap() };
+    let fw = host.join("fw").display().to_string();
+
+    assert_eq!(
+        Command::parse("put -r a /b"),
+        Command::Put { source: "a".into(), dest: "/b".into(), recursive: true }
+    );
+    assert!(matches!(Command::parse("get /a"), Command::Unknown(_)));
+    assert!(Command::parse("put a b").modifies());
+
+    // un dossier sans -r est refuse
+    assert!(run(&mut cli, &format!("put '{}' /", fw)).is_err());
+    let out = run(&mut cli, &format!("put -r '{}' /", fw)).unwrap();
+    assert!(out.contains("-> /fw/app.bin (4 bytes)"), "{}", out);
+    assert_eq!(cli.filesystem().read_file("/fw/boot/config.txt").unwrap(), b"speed=1");
+    let meta = cli.filesystem().metadata("/fw/app.bin").unwrap();
+    assert_eq!(meta.modified.map(|t| t.to_unix_timestamp()), Some(1_600_000_000));
+
+    // retour vers l'hote, dans un dossier existant
+    let back = host.join("back");
+    std::fs::create_dir(&back).unwrap();
+    run(&mut cli, &format!("get -r /fw '{}'", back.display())).unwrap();
+    assert_eq!(std::fs::read(back.join("fw/app.bin")).unwrap(), [0u8, 1, 2, 0xFF]);
+    assert_eq!(std::fs::read_to_string(back.join("fw/boot/config.txt")).unwrap(), "speed=1");
+    assert_eq!(std::fs::metadata(back.join("fw/app.bin")).unwrap().modified().unwrap(), modified);
+
+    // un seul fichier, vers un nouveau nom
+    run(&mut cli, &format!("get /fw/boot/config.txt '{}'", back.join("cfg.txt").display())).unwrap();
+    assert_eq!(std::fs::read_to_string(back.join("cfg.txt")).unwrap(), "speed=1");
+    assert!(run(&mut cli, &format!("get /fw '{}'", back.display())).is_err());
+
+    std::fs::remove_dir_all(&host).unwrap();
+}
+
+#[test]
+#[cfg(unix)]
+fn test_put_skips_symlink_loops() {
+    let host = std::env::temp_dir().join(format!("mini-fat32-symlink-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&host);
+    std::fs::create_dir_all(host.join("fw/boot")).unwrap();
+    std::fs::write(host.join("fw/boot/config.txt"), "speed=1").unwrap();
+    // lien vers un dossier parent : suivi, il ferait boucler la copie
+    std::os::unix::fs::symlink(host.join("fw"), host.join("fw/boot/up")).unwrap();
+
+    let img = create_test_filesystem();
+    let mut cli = unsafe { Cli::new(&img).unwrap() };
+    let out = run(&mut cli, &format!("put -r '{}' /", host.join("fw").display())).unwrap();
+    assert!(out.contains("/fw/boot/up (symbolic link)"), "{}", out);
+    assert_eq!(cli.filesystem().read_file("/fw/boot/config.txt").unwrap(), b"speed=1");
+    assert!(!cli.filesystem().exists("/fw/boot/up"));
+
+    // en JSON : une entree par dossier, fichier ou lien ignore
+    let mut json_cli = unsafe { Cli::new(&img).unwrap() }.with_output_format(mini_fat32::cli::OutputFormat::Json);
+    let out = run(&mut json_cli, &format!("put -r '{}' /", host.join("fw").display())).unwrap();
+    let link = host.join("fw/boot/up");
+    assert!(out.contains(&format!("{{\"kind\":\"skipped_symlink\",\"source\":\"{}\",\"dest\":null,\"size\":null}}", link.display())), "{}", out);
+    assert!(out.contains("\"kind\":\"directory\",") && out.contains("\"dest\":\"/fw/boot/config.txt\",\"size\":7}"), "{}", out);
+
+    let mut output = String::new();
+    let options = mini_fat32::fs::FormatOptions::new(common::IMAGE_SIZE);
+    let image = mini_fat32::cli::build_image(&host.join("fw"), &options, true, &mut output).unwrap();
+    let built = unsafe { Cli::new(&image).unwrap() };
+    assert_eq!(built.filesystem().read_file("/boot/config.txt").unwrap(), b"speed=1");
+    assert!(!built.filesystem().exists("/boot/up"));
+
+    std::fs::remove_dir_all(&host).unwrap();
+}
+
+#[test]
+#[cfg(feature = "tar")]
+fn test_export_tar_command() {
+    let img = create_test_filesystem();
+    let mut cli = unsafe { Cli::new(&img).unwrap() };
+    run(&mut cli, "write /a.txt hello").unwrap();
+
+    assert_eq!(
+        Command::parse("export-tar / -"),
+        Command::ExportTar { source: "/".into(), dest: "-".into() }
+    );
+    assert!(matches!(Command::parse("export-tar /"), Command::Unknown(_)));
+
+    let archive = cli.execute_raw(Command::parse("export-tar / -")).unwrap();
+    assert_eq!(&archive[..5], b"a.txt");
+    assert_eq!(&archive[512..517], b"hello");
+    assert_eq!(archive.len(), 4 * 512);
+
+    let out = std::env::temp_dir().join(format!("mini-fat32-export-{}.tar", std::process::id()));
+    let text = run(&mut cli, &format!("export-tar / '{}'", out.display())).unwrap();
+    assert!(text.ends_with("(2048 bytes)\n"), "{}", text);
+    assert_eq!(std::fs::read(&out).unwrap(), archive);
+    std::fs::remove_file(&out).unwrap();
+}
+
+#[test]
+fn test_binary_build_reproducible() {
+    use std::process::Command as Process;
+
+    let dir = std::env::temp_dir().join(format!("mini-fat32-build-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("rootfs/boot")).unwrap();
+    std::fs::write(dir.join("rootfs/boot/config.txt"), "kernel=1").unwrap();
+    std::fs::write(dir.join("rootfs/Read Me.txt"), "hi").unwrap();
+
+    let build = |out: &str| {
+        Process::new(env!("CARGO_BIN_EXE_mini-fat32"))
+            .args(["build", "--size", "40M", "--label", "BOOT", "--reproducible"])
+            .arg(dir.join("rootfs"))
+            .arg(dir.join(out))
+            .env("SOURCE_DATE_EPOCH", "1700000000")
+            .output()
+            .unwrap()
+    };
+    assert_eq!(build("a.img").status.code(), Some(0));
+    std::fs::write(dir.join("rootfs/boot/config.txt"), "kernel=1").unwrap();
+    assert_eq!(build("b.img").status.code(), Some(0));
+
+    let image = std::fs::read(dir.join("a.img")).unwrap();
+    assert_eq!(image.len(), 40 * 1024 * 1024);
+    assert_eq!(image, std::fs::read(dir.join("b.img")).unwrap());
+
+    let mut cli = unsafe { Cli::new(&image).unwrap() };
+    assert_eq!(run(&mut cli, "cat /boot/config.txt").unwrap(), "kernel=1");
+    assert_eq!(run(&mut cli, "label").unwrap(), "BOOT\n");
+    assert!(run(&mut cli, "info").unwrap().contains("Volume ID:      1234-5678"));
+    let meta = cli.filesystem().metadata("/Read Me.txt").unwrap();
+    assert_eq!(meta.modified.map(|t| t.to_unix_timestamp()), Some(1_700_000_000));
+
+    let usage = Process::new(env!("CARGO_BIN_EXE_mini-fat32")).args(["build", "--size", "64M"]).output().unwrap();
+    assert_eq!(usage.status.code(), Some(2));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_tree_sizes_and_totals() {
+    let img = create_test_filesystem();
+    let mut cli = unsafe { Cli::new(&img).unwrap() };
+    cli.filesystem_mut().create_dir("/boot").unwrap();
+    cli.filesystem_mut().create_dir("/boot/overlays").unwrap();
+    cli.filesystem_mut().write_file("/boot/overlays/Big Blob.dtbo", &[0u8; 1500]).unwrap();
+    run(&mut cli, "write /boot/config.txt kernel=1").unwrap();
+    run(&mut cli, "write /README.TXT hi").unwrap();
+
+    assert_eq!(Command::parse("tree -s /boot"), Command::Tree { path: Some("/boot".into()), short_names: true });
+    assert_eq!(
+        run(&mut cli, "tree").unwrap(),
+        "/ (1 cluster)\n\
+         ├── boot/ (1 cluster)\n\
+         │   ├── overlays/ (1 cluster)\n\
+         │   │   └── Big Blob.dtbo (1500 bytes, 3 clusters)\n\
+         │   └── config.txt (8 bytes, 1 cluster)\n\
+         └── README.TXT (2 bytes, 1 cluster)\n\
+         \n\
+         2 directories, 3 files, 1510 bytes, 8 clusters allocated\n"
+    );
+
+    cli.filesystem_mut().cd("/boot").unwrap();
+    let out = run(&mut cli, "tree -s overlays").unwrap();
+    assert!(out.starts_with("/boot/overlays (1 cluster)\n└── Big Blob.dtbo [BIGBLO~1.DTB] (1500 bytes"), "{}", out);
+    assert!(run(&mut cli, "tree config.txt").is_err());
+}
+
+#[test]
+fn test_ls_hidden_sort_and_long_format() {
+    use mini_fat32::{Attributes, DateTime};
+
+    let img = create_test_filesystem();
+    let mut cli = unsafe { Cli::new(&img).unwrap() };
+    run(&mut cli, "write /small.txt a").unwrap();
+    run(&mut cli, "write /big.txt aaaaaaaaaa").unwrap();
+    run(&mut cli, "write /secret.txt aaaaa").unwrap();
+    let Volume::Fat(fs) = cli.volume_mut() else { unreachable!() };
+    fs.set_attributes("/secret.txt", Attributes::HIDDEN).unwrap();
+    let day = |day| Some(DateTime { year: 2024, month: 1, day, hour: 0, minute: 0, second: 0, millisecond: 0 });
+    fs.set_times("/small.txt", None, day(3), None).unwrap();
+    fs.set_times("/big.txt", None, day(1), None).unwrap();
+    fs.set_times("/secret.txt", None, day(2), None).unwrap();
+
+    assert_eq!(
+        Command::parse("ls -laSr /"),
+        Command::List { path: Some("/".into()), long: true, all: true, sort: ListSort::Size, reverse: true }
+    );
+    assert!(matches!(Command::parse("ls -x"), Command::Unknown(_)));
+
+    assert_eq!(run(&mut cli, "ls /").unwrap(), "small.txt\nbig.txt\n");
+    assert_eq!(run(&mut cli, "ls -a /").unwrap(), "small.txt\nbig.txt\nsecret.txt\n");
+    assert_eq!(run(&mut cli, "ls -S -a").unwrap(), "big.txt\nsecret.txt\nsmall.txt\n");
+    assert_eq!(run(&mut cli, "ls -tr -a").unwrap(), "big.txt\nsecret.txt\nsmall.txt\n");
+    assert_eq!(run(&mut cli, "ls -t *.txt").unwrap(), "/small.txt\n/big.txt\n");
+
+    // -l : attributs, taille, dates, premier cluster, nom 8.3 puis nom long
+    let long = run(&mut cli, "ls -la /").unwrap();
+    let secret = long.lines().find(|line| line.ends_with("secret.txt")).unwrap();
+    assert!(secret.starts_with("- -H---- "), "{}", secret);
+    let cluster = cli.filesystem().metadata("/secret.txt").unwrap().first_cluster;
+    assert!(secret.contains("  2024-01-02 00:00:00  "), "{}", secret);
+    assert!(secret.contains(&format!("{:>8}  SECRET.TXT    secret.txt", cluster)), "{}", secret);
+}
+
+#[test]
+fn test_inspection_commands() {
+    use mini_fat32::cli::DumpUnit;
+
+    let img = create_test_filesystem();
+    let mut cli = unsafe { Cli::new(&img).unwrap() };
+    run(&mut cli, "write /a.txt hello").unwrap();
+    run(&mut cli, "write /b.txt world").unwrap();
+    cli.filesystem_mut().write_file("/a.txt", &[b'x'; 1500]).unwrap();
+
+    assert_eq!(Command::parse("hexdump cluster 0x10"), Command::HexDump { unit: DumpUnit::Cluster, index: 16 });
+    assert_eq!(Command::parse("fat 7"), Command::FatEntry(7));
+    assert!(matches!(Command::parse("hexdump block 1"), Command::Unknown(_)));
+    assert!(matches!(Command::parse("chain x"), Command::Unknown(_)));
+
+    let first = cli.filesystem().metadata("/a.txt").unwrap().first_cluster;
+    let chain = run(&mut cli, &format!("chain {}", first)).unwrap();
+    assert!(chain.starts_with(&format!("Chain from cluster {}: 3 clusters (1536 bytes)\n", first)), "{}", chain);
+    assert!(chain.ends_with("-> end\n"), "{}", chain);
+
+    assert_eq!(run(&mut cli, "fat 0").unwrap(), "FAT entry 0 (FAT32): 0x0FFFFFF8 = reserved entry (media descriptor 0xF8)\n");
+    assert_eq!(run(&mut cli, "fat 2").unwrap(), "FAT entry 2 (FAT32): 0x0FFFFFFF = end of chain\n");
+    assert_eq!(run(&mut cli, "fat 1000").unwrap(), "FAT entry 1000 (FAT32): 0x00000000 = free\n");
+    assert!(run(&mut cli, &format!("fat {}", first)).unwrap().contains(" = next cluster "));
+
+    let boot = run(&mut cli, "hexdump sector 0").unwrap();
+    assert!(boot.starts_with("Sector 0 (boot sector)\n00000000  eb 58 90 4d 53 57 49 4e  34 2e 31 00 02 01 20 00  |.X.MSWIN4.1... .|\n"), "{}", boot);
+    assert!(boot.contains("\n*\n"), "{}", boot);
+    assert!(boot.ends_with("000001f0  00 00 00 00 00 00 00 00  00 00 00 00 00 00 55 aa  |..............U.|\n00000200\n"), "{}", boot);
+    assert!(run(&mut cli, "hexdump sector 32").unwrap().starts_with("Sector 32 (FAT #1, sector 0)\n"));
+
+    // le cluster 2 commence apres les 32 secteurs reserves et les 2 FATs de 543 secteurs
+    let root = run(&mut cli, "hexdump cluster 2").unwrap();
+    assert!(root.starts_with("Cluster 2 (sector 1118, 512 bytes)\n0008bc00  "), "{}", root);
+    assert!(root.contains("|A       TXT ....|"), "{}", root);
+    assert!(run(&mut cli, "hexdump sector 99999999").is_err());
+
+    let bpb = run(&mut cli, "bpb").unwrap();
+    assert!(bpb.contains("  Bytes per sector:     512\n"), "{}", bpb);
+    assert!(bpb.contains("  Root cluster:         2\n"), "{}", bpb);
+    assert!(bpb.contains("  FS type:              \"FAT32   \"\n"), "{}", bpb);
+    assert!(bpb.contains("  Signature:            0xAA55\n"), "{}", bpb);
+    assert!(bpb.contains("  Data start sector:    1118\n"), "{}", bpb);
+}
+
+#[test]
+fn test_fsck_command() {
+    use mini_fat32::cli::OutputFormat;
+
+    assert_eq!(Command::parse("fsck"), Command::Fsck);
+    let img = create_test_filesystem();
+    let mut cli = unsafe { Cli::new(&img).unwrap() };
+    run(&mut cli, "write /a.txt hello").unwrap();
+    assert_eq!(run(&mut cli, "fsck").unwrap(), "1 directory, 1 file, 2 clusters: no problems found\n");
+
+    // taille de /a.txt faussee dans son entree : 1000 octets sur un seul cluster
+    let Volume::Fat(fs) = cli.volume() else { unreachable!() };
+    let mut image = fs.device_data().to_vec();
+    let root = common::cluster_offset(&image, 2);
+    let entry = (0..16).map(|i| root + i * 32).find(|&offset| &image[offset..offset + 11] == b"A       TXT").unwrap();
+    image[entry + 28..entry + 32].copy_from_slice(&1000u32.to_le_bytes());
+    let mut cli = unsafe { Cli::new(&image).unwrap() };
+    assert_eq!(
+        run(&mut cli, "fsck").unwrap(),
+        "/a.txt: size is 1000 bytes but 1 cluster allocated\n\
+         1 directory, 1 file, 2 clusters: 1 problem found\n"
+    );
+
+    let mut cli = cli.with_output_format(OutputFormat::Json);
+    assert_eq!(
+        run(&mut cli, "fsck").unwrap(),
+        "{\"clean\":false,\"directories\":1,\"files\":1,\"reachable_clusters\":2,\"issues\":[\
+         {\"kind\":\"size_mismatch\",\"path\":\"/a.txt\",\"message\":\"/a.txt: size is 1000 bytes but 1 cluster allocated\",\
+         \"size\":1000,\"clusters\":1}]}\n"
+    );
+}
+
+#[test]
+fn test_json_output() {
+    use mini_fat32::cli::json::{error_document, Json};
+    use mini_fat32::cli::OutputFormat;
+
+    let img = create_test_filesystem();
+    let mut cli = unsafe { Cli::new(&img).unwrap() }.with_output_format(OutputFormat::Json);
+    assert_eq!(cli.output_format(), OutputFormat::Json);
+    run(&mut cli, "write /a.txt hi").unwrap();
+    cli.filesystem_mut().create_dir("/dir").unwrap();
+
+    let ls = run(&mut cli, "ls /").unwrap();
+    assert!(ls.starts_with("{\"path\":\"/\",\"entries\":[{\"name\":\"a.txt\",\"short_name\":\"A.TXT\",\"kind\":\"file\",\"size\":2,\"attributes\":[\"archive\"],\"first_cluster\":3,"), "{}", ls);
+    assert!(ls.ends_with("}]}\n") && ls.matches('\n').count() == 1, "{}", ls);
+
+    let stat = run(&mut cli, "stat /dir").unwrap();
+    assert!(stat.contains("\"kind\":\"directory\",\"size\":0,\"allocated_size\":512,\"attributes\":[\"directory\"]"), "{}", stat);
+    assert!(stat.contains("\"entry\":{\"index\":3,\"dir_cluster\":2}"), "{}", stat);
+
+    let info = run(&mut cli, "info").unwrap();
+    assert!(info.starts_with("{\"type\":\"FAT32\",\"label\":null,"), "{}", info);
+    assert!(info.contains("\"bad_clusters\":0"), "{}", info);
+
+    let tree = run(&mut cli, "tree").unwrap();
+    assert!(tree.contains("\"children\":[{\"name\":\"a.txt\",\"short_name\":\"A.TXT\",\"kind\":\"file\",\"size\":2,\"clusters\":1}"), "{}", tree);
+    assert!(tree.ends_with("\"totals\":{\"directories\":1,\"files\":1,\"bytes\":2,\"clusters\":3}}\n"), "{}", tree);
+
+    // chaque commande a son document, plus de texte emballe
+    assert_eq!(run(&mut cli, "pwd").unwrap(), "{\"cwd\":\"/\"}\n");
+    assert_eq!(run(&mut cli, "cd dir").unwrap(), "{\"cwd\":\"/dir\"}\n");
+    assert_eq!(run(&mut cli, "cd /").unwrap(), "{\"cwd\":\"/\"}\n");
+    assert_eq!(run(&mut cli, "write /b.bin x").unwrap(), "{\"path\":\"/b.bin\",\"size\":1}\n");
+    cli.filesystem_mut().write_file("/b.bin", &[0, 0xFF]).unwrap();
+    assert_eq!(
+        run(&mut cli, "cat /*.*").unwrap(),
+        "{\"files\":[{\"path\":\"/a.txt\",\"size\":2,\"encoding\":\"utf-8\",\"data\":\"hi\"},\
+         {\"path\":\"/b.bin\",\"size\":2,\"encoding\":\"hex\",\"data\":\"00ff\"}]}\n"
+    );
+    assert_eq!(run(&mut cli, "label disk").unwrap(), "{\"label\":\"DISK\"}\n");
+    let find = run(&mut cli, "find / -name a.*").unwrap();
+    assert!(find.starts_with("{\"root\":\"/\",\"name\":\"a.*\",\"matches\":[{\"name\":\"/a.txt\",\"short_name\":\"A.TXT\","), "{}", find);
+    assert_eq!(run(&mut cli, "chain 3").unwrap(), "{\"start\":3,\"clusters\":[3],\"bytes\":512}\n");
+    assert_eq!(
+        run(&mut cli, "fat 3").unwrap(),
+        "{\"cluster\":3,\"fat_type\":\"FAT32\",\"value\":268435455,\"meaning\":\"end of chain\"}\n"
+    );
+    let hexdump = run(&mut cli, "hexdump sector 0").unwrap();
+    assert!(hexdump.starts_with("{\"unit\":\"sector\",\"index\":0,\"offset\":0,\"region\":\"boot sector\",\"data\":\"eb58"), "{}", hexdump);
+    let bpb = run(&mut cli, "bpb").unwrap();
+    assert!(bpb.contains("\"bytes_per_sector\":512,") && bpb.contains("\"root_cluster\":2,"), "{}", bpb);
+    let help = run(&mut cli, "help").unwrap();
+    assert!(help.contains("{\"usage\":\"fsck\",\"description\":\"Check chains, lost clusters, FAT copies and FSInfo (read-only)\",\"details\":[]}"), "{}", help);
+    assert_eq!(run(&mut cli, "exit").unwrap(), "{\"exit\":true}\n");
+    assert_eq!(cli.output_format(), OutputFormat::Json);
+
+    let error = run(&mut cli, "stat /missing").unwrap_err();
+    assert_eq!(error.code(), "file_not_found");
+    assert_eq!(error.to_json(), "{\"error\":{\"code\":\"file_not_found\",\"message\":\"File not found: /missing\"}}\n");
+    assert_eq!(error_document("usage", "say \"hi\"\t"), "{\"error\":{\"code\":\"usage\",\"message\":\"say \\\"hi\\\"\\t\"}}\n");
+    assert_eq!(Json::Array(vec![Json::Null, true.into(), 7u32.into(), "\u{1}".into()]).to_string(), "[null,true,7,\"\\u0001\"]");
+}
+
+#[test]
+fn test_binary_json_flag() {
+    use std::process::Command as Process;
+
+    let image = std::env::temp_dir().join(format!("mini-fat32-json-{}.img", std::process::id()));
+    std::fs::write(&image, create_test_filesystem()).unwrap();
+    let binary = env!("CARGO_BIN_EXE_mini-fat32");
+
+    let info = Process::new(binary).arg("--json").arg(&image).arg("info").output().unwrap();
+    assert_eq!(info.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&info.stdout).starts_with("{\"type\":\"FAT32\","));
+
+    // erreurs sur stdout en JSON, meme code de sortie qu'en texte
+    let missing = Process::new(binary).arg(&image).args(["--json", "cat", "/missing.txt"]).output().unwrap();
+    assert_eq!(missing.status.code(), Some(1));
+    assert_eq!(missing.stdout, b"{\"error\":{\"code\":\"file_not_found\",\"message\":\"File not found: /missing.txt\"}}\n");
+    assert!(missing.stderr.is_empty());
+
+    let usage = Process::new(binary).arg("--json").arg(&image).arg("frobnicate").output().unwrap();
+    assert_eq!(usage.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&usage.stdout).starts_with("{\"error\":{\"code\":\"usage\","));
+
+    std::fs::remove_file(&image).unwrap();
+}
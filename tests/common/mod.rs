@@ -0,0 +1,60 @@
+// Helpers partages par les tests d'integration (`mod common;`)
+
+// chaque fichier de test n'utilise qu'une partie des helpers
+#![allow(dead_code)]
+
+use mini_fat32::fs::format::{self, FormatOptions};
+
+/// Taille des images de test : 70000 secteurs de 512 octets, 1 secteur par cluster
+pub const IMAGE_SIZE: u64 = 70000 * 512;
+
+/// Helper: image FAT32 vide formatee (1 secteur par cluster, 2 FATs, FSInfo, racine au cluster 2)
+pub fn create_test_filesystem() -> Vec<u8> {
+    format::format(&FormatOptions::new(IMAGE_SIZE)).unwrap()
+}
+
+/// Helper: offsets des deux copies de la FAT
+pub fn fat_offsets(img: &[u8]) -> [usize; 2] {
+    let reserved = u16::from_le_bytes([img[14], img[15]]) as usize;
+    let start = reserved * 512;
+    [start, start + fat_size(img)]
+}
+
+/// Helper: taille d'une FAT en octets
+pub fn fat_size(img: &[u8]) -> usize {
+    u32::from_le_bytes([img[36], img[37], img[38], img[39]]) as usize * 512
+}
+
+/// Helper: offset d'un cluster dans l'image
+pub fn cluster_offset(img: &[u8], cluster: u32) -> usize {
+    let [_, second_fat] = fat_offsets(img);
+    second_fat + fat_size(img) + (cluster as usize - 2) * 512
+}
+
+/// Helper: valeur d'une entree dans les deux FATs
+pub fn set_fat_entry(img: &mut [u8], cluster: u32, value: u32) {
+    for fat in fat_offsets(img) {
+        let offset = fat + cluster as usize * 4;
+        img[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+}
+
+/// Helper: entree de repertoire 8.3
+pub fn dir_entry(name: &[u8; 11], attributes: u8, cluster: u32, size: u32) -> [u8; 32] {
+    let mut entry = [0u8; 32];
+    entry[0..11].copy_from_slice(name);
+    entry[11] = attributes;
+    entry[20..22].copy_from_slice(&((cluster >> 16) as u16).to_le_bytes());
+    entry[26..28].copy_from_slice(&(cluster as u16).to_le_bytes());
+    entry[28..32].copy_from_slice(&size.to_le_bytes());
+    entry
+}
+
+/// Helper: dossier d'un seul cluster, marque fin de chaine dans les FATs
+pub fn write_dir(img: &mut [u8], cluster: u32, entries: &[[u8; 32]]) {
+    set_fat_entry(img, cluster, 0x0FFF_FFFF);
+    let offset = cluster_offset(img, cluster);
+    for (i, entry) in entries.iter().enumerate() {
+        img[offset + i * 32..offset + i * 32 + 32].copy_from_slice(entry);
+    }
+}
@@ -0,0 +1,119 @@
+// Tests de l'edition de ligne du shell et de la completion
+
+#![cfg(feature = "std")]
+
+use mini_fat32::cli::complete::{complete, Completion};
+use mini_fat32::cli::editor::LineEditor;
+use mini_fat32::{Fat32Fs, FileSystem};
+
+mod common;
+
+/// Helper: volume avec `/boot/config.txt`, `/boot/cmdline.txt`, `/boot/overlays/` et `/My Notes.txt`
+fn create_tree_filesystem() -> Fat32Fs {
+    let image = common::create_test_filesystem();
+    let mut fs = unsafe { Fat32Fs::new(&image).unwrap() };
+    fs.create_dir("/boot").unwrap();
+    fs.create_dir("/boot/overlays").unwrap();
+    fs.write_file("/boot/config.txt", b"kernel=1").unwrap();
+    fs.write_file("/boot/cmdline.txt", b"quiet").unwrap();
+    fs.write_file("/My Notes.txt", b"hi").unwrap();
+    fs
+}
+
+/// Helper: saisie d'une ligne avec des touches brutes, sans completion
+fn type_line(editor: &mut LineEditor, keys: &[u8]) -> Option<String> {
+    let mut output = Vec::new();
+    editor.read_line("> ", &mut &keys[..], &mut output, |_| Completion::default()).unwrap()
+}
+
+#[test]
+fn test_complete_commands_and_paths() {
+    let mut fs = create_tree_filesystem();
+
+    let commands = complete(&fs, "ex");
+    assert_eq!(commands, Completion { start: 0, candidates: vec!["exit".into(), "export-tar".into()] });
+    assert_eq!(commands.common_prefix(), "ex");
+
+    // chemins sans tenir compte de la casse, dossiers avec `/`
+    assert_eq!(complete(&fs, "cat /bo").candidates, vec!["/boot/"]);
+    assert_eq!(complete(&fs, "cat /boot/c").candidates, vec!["/boot/cmdline.txt", "/boot/config.txt"]);
+    assert_eq!(complete(&fs, "cat /boot/C").common_prefix(), "/boot/c");
+    assert_eq!(complete(&fs, "ls /boot/o"), Completion { start: 3, candidates: vec!["/boot/overlays/".into()] });
+
+    // espaces echappes, y compris pour un mot entre quotes
+    assert_eq!(complete(&fs, "cat my").candidates, vec!["My\\ Notes.txt"]);
+    assert_eq!(complete(&fs, "cat My\\ N").candidates, vec!["My\\ Notes.txt"]);
+    assert_eq!(complete(&fs, "cat 'My N"), Completion { start: 4, candidates: vec!["My\\ Notes.txt".into()] });
+
+    // relatif au dossier courant ; `.` et `..` seulement si on les tape
+    fs.cd("/boot").unwrap();
+    assert_eq!(complete(&fs, "stat ").candidates, vec!["cmdline.txt", "config.txt", "overlays/"]);
+    assert_eq!(complete(&fs, "cd ..").candidates, vec!["../"]);
+
+    // chemins de l'hote : rien a proposer
+    assert!(complete(&fs, "put con").candidates.is_empty());
+    assert!(complete(&fs, "get -r config.txt con").candidates.is_empty());
+    assert_eq!(complete(&fs, "put notes.txt con").candidates, vec!["config.txt"]);
+    assert!(complete(&fs, "cat /missing/").candidates.is_empty());
+}
+
+#[test]
+fn test_line_editing_keys() {
+    let mut editor = LineEditor::new();
+    // fleche gauche x2, insertion, Home, Delete, End, Backspace
+    assert_eq!(type_line(&mut editor, b"cat ab\x1b[D\x1b[DX\x1b[H\x1b[3~\x1b[F\x7f\r").as_deref(), Some("at Xa"));
+    // parametre trop long : touche ignoree, sans debordement
+    assert_eq!(type_line(&mut editor, b"ok\x1b[99999999999999999999~\r").as_deref(), Some("ok"));
+    // Ctrl-A / Ctrl-K, Ctrl-W, Ctrl-U
+    assert_eq!(type_line(&mut editor, b"ls /boot\x01\x0bpwd\r").as_deref(), Some("pwd"));
+    assert_eq!(type_line(&mut editor, b"cat /a /b\x17\r").as_deref(), Some("cat /a "));
+    assert_eq!(type_line(&mut editor, b"junk\x15info\r").as_deref(), Some("info"));
+    // UTF-8
+    assert_eq!(type_line(&mut editor, "cat /é\r".as_bytes()).as_deref(), Some("cat /é"));
+    // Ctrl-C abandonne la ligne, Ctrl-D sur une ligne vide ou fin d'entree : None
+    assert_eq!(type_line(&mut editor, b"oops\x03").as_deref(), Some(""));
+    assert_eq!(type_line(&mut editor, b"\x04"), None);
+    assert_eq!(type_line(&mut editor, b"no newline"), None);
+
+    assert_eq!(editor.history(), ["at Xa", "ok", "pwd", "cat /a ", "info", "cat /é"]);
+}
+
+#[test]
+fn test_history_and_search() {
+    let mut editor = LineEditor::new();
+    for line in ["cd /boot", "cat config.txt", "ls", "ls"] {
+        editor.add_history(line);
+    }
+    editor.add_history("   ");
+    assert_eq!(editor.history(), ["cd /boot", "cat config.txt", "ls"]);
+
+    // haut x2 puis bas : ligne precedente ; bas apres la fin : brouillon rendu
+    assert_eq!(type_line(&mut editor, b"\x1b[A\x1b[A\x1b[B\r").as_deref(), Some("ls"));
+    assert_eq!(type_line(&mut editor, b"draft\x1b[A\x1b[B\r").as_deref(), Some("draft"));
+
+    // Ctrl-R : la plus recente qui contient le texte, Ctrl-R encore : plus ancienne
+    assert_eq!(type_line(&mut editor, b"\x12c\r").as_deref(), Some("cat config.txt"));
+    assert_eq!(type_line(&mut editor, b"\x12c\x12\x12\r").as_deref(), Some("cd /boot"));
+    // une autre touche garde la ligne trouvee pour l'editer, Ctrl-G annule
+    assert_eq!(type_line(&mut editor, b"\x12boot\x1b[C!\r").as_deref(), Some("cd /boot!"));
+    assert_eq!(type_line(&mut editor, b"pwd\x12zzz\x07\r").as_deref(), Some("pwd"));
+}
+
+#[test]
+fn test_tab_completion_in_editor() {
+    let fs = create_tree_filesystem();
+    let mut editor = LineEditor::new();
+    let mut read = |keys: &[u8]| {
+        let mut output = Vec::new();
+        let line = editor.read_line("> ", &mut &keys[..], &mut output, |line| complete(&fs, line)).unwrap();
+        (line, String::from_utf8(output).unwrap())
+    };
+
+    // un candidat : remplace, espace apres un fichier, rien apres un dossier
+    assert_eq!(read(b"ca\t/bo\tcon\t\r").0.as_deref(), Some("cat /boot/config.txt "));
+    // plusieurs candidats : debut commun, puis la liste au Tab suivant
+    let (line, output) = read(b"cat /boot/c\t\r");
+    assert_eq!(line.as_deref(), Some("cat /boot/c"));
+    assert!(output.contains("\r\ncmdline.txt  config.txt  \r\n"), "{:?}", output);
+    assert_eq!(read(b"cat /boot/co\t\x1b[H\x1b[3~\x1b[3~\x1b[3~ls\r").0.as_deref(), Some("ls /boot/config.txt "));
+}
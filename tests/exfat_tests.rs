@@ -0,0 +1,320 @@
+// Tests exFAT : boot region, up-case, ensembles d'entrees, NoFatChain
+
+use mini_fat32::{ExFatFs, FileKind, FileSystem};
+use mini_fat32::fs::exfat::{boot_checksum, entry_set_checksum, upcase_table_checksum, ExFatBootSector, UpcaseTable};
+use mini_fat32::fs::fat_table::{FatTable, FatType};
+
+const CLUSTER_COUNT: u32 = 100;
+const FAT_OFFSET: usize = 24;
+const HEAP_OFFSET: usize = 32;
+
+/// Helper: offset en octets d'un cluster du heap (clusters de 512 octets)
+fn cluster_offset(cluster: u32) -> usize {
+    (HEAP_OFFSET + (cluster as usize - 2)) * 512
+}
+
+/// Helper: table up-case compressee, seules a-z sont converties
+fn upcase_table() -> Vec<u8> {
+    let mut words = vec![0xFFFFu16, 0x61];
+    words.extend(0x41u16..=0x5A);
+    words.extend([0xFFFF, 0xFF85]);
+    words.iter().flat_map(|w| w.to_le_bytes()).collect()
+}
+
+/// Helper: NameHash d'un nom ASCII
+fn name_hash(name: &str) -> u16 {
+    let mut hash: u16 = 0;
+    for ch in name.encode_utf16() {
+        let ch = if (0x61..=0x7A).contains(&ch) { ch - 0x20 } else { ch };
+        for byte in ch.to_le_bytes() {
+            hash = hash.rotate_right(1).wrapping_add(byte as u16);
+        }
+    }
+    hash
+}
+
+/// Helper: ensemble File + Stream Extension + File Name avec son checksum
+fn file_entry_set(name: &str, attributes: u16, first_cluster: u32, length: u64, valid_length: u64, no_fat_chain: bool) -> Vec<u8> {
+    let chars: Vec<u16> = name.encode_utf16().collect();
+    let name_entries = chars.len().div_ceil(15);
+    let mut set = vec![0u8; (2 + name_entries) * 32];
+
+    set[0] = 0x85;
+    set[1] = (1 + name_entries) as u8;
+    set[4..6].copy_from_slice(&attributes.to_le_bytes());
+    // 2024-05-17 10:30:20
+    let timestamp: u32 = (((2024 - 1980) << 9 | 5 << 5 | 17) << 16) | (10 << 11 | 30 << 5 | 10);
+    set[8..12].copy_from_slice(&timestamp.to_le_bytes());
+    set[12..16].copy_from_slice(&timestamp.to_le_bytes());
+    set[16..20].copy_from_slice(&timestamp.to_le_bytes());
+
+    let stream = &mut set[32..64];
+    stream[0] = 0xC0;
+    stream[1] = 0x01 | if no_fat_chain { 0x02 } else { 0 };
+    stream[3] = chars.len() as u8;
+    stream[4..6].copy_from_slice(&name_hash(name).to_le_bytes());
+    stream[8..16].copy_from_slice(&valid_length.to_le_bytes());
+    stream[20..24].copy_from_slice(&first_cluster.to_le_bytes());
+    stream[24..32].copy_from_slice(&length.to_le_bytes());
+
+    for (i, part) in chars.chunks(15).enumerate() {
+        let entry = &mut set[(2 + i) * 32..(3 + i) * 32];
+        entry[0] = 0xC1;
+        for (j, ch) in part.iter().enumerate() {
+            entry[2 + j * 2..4 + j * 2].copy_from_slice(&ch.to_le_bytes());
+        }
+    }
+
+    let checksum = entry_set_checksum(&set);
+    set[2..4].copy_from_slice(&checksum.to_le_bytes());
+    set
+}
+
+/// Helper: image exFAT
+///
+/// /Hello World.txt              clusters 6 -> 8 (FAT), 700 octets
+/// /contiguous.bin               clusters 10-12 NoFatChain, 1300 octets
+/// /Photos/                      cluster 14 NoFatChain
+/// /Photos/A rather long photo name.jpg   cluster 15, 10 octets dont 5 valides
+fn create_exfat_image() -> Vec<u8> {
+    let mut img = vec![0u8; (HEAP_OFFSET + CLUSTER_COUNT as usize) * 512];
+
+    // Main Boot Sector
+    img[0..3].copy_from_slice(&[0xEB, 0x76, 0x90]);
+    img[3..11].copy_from_slice(b"EXFAT   ");
+    img[72..80].copy_from_slice(&((HEAP_OFFSET as u64) + CLUSTER_COUNT as u64).to_le_bytes());
+    img[80..84].copy_from_slice(&(FAT_OFFSET as u32).to_le_bytes());
+    img[84..88].copy_from_slice(&1u32.to_le_bytes());
+    img[88..92].copy_from_slice(&(HEAP_OFFSET as u32).to_le_bytes());
+    img[92..96].copy_from_slice(&CLUSTER_COUNT.to_le_bytes());
+    img[96..100].copy_from_slice(&4u32.to_le_bytes());
+    img[100..104].copy_from_slice(&0xCAFE_F00Du32.to_le_bytes());
+    img[104..106].copy_from_slice(&0x0100u16.to_le_bytes());
+    img[108] = 9;
+    img[109] = 0;
+    img[110] = 1;
+    img[510..512].copy_from_slice(&0xAA55u16.to_le_bytes());
+    for sector in 1..9 {
+        img[sector * 512 + 510..sector * 512 + 512].copy_from_slice(&0xAA55u16.to_le_bytes());
+    }
+    let checksum = boot_checksum(&img, 512);
+    for i in 0..128 {
+        img[11 * 512 + i * 4..11 * 512 + i * 4 + 4].copy_from_slice(&checksum.to_le_bytes());
+    }
+
+    // FAT : les fichiers NoFatChain n'y apparaissent pas
+    let fat = FAT_OFFSET * 512;
+    let set_fat = |img: &mut Vec<u8>, cluster: usize, value: u32| {
+        img[fat + cluster * 4..fat + cluster * 4 + 4].copy_from_slice(&value.to_le_bytes());
+    };
+    set_fat(&mut img, 0, 0xFFFF_FFF8);
+    set_fat(&mut img, 1, 0xFFFF_FFFF);
+    for cluster in [2, 3, 4, 8] {
+        set_fat(&mut img, cluster, 0xFFFF_FFFF);
+    }
+    set_fat(&mut img, 6, 8);
+
+    // bitmap d'allocation (cluster 2)
+    let bitmap = cluster_offset(2);
+    for cluster in [2u32, 3, 4, 6, 8, 10, 11, 12, 14, 15] {
+        let index = (cluster - 2) as usize;
+        img[bitmap + index / 8] |= 1 << (index % 8);
+    }
+
+    // table up-case (cluster 3)
+    let table = upcase_table();
+    img[cluster_offset(3)..cluster_offset(3) + table.len()].copy_from_slice(&table);
+
+    // racine (cluster 4)
+    let mut root = Vec::new();
+    let mut bitmap_entry = [0u8; 32];
+    bitmap_entry[0] = 0x81;
+    bitmap_entry[20..24].copy_from_slice(&2u32.to_le_bytes());
+    bitmap_entry[24..32].copy_from_slice(&13u64.to_le_bytes());
+    root.extend_from_slice(&bitmap_entry);
+    let mut upcase_entry = [0u8; 32];
+    upcase_entry[0] = 0x82;
+    upcase_entry[4..8].copy_from_slice(&upcase_table_checksum(&table).to_le_bytes());
+    upcase_entry[20..24].copy_from_slice(&3u32.to_le_bytes());
+    upcase_entry[24..32].copy_from_slice(&(table.len() as u64).to_le_bytes());
+    root.extend_from_slice(&upcase_entry);
+    let mut label_entry = [0u8; 32];
+    label_entry[0] = 0x83;
+    label_entry[1] = 6;
+    for (i, ch) in "SDCARD".encode_utf16().enumerate() {
+        label_entry[2 + i * 2..4 + i * 2].copy_from_slice(&ch.to_le_bytes());
+    }
+    root.extend_from_slice(&label_entry);
+    root.extend(file_entry_set("Hello World.txt", 0x20, 6, 700, 700, false));
+    root.extend(file_entry_set("contiguous.bin", 0x20, 10, 1300, 1300, true));
+    root.extend(file_entry_set("Photos", 0x10, 14, 512, 512, true));
+    img[cluster_offset(4)..cluster_offset(4) + root.len()].copy_from_slice(&root);
+
+    // donnees
+    for (i, byte) in img[cluster_offset(6)..cluster_offset(6) + 512].iter_mut().enumerate() {
+        *byte = b'a' + (i % 26) as u8;
+    }
+    img[cluster_offset(8)..cluster_offset(8) + 188].fill(b'Z');
+    img[cluster_offset(10)..cluster_offset(10) + 1300].fill(0x42);
+
+    let photos = file_entry_set("A rather long photo name.jpg", 0x20, 15, 10, 5, true);
+    img[cluster_offset(14)..cluster_offset(14) + photos.len()].copy_from_slice(&photos);
+    img[cluster_offset(15)..cluster_offset(15) + 10].copy_from_slice(b"JPEG!junk!");
+
+    img
+}
+
+#[test]
+fn test_exfat_boot_sector() {
+    let img = create_exfat_image();
+    assert!(ExFatBootSector::is_exfat(&img));
+    let bs = unsafe { ExFatBootSector::from_bytes(&img).unwrap() };
+    assert_eq!(bs.bytes_per_sector(), 512);
+    assert_eq!(bs.cluster_size(), 512);
+    assert_eq!(bs.root_cluster(), 4);
+    assert_eq!(bs.cluster_count(), CLUSTER_COUNT);
+}
+
+#[test]
+fn test_exfat_root_cluster_out_of_range() {
+    let mut img = create_exfat_image();
+    // ClusterCount a 0xFFFFFFFF : pas de debordement, 0xFFFFFFFF reste un cluster valide
+    img[92..96].copy_from_slice(&u32::MAX.to_le_bytes());
+    img[96..100].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(unsafe { ExFatBootSector::from_bytes(&img) }.is_ok());
+
+    let mut img = create_exfat_image();
+    img[96..100].copy_from_slice(&(CLUSTER_COUNT + 2).to_le_bytes());
+    assert!(unsafe { ExFatBootSector::from_bytes(&img) }.is_err());
+    img[96..100].copy_from_slice(&(CLUSTER_COUNT + 1).to_le_bytes());
+    assert!(unsafe { ExFatBootSector::from_bytes(&img) }.is_ok());
+}
+
+#[test]
+fn test_exfat_boot_checksum_mismatch() {
+    let mut img = create_exfat_image();
+    img[120] ^= 0xFF; // boot code couvert par le checksum
+    assert!(unsafe { ExFatFs::new(&img) }.is_err());
+
+    // VolumeFlags est exclu du checksum
+    let mut img = create_exfat_image();
+    img[106] = 0x02;
+    assert!(unsafe { ExFatFs::new(&img) }.is_ok());
+}
+
+#[test]
+fn test_exfat_list_root() {
+    let img = create_exfat_image();
+    let fs = unsafe { ExFatFs::new(&img).unwrap() };
+    assert_eq!(fs.volume_label(), Some("SDCARD"));
+    assert!(fs.allocation_bitmap().is_allocated(14));
+    assert!(!fs.allocation_bitmap().is_allocated(13));
+    assert_eq!(fs.allocation_bitmap().free_clusters(), CLUSTER_COUNT - 10);
+
+    let entries = fs.list("/").unwrap();
+    let names: Vec<String> = entries.iter().map(|e| e.name().unwrap()).collect();
+    assert_eq!(names, vec!["Hello World.txt", "contiguous.bin", "Photos"]);
+    assert!(entries[2].is_directory());
+    assert_eq!(entries[0].file_size(), 700);
+    assert_eq!(entries[0].entry.short_name().unwrap(), "HELLOWOR.TXT");
+}
+
+#[test]
+fn test_exfat_read_fat_chain_and_contiguous() {
+    let img = create_exfat_image();
+    let fs = unsafe { ExFatFs::new(&img).unwrap() };
+
+    // recherche sans casse via la table up-case
+    let data = fs.read_file("/HELLO WORLD.TXT").unwrap();
+    assert_eq!(data.len(), 700);
+    assert_eq!(data[511], b'a' + (511 % 26) as u8);
+    assert!(data[512..].iter().all(|&b| b == b'Z'));
+
+    let data = fs.read_file("/contiguous.bin").unwrap();
+    assert_eq!(data.len(), 1300);
+    assert!(data.iter().all(|&b| b == 0x42));
+}
+
+#[test]
+fn test_exfat_cd_and_valid_data_length() {
+    let img = create_exfat_image();
+    let mut fs = unsafe { ExFatFs::new(&img).unwrap() };
+
+    fs.cd("photos").unwrap();
+    assert_eq!(fs.pwd(), "/photos");
+    let names: Vec<String> = fs.list(".").unwrap().iter().map(|e| e.name().unwrap()).collect();
+    assert_eq!(names, vec!["A rather long photo name.jpg"]);
+
+    // les octets au dela de ValidDataLength sont lus comme des zeros
+    assert_eq!(fs.read_file("A rather long photo name.jpg").unwrap(), b"JPEG!\0\0\0\0\0");
+
+    fs.cd("..").unwrap();
+    assert_eq!(fs.pwd(), "/");
+    assert!(fs.cd("/contiguous.bin").is_err());
+    assert!(fs.write_file("/new.txt", b"x").is_err());
+}
+
+#[test]
+fn test_exfat_entry_set_checksum_mismatch() {
+    let mut img = create_exfat_image();
+    // nom du premier fichier modifie sans recalculer le checksum
+    let first_name = cluster_offset(4) + 3 * 32 + 2 * 32 + 2;
+    img[first_name] = b'J';
+    let fs = unsafe { ExFatFs::new(&img).unwrap() };
+
+    // seul l'ensemble corrompu est ignore
+    let names: Vec<String> = fs.list("/").unwrap().iter().map(|e| e.name().unwrap()).collect();
+    assert_eq!(names, vec!["contiguous.bin", "Photos"]);
+    assert!(fs.read_file("/Hello World.txt").is_err());
+    assert_eq!(fs.read_file("/contiguous.bin").unwrap().len(), 1300);
+}
+
+#[test]
+fn test_exfat_fat_entries_use_32_bits() {
+    let mut fat = Vec::new();
+    for value in [0xFFFF_FFF8u32, 0xFFFF_FFFF, 0x1000_0003, 0xFFFF_FFF7, 0xFFFF_FFFF] {
+        fat.extend_from_slice(&value.to_le_bytes());
+    }
+    let table = unsafe { FatTable::from_bytes_with_type(&fat, FatType::ExFat).unwrap() };
+    // pas de masque sur 28 bits comme en FAT32
+    assert_eq!(table.get_entry(2).unwrap(), 0x1000_0003);
+    assert!(!table.is_end_of_chain(2));
+    assert!(table.is_bad_cluster(3));
+    assert!(table.is_end_of_chain(4));
+
+    let fat32 = unsafe { FatTable::from_bytes_with_type(&fat, FatType::Fat32).unwrap() };
+    assert_eq!(fat32.get_entry(2).unwrap(), 0x0000_0003);
+}
+
+#[test]
+fn test_upcase_table_decoding() {
+    let table = UpcaseTable::from_bytes(&upcase_table()).unwrap();
+    assert_eq!(table.upcase('a' as u16), 'A' as u16);
+    assert_eq!(table.upcase('z' as u16), 'Z' as u16);
+    assert_eq!(table.upcase('{' as u16), '{' as u16);
+    assert_eq!(table.upcase(0x00E9), 0x00E9);
+    assert_eq!(table.name_hash(&"abc".encode_utf16().collect::<Vec<_>>()), name_hash("ABC"));
+}
+
+#[test]
+fn test_exfat_metadata() {
+    let img = create_exfat_image();
+    let fs = unsafe { ExFatFs::new(&img).unwrap() };
+
+    let hello = fs.metadata("/hello world.txt").unwrap();
+    assert_eq!(hello.kind, FileKind::File);
+    assert_eq!(hello.name(), "Hello World.txt");
+    assert_eq!((hello.size, hello.allocated_size), (700, 1024));
+    assert_eq!(hello.first_cluster, 6);
+    assert!(hello.location.is_none());
+
+    // NoFatChain : taille allouee deduite de la longueur
+    assert_eq!(fs.metadata("/contiguous.bin").unwrap().allocated_size, 1536);
+    assert!(fs.metadata("/Photos").unwrap().is_directory());
+
+    let root = fs.metadata("/").unwrap();
+    assert!(root.is_directory());
+    assert_eq!(root.first_cluster, 4);
+    assert!(fs.exists("/photos/A RATHER LONG PHOTO NAME.JPG"));
+    assert!(!fs.exists("/missing.txt"));
+}
@@ -0,0 +1,248 @@
+// Tests FAT12 / FAT16 : detection du type et racine fixe
+
+use mini_fat32::{Fat32Fs, FatType, FileSystem};
+use mini_fat32::fs::boot::BootSector;
+use mini_fat32::fs::fat_table::FatTable;
+
+/// Helper: boot sector FAT12/16 (BPB etendu a l'offset 36)
+fn create_boot_sector(
+    sectors_per_cluster: u8,
+    root_entries: u16,
+    total_sectors: u16,
+    sectors_per_fat: u16,
+    fs_type: &[u8; 8],
+) -> Vec<u8> {
+    let mut bs = vec![0u8; 512];
+    bs[0] = 0xEB;
+    bs[1] = 0x3C;
+    bs[2] = 0x90;
+    bs[3..11].copy_from_slice(b"MSWIN4.1");
+    bs[11..13].copy_from_slice(&512u16.to_le_bytes());
+    bs[13] = sectors_per_cluster;
+    bs[14..16].copy_from_slice(&1u16.to_le_bytes()); // 1 reserved sector
+    bs[16] = 2;
+    bs[17..19].copy_from_slice(&root_entries.to_le_bytes());
+    bs[19..21].copy_from_slice(&total_sectors.to_le_bytes());
+    bs[21] = 0xF0;
+    bs[22..24].copy_from_slice(&sectors_per_fat.to_le_bytes());
+    bs[38] = 0x29;
+    bs[39..43].copy_from_slice(&0x1234_5678u32.to_le_bytes());
+    bs[43..54].copy_from_slice(b"NO NAME    ");
+    bs[54..62].copy_from_slice(fs_type);
+    bs[510..512].copy_from_slice(&0xAA55u16.to_le_bytes());
+    bs
+}
+
+/// Helper: entree de repertoire 8.3
+fn dir_entry(name: &[u8; 11], attributes: u8, cluster: u16, size: u32) -> [u8; 32] {
+    let mut entry = [0u8; 32];
+    entry[0..11].copy_from_slice(name);
+    entry[11] = attributes;
+    entry[26..28].copy_from_slice(&cluster.to_le_bytes());
+    entry[28..32].copy_from_slice(&size.to_le_bytes());
+    entry
+}
+
+/// Helper: ecrire une entree FAT12 (deux entrees par trois octets)
+fn set_fat12(fat: &mut [u8], cluster: usize, value: u16) {
+    let offset = cluster + cluster / 2;
+    let mut pair = u16::from_le_bytes([fat[offset], fat[offset + 1]]);
+    if cluster % 2 == 1 {
+        pair = (pair & 0x000F) | (value << 4);
+    } else {
+        pair = (pair & 0xF000) | (value & 0x0FFF);
+    }
+    fat[offset..offset + 2].copy_from_slice(&pair.to_le_bytes());
+}
+
+/// Helper: disquette 1.44M FAT12
+/// racine fixe : HELLO.TXT (cluster 2) et SUB (cluster 3)
+/// SUB : `.`, `..` et LONG.TXT sur deux clusters (4 -> 5)
+fn create_fat12_image() -> Vec<u8> {
+    let mut img = create_boot_sector(1, 224, 2880, 9, b"FAT12   ");
+    img.resize(2880 * 512, 0);
+
+    let fat_start = 512;
+    {
+        let fat = &mut img[fat_start..fat_start + 9 * 512];
+        set_fat12(fat, 0, 0xFF0);
+        set_fat12(fat, 1, 0xFFF);
+        set_fat12(fat, 2, 0xFFF);
+        set_fat12(fat, 3, 0xFFF);
+        set_fat12(fat, 4, 5);
+        set_fat12(fat, 5, 0xFFF);
+    }
+
+    let root_start = (1 + 2 * 9) * 512;
+    let data_start = root_start + 14 * 512;
+    let cluster = |n: usize| data_start + (n - 2) * 512;
+
+    img[root_start..root_start + 32].copy_from_slice(&dir_entry(b"HELLO   TXT", 0x20, 2, 6));
+    img[root_start + 32..root_start + 64].copy_from_slice(&dir_entry(b"SUB        ", 0x10, 3, 0));
+    img[cluster(2)..cluster(2) + 6].copy_from_slice(b"hello\n");
+
+    let sub = cluster(3);
+    img[sub..sub + 32].copy_from_slice(&dir_entry(b".          ", 0x10, 3, 0));
+    img[sub + 32..sub + 64].copy_from_slice(&dir_entry(b"..         ", 0x10, 0, 0));
+    img[sub + 64..sub + 96].copy_from_slice(&dir_entry(b"LONG    TXT", 0x20, 4, 600));
+    for (i, byte) in img[cluster(4)..cluster(4) + 1024].iter_mut().enumerate() {
+        *byte = b'a' + (i % 26) as u8;
+    }
+
+    img
+}
+
+/// Helper: petit volume FAT16 (5000 clusters)
+/// racine fixe : DATA.BIN (clusters 2 -> 3 -> 4)
+fn create_fat16_image() -> Vec<u8> {
+    let total_sectors = 1 + 2 * 20 + 32 + 5000;
+    let mut img = create_boot_sector(1, 512, total_sectors as u16, 20, b"FAT16   ");
+    img.resize(total_sectors * 512, 0);
+
+    let fat_start = 512;
+    let entries: [(usize, u16); 5] = [(0, 0xFFF8), (1, 0xFFFF), (2, 3), (3, 4), (4, 0xFFFF)];
+    for (cluster, value) in entries {
+        let offset = fat_start + cluster * 2;
+        img[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+    }
+
+    let root_start = (1 + 2 * 20) * 512;
+    let data_start = root_start + 32 * 512;
+    img[root_start..root_start + 32].copy_from_slice(&dir_entry(b"DATA    BIN", 0x20, 2, 1500));
+    for (i, byte) in img[data_start..data_start + 1536].iter_mut().enumerate() {
+        *byte = (i / 512) as u8 + 1;
+    }
+
+    img
+}
+
+#[test]
+fn test_detect_fat_type_by_cluster_count() {
+    assert_eq!(FatType::from_cluster_count(4084), FatType::Fat12);
+    assert_eq!(FatType::from_cluster_count(4085), FatType::Fat16);
+    assert_eq!(FatType::from_cluster_count(65524), FatType::Fat16);
+    assert_eq!(FatType::from_cluster_count(65525), FatType::Fat32);
+
+    // fs_type n'est qu'informatif : seul le nombre de clusters compte
+    let img = create_boot_sector(1, 224, 2880, 9, b"FAT16   ");
+    let bs = unsafe { BootSector::from_bytes(&img).unwrap() };
+    assert_eq!(bs.fat_type(), FatType::Fat12);
+    assert_eq!(bs.root_dir_sectors(), 14);
+    assert_eq!(bs.data_start_sector(), 33);
+}
+
+#[test]
+fn test_fat12_packed_entries() {
+    let img = create_fat12_image();
+    let fat = unsafe { FatTable::from_bytes_with_type(&img[512..512 + 9 * 512], FatType::Fat12).unwrap() };
+    assert_eq!(fat.get_entry(2).unwrap(), 0xFFF);
+    assert_eq!(fat.get_entry(4).unwrap(), 5);
+    assert!(fat.is_end_of_chain(5));
+    assert!(fat.is_free_cluster(6));
+}
+
+#[test]
+fn test_fat12_list_read_and_cd() {
+    let img = create_fat12_image();
+    let mut fs = unsafe { Fat32Fs::new(&img).unwrap() };
+    assert_eq!(fs.fat_type(), FatType::Fat12);
+
+    let names: Vec<String> = fs.list("/").unwrap().iter().map(|e| e.name().unwrap()).collect();
+    assert_eq!(names, vec!["HELLO.TXT", "SUB"]);
+    assert_eq!(fs.read_file("/hello.txt").unwrap(), b"hello\n");
+
+    fs.cd("sub").unwrap();
+    assert_eq!(fs.pwd(), "/sub");
+    let data = fs.read_file("long.txt").unwrap();
+    assert_eq!(data.len(), 600);
+    assert_eq!(data[599], b'a' + (599 % 26) as u8);
+
+    // `..` pointe sur le cluster 0 = racine fixe
+    let parent: Vec<String> = fs.list("/sub/..").unwrap().iter().map(|e| e.name().unwrap()).collect();
+    assert_eq!(parent, vec!["HELLO.TXT", "SUB"]);
+}
+
+#[test]
+fn test_fat16_read_chain() {
+    let img = create_fat16_image();
+    let fs = unsafe { Fat32Fs::new(&img).unwrap() };
+    assert_eq!(fs.fat_type(), FatType::Fat16);
+
+    let data = fs.read_file("/DATA.BIN").unwrap();
+    assert_eq!(data.len(), 1500);
+    assert_eq!(data[0], 1);
+    assert_eq!(data[700], 2);
+    assert_eq!(data[1499], 3);
+}
+
+#[test]
+fn test_fat12_write_packs_entries() {
+    let img = create_fat12_image();
+    let mut fs = unsafe { Fat32Fs::new(&img).unwrap() };
+    fs.write_file("/sub/new file.txt", &[b'x'; 1200]).unwrap();
+
+    let written = fs.into_device_data();
+    let fs = unsafe { Fat32Fs::new(&written).unwrap() };
+    assert_eq!(fs.read_file("/SUB/NEW FILE.TXT").unwrap(), vec![b'x'; 1200]);
+    // les entrees voisines deja empaquetees ne sont pas abimees
+    assert_eq!(fs.read_file("/sub/long.txt").unwrap().len(), 600);
+    let fat = unsafe { FatTable::from_bytes_with_type(&written[512..512 + 9 * 512], FatType::Fat12).unwrap() };
+    assert_eq!(fat.get_entry(4).unwrap(), 5);
+    assert_eq!(fat.get_entry(6).unwrap(), 7);
+    assert_eq!(fat.get_entry(8).unwrap(), 0xFFF);
+}
+
+#[test]
+fn test_fat12_metadata_of_fixed_root() {
+    let img = create_fat12_image();
+    let fs = unsafe { Fat32Fs::new(&img).unwrap() };
+
+    // racine fixe : pas de cluster, 14 secteurs
+    let root = fs.metadata("/").unwrap();
+    assert_eq!((root.first_cluster, root.allocated_size), (0, 14 * 512));
+
+    let long = fs.metadata("/sub/long.txt").unwrap();
+    assert_eq!((long.size, long.allocated_size), (600, 1024));
+    let location = long.location.unwrap();
+    assert_eq!((location.dir_cluster, location.index), (3, 2));
+    assert!(fs.metadata("/sub").unwrap().is_directory());
+}
+
+#[test]
+fn test_fat12_read_dir_fixed_root() {
+    let img = create_fat12_image();
+    let fs = unsafe { Fat32Fs::new(&img).unwrap() };
+    let names: Vec<String> = fs.read_dir("/").unwrap().map(|e| e.unwrap().name().unwrap()).collect();
+    assert_eq!(names, vec!["HELLO.TXT", "SUB"]);
+    let names: Vec<String> = fs.read_dir("/sub").unwrap().map(|e| e.unwrap().name().unwrap()).collect();
+    assert_eq!(names, vec![".", "..", "LONG.TXT"]);
+}
+
+#[test]
+fn test_fat12_volume_info() {
+    let img = create_fat12_image();
+    let fs = unsafe { Fat32Fs::new(&img).unwrap() };
+    let info = fs.volume_info();
+    assert_eq!(info.fat_type, FatType::Fat12);
+    assert_eq!(info.total_clusters, 2847);
+    assert_eq!((info.used_clusters, info.free_clusters, info.bad_clusters), (4, 2843, 0));
+    // BPB etendu FAT12/16 a l'offset 36, "NO NAME" = pas de label
+    assert_eq!(info.volume_id, Some(0x1234_5678));
+    assert_eq!(info.volume_label, None);
+    assert!(fs.fs_info().is_none());
+}
+
+#[test]
+fn test_fat12_set_label_in_fixed_root() {
+    let img = create_fat12_image();
+    let mut fs = unsafe { Fat32Fs::new(&img).unwrap() };
+    assert_eq!(fs.label(), None);
+    fs.set_label("FLOPPY").unwrap();
+    assert_eq!(fs.label().as_deref(), Some("FLOPPY"));
+
+    let img = fs.into_device_data();
+    assert_eq!(&img[43..54], b"FLOPPY     ");
+    let fs = unsafe { Fat32Fs::new(&img).unwrap() };
+    assert_eq!(fs.volume_info().volume_label.as_deref(), Some("FLOPPY"));
+    assert_eq!(fs.list("/").unwrap().len(), 2);
+}
@@ -0,0 +1,55 @@
+// Tests du formatage FAT32
+
+use mini_fat32::fs::format::{self, FormatOptions};
+use mini_fat32::{DateTime, Fat32Fs, FatType, FileSystem, FileSystemError};
+
+const MIB: u64 = 1024 * 1024;
+
+#[test]
+fn test_format_empty_volume() {
+    let image = format::format(&FormatOptions::new(64 * MIB).with_volume_id(0xCAFE_F00D)).unwrap();
+    assert_eq!(image.len() as u64, 64 * MIB);
+    // copie de secours du boot sector et de FSInfo
+    assert_eq!(image[..1024], image[6 * 512..8 * 512]);
+
+    let mut fs = unsafe { Fat32Fs::new(&image).unwrap() };
+    assert_eq!(fs.fat_type(), FatType::Fat32);
+    assert!(fs.list("/").unwrap().is_empty());
+
+    let info = fs.volume_info();
+    assert_eq!(info.cluster_size, 512);
+    assert_eq!(info.used_clusters, 1);
+    assert_eq!(info.fs_info_free_clusters, Some(info.free_clusters));
+    assert_eq!(info.volume_id, Some(0xCAFE_F00D));
+    assert_eq!(info.volume_label, None);
+
+    fs.create_dir("/boot").unwrap();
+    fs.write_file("/boot/config.txt", b"kernel=1").unwrap();
+    assert_eq!(fs.read_file("/boot/config.txt").unwrap(), b"kernel=1");
+}
+
+#[test]
+fn test_format_label_and_cluster_size() {
+    let stamp = DateTime { year: 2024, month: 1, day: 2, hour: 3, minute: 4, second: 6, millisecond: 0 };
+    let options = FormatOptions::new(300 * MIB).with_label("boot").with_timestamp(stamp);
+    let image = format::format(&options).unwrap();
+    // meme options, meme image
+    assert_eq!(image, format::format(&options).unwrap());
+
+    let fs = unsafe { Fat32Fs::new(&image).unwrap() };
+    assert_eq!(fs.label().as_deref(), Some("BOOT"));
+    assert_eq!(&image[71..82], b"BOOT       ");
+    // plus de 260 Mo : clusters de 4 Ko
+    assert_eq!(fs.volume_info().cluster_size, 4096);
+
+    let image = format::format(&FormatOptions::new(300 * MIB).with_sectors_per_cluster(1)).unwrap();
+    assert_eq!(unsafe { Fat32Fs::new(&image).unwrap() }.volume_info().cluster_size, 512);
+}
+
+#[test]
+fn test_format_rejects_invalid_options() {
+    // moins de 65525 clusters : pas du FAT32
+    assert!(matches!(format::format(&FormatOptions::new(16 * MIB)), Err(FileSystemError::Unsupported(_))));
+    assert!(format::format(&FormatOptions::new(64 * MIB).with_sectors_per_cluster(3)).is_err());
+    assert!(format::format(&FormatOptions::new(64 * MIB).with_label("far too long label")).is_err());
+}
@@ -0,0 +1,114 @@
+// Tests de Fat32Fs::check (fsck en lecture seule)
+
+use mini_fat32::fs::{FsckIssue, FsckReport};
+use mini_fat32::{Fat32Fs, FileSystem};
+
+mod common;
+
+use common::{cluster_offset, create_test_filesystem, dir_entry, fat_offsets, set_fat_entry};
+
+/// Helper: monte une image modifiee et la verifie
+fn check(img: &[u8]) -> FsckReport {
+    let fs = unsafe { Fat32Fs::new(img).unwrap() };
+    fs.check().unwrap()
+}
+
+/// Helper: change le premier cluster d'une entree 8.3 de la racine (cluster 2)
+fn set_root_entry_cluster(img: &mut [u8], name: &[u8; 11], cluster: u32) {
+    let root = cluster_offset(img, 2);
+    let entry = (0..16).map(|i| root + i * 32).find(|&offset| &img[offset..offset + 11] == name).unwrap();
+    img[entry + 20..entry + 22].copy_from_slice(&((cluster >> 16) as u16).to_le_bytes());
+    img[entry + 26..entry + 28].copy_from_slice(&(cluster as u16).to_le_bytes());
+}
+
+#[test]
+fn test_check_clean_volume() {
+    let img = create_test_filesystem();
+    let mut fs = unsafe { Fat32Fs::new(&img).unwrap() };
+    fs.write_file("/A.TXT", b"hello").unwrap();
+    fs.create_dir("/D").unwrap();
+    fs.write_file("/D/B.TXT", &[7u8; 1000]).unwrap();
+    fs.create_file("/D/EMPTY").unwrap();
+
+    let report = fs.check().unwrap();
+    assert!(report.is_clean(), "{:?}", report.issues);
+    assert_eq!((report.directories, report.files), (2, 3));
+    // racine, A.TXT, D, B.TXT sur deux clusters
+    assert_eq!(report.reachable_clusters, 5);
+}
+
+#[test]
+fn test_check_size_mismatch_and_lost_clusters() {
+    let img = create_test_filesystem();
+    let mut fs = unsafe { Fat32Fs::new(&img).unwrap() };
+    fs.write_file("/A.TXT", &[1u8; 1000]).unwrap();
+    let mut img = fs.into_device_data();
+    // chaine 3 -> 4 coupee apres 3 : le cluster 4 n'est plus atteint
+    set_fat_entry(&mut img, 3, 0x0FFF_FFFF);
+
+    let report = check(&img);
+    assert_eq!(
+        report.issues,
+        vec![
+            FsckIssue::SizeMismatch { path: "/A.TXT".into(), size: 1000, clusters: 1 },
+            FsckIssue::LostClusters { count: 1, first: 4 },
+        ]
+    );
+    assert_eq!(report.issues[0].to_string(), "/A.TXT: size is 1000 bytes but 1 cluster allocated");
+    assert_eq!(report.issues[0].kind(), "size_mismatch");
+    assert_eq!(report.issues[1].path(), None);
+}
+
+#[test]
+fn test_check_cross_linked_and_broken_chains() {
+    let img = create_test_filesystem();
+    let mut fs = unsafe { Fat32Fs::new(&img).unwrap() };
+    fs.write_file("/A.TXT", b"a").unwrap();
+    fs.write_file("/B.TXT", b"b").unwrap();
+    fs.write_file("/C.TXT", &[3u8; 1000]).unwrap();
+    let free = fs.fs_info().unwrap().free_count.unwrap();
+    let mut img = fs.into_device_data();
+    // B.TXT partage le cluster de A.TXT, C.TXT commence sur un cluster libre
+    set_root_entry_cluster(&mut img, b"B       TXT", 3);
+    set_fat_entry(&mut img, 5, 0);
+
+    let report = check(&img);
+    assert_eq!(
+        report.issues,
+        vec![
+            FsckIssue::CrossLinked { path: "/B.TXT".into(), cluster: 3, other: "/A.TXT".into() },
+            FsckIssue::BrokenChain { path: "/C.TXT".into(), reason: "starts on free cluster 5".into() },
+            FsckIssue::LostClusters { count: 2, first: 4 },
+            // le cluster 5 a ete libere sans mettre FSInfo a jour
+            FsckIssue::FreeCountMismatch { stored: free, actual: free + 1 },
+        ]
+    );
+    assert_eq!(report.files, 3);
+}
+
+#[test]
+fn test_check_directory_loop() {
+    let img = create_test_filesystem();
+    let mut fs = unsafe { Fat32Fs::new(&img).unwrap() };
+    fs.create_dir("/D").unwrap();
+    let mut img = fs.into_device_data();
+    // un sous-dossier de /D qui pointe sur la racine : signale, pas parcouru
+    let entry = dir_entry(b"LOOP       ", 0x10, 2, 0);
+    let offset = cluster_offset(&img, 3) + 2 * 32;
+    img[offset..offset + 32].copy_from_slice(&entry);
+
+    let report = check(&img);
+    assert_eq!(report.issues, vec![FsckIssue::CrossLinked { path: "/D/LOOP".into(), cluster: 2, other: "/".into() }]);
+    assert_eq!(report.directories, 2);
+}
+
+#[test]
+fn test_check_fat_copy_mismatch() {
+    let mut img = create_test_filesystem();
+    let [_, second_fat] = fat_offsets(&img);
+    img[second_fat + 100 * 4] = 0xF7;
+
+    let report = check(&img);
+    assert_eq!(report.issues, vec![FsckIssue::FatCopyMismatch { copy: 1 }]);
+    assert_eq!(report.issues[0].to_string(), "FAT copy 1 differs from the first FAT");
+}
@@ -0,0 +1,142 @@
+// Tests des motifs glob (`*`, `?`, `[...]`, `**`)
+
+use mini_fat32::{Fat32Fs, FileSystem, Path};
+use mini_fat32::fs::glob;
+
+mod common;
+
+/// Helper: image FAT32 avec une arborescence
+/// /DCIM/IMG_0001.JPG, /DCIM/IMG_0002.JPG, /DCIM/NOTES.TXT, /DCIM/OLD/IMG_0003.JPG,
+/// /LOGS/APP.LOG, /README.TXT ; /DCIM/OLD/BACK pointe sur DCIM (cycle)
+fn create_tree_filesystem() -> Vec<u8> {
+    let mut img = common::create_test_filesystem();
+    common::write_dir(&mut img, 2, &[
+        common::dir_entry(b"DCIM       ", 0x10, 3, 0),
+        common::dir_entry(b"LOGS       ", 0x10, 5, 0),
+        common::dir_entry(b"README  TXT", 0x20, 0, 0),
+    ]);
+    common::write_dir(&mut img, 3, &[
+        common::dir_entry(b".          ", 0x10, 3, 0),
+        common::dir_entry(b"..         ", 0x10, 0, 0),
+        common::dir_entry(b"IMG_0001JPG", 0x20, 0, 0),
+        common::dir_entry(b"IMG_0002JPG", 0x20, 0, 0),
+        common::dir_entry(b"NOTES   TXT", 0x20, 0, 0),
+        common::dir_entry(b"OLD        ", 0x10, 4, 0),
+    ]);
+    common::write_dir(&mut img, 4, &[
+        common::dir_entry(b".          ", 0x10, 4, 0),
+        common::dir_entry(b"..         ", 0x10, 3, 0),
+        common::dir_entry(b"IMG_0003JPG", 0x20, 0, 0),
+        common::dir_entry(b"BACK       ", 0x10, 3, 0),
+    ]);
+    common::write_dir(&mut img, 5, &[
+        common::dir_entry(b".          ", 0x10, 5, 0),
+        common::dir_entry(b"..         ", 0x10, 0, 0),
+        common::dir_entry(b"APP     LOG", 0x20, 0, 0),
+    ]);
+    img
+}
+
+/// Helper: chemins trouves, tries
+fn glob_paths(fs: &Fat32Fs, pattern: &str) -> Vec<String> {
+    let mut found: Vec<String> = fs.glob(pattern).unwrap().iter().map(|(path, _)| path.to_string()).collect();
+    found.sort();
+    found
+}
+
+#[test]
+fn test_matches_wildcards() {
+    assert!(glob::matches("*.jpg", "IMG_0001.JPG"));
+    assert!(glob::matches("IMG_000?.JPG", "img_0002.jpg"));
+    assert!(!glob::matches("IMG_000?.JPG", "IMG_00010.JPG"));
+    assert!(glob::matches("*", ""));
+    assert!(glob::matches("a*b*c", "aXXbYYbc"));
+    assert!(!glob::matches("a*b*c", "aXXbYYb"));
+    assert!(glob::matches("**.txt", "notes.txt"));
+}
+
+#[test]
+fn test_matches_classes() {
+    assert!(glob::matches("[abc].txt", "B.TXT"));
+    assert!(glob::matches("file[0-9]", "file7"));
+    assert!(!glob::matches("file[0-9]", "fileA"));
+    assert!(glob::matches("file[!0-9]", "fileA"));
+    assert!(glob::matches("file[^0-9]", "fileA"));
+    assert!(glob::matches("[]]", "]"));
+    // crochet non ferme : caractere ordinaire
+    assert!(glob::matches("a[b", "A[B"));
+    assert!(!glob::matches("a[b", "ab"));
+}
+
+#[test]
+fn test_path_patterns() {
+    let pattern = Path::new("/DCIM/**/*.jpg").unwrap();
+    assert!(pattern.is_pattern());
+    assert!(!Path::new("/DCIM/OLD").unwrap().is_pattern());
+    assert!(Path::new("/dcim/IMG_0001.JPG").unwrap().matches(&pattern));
+    assert!(Path::new("/DCIM/OLD/IMG_0003.JPG").unwrap().matches(&pattern));
+    assert!(!Path::new("/LOGS/APP.LOG").unwrap().matches(&pattern));
+}
+
+#[test]
+fn test_glob_single_directory() {
+    let img = create_tree_filesystem();
+    let fs = unsafe { Fat32Fs::new(&img).unwrap() };
+    assert_eq!(glob_paths(&fs, "/DCIM/*.JPG"), vec!["/DCIM/IMG_0001.JPG", "/DCIM/IMG_0002.JPG"]);
+    assert_eq!(glob_paths(&fs, "/dcim/img_000[2-9].jpg"), vec!["/DCIM/IMG_0002.JPG"]);
+    assert!(glob_paths(&fs, "/DCIM/*.GIF").is_empty());
+}
+
+#[test]
+fn test_glob_wildcard_directories() {
+    let img = create_tree_filesystem();
+    let fs = unsafe { Fat32Fs::new(&img).unwrap() };
+    // un composant intermediaire ne garde que les dossiers
+    assert_eq!(glob_paths(&fs, "/*/*.LOG"), vec!["/LOGS/APP.LOG"]);
+    assert_eq!(glob_paths(&fs, "/*/OLD"), vec!["/DCIM/OLD"]);
+}
+
+#[test]
+fn test_glob_recursive_with_cycle() {
+    let img = create_tree_filesystem();
+    let fs = unsafe { Fat32Fs::new(&img).unwrap() };
+    // BACK ramene sur DCIM : deja visite, pas reparcouru
+    assert_eq!(
+        glob_paths(&fs, "/**/*.jpg"),
+        vec!["/DCIM/IMG_0001.JPG", "/DCIM/IMG_0002.JPG", "/DCIM/OLD/IMG_0003.JPG"]
+    );
+    assert_eq!(glob_paths(&fs, "/**/*.log"), vec!["/LOGS/APP.LOG"]);
+    // `**` repete : chaque chemin une seule fois
+    assert_eq!(
+        glob_paths(&fs, "/**/**/*.jpg"),
+        vec!["/DCIM/IMG_0001.JPG", "/DCIM/IMG_0002.JPG", "/DCIM/OLD/IMG_0003.JPG"]
+    );
+    assert_eq!(glob_paths(&fs, "/DCIM/**/**/OLD"), vec!["/DCIM/OLD"]);
+}
+
+#[test]
+fn test_find_in_dir_matches_listing() {
+    let img = create_tree_filesystem();
+    let fs = unsafe { Fat32Fs::new(&img).unwrap() };
+    let names = |entries: Vec<mini_fat32::DirEntry>| -> Vec<String> { entries.iter().map(|e| e.name().unwrap()).collect() };
+
+    // Fat32Fs cherche dans les entrees brutes : meme resultat que le filtre de `list`
+    for component in ["IMG_*.JPG", "notes.txt", "old", "[!I]*", "MISSING"] {
+        assert_eq!(
+            names(fs.find_in_dir("/DCIM", component).unwrap()),
+            names(glob::find_in_list(&fs, "/DCIM", component).unwrap()),
+            "{}", component
+        );
+    }
+    assert_eq!(names(fs.find_in_dir("/DCIM", "img_000?.jpg").unwrap()), vec!["IMG_0001.JPG", "IMG_0002.JPG"]);
+    assert!(fs.find_in_dir("/MISSING", "*").is_err());
+}
+
+#[test]
+fn test_glob_relative_to_current_directory() {
+    let img = create_tree_filesystem();
+    let mut fs = unsafe { Fat32Fs::new(&img).unwrap() };
+    fs.cd("/DCIM").unwrap();
+    assert_eq!(glob_paths(&fs, "*.txt"), vec!["/DCIM/NOTES.TXT"]);
+    assert_eq!(glob_paths(&fs, "../*.TXT"), vec!["/README.TXT"]);
+}
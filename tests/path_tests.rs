@@ -0,0 +1,93 @@
+// Tests de l'API Path (extension, prefixes, iteration, normalisation, validation)
+
+use mini_fat32::{Path, PathBuf};
+use mini_fat32::fs::PathError;
+
+fn path(text: &str) -> Path {
+    Path::new(text).unwrap()
+}
+
+#[test]
+fn test_extension_and_stem() {
+    let archive = path("/backup/notes.tar.gz");
+    assert_eq!(archive.extension(), Some("gz"));
+    assert_eq!(archive.file_stem(), Some("notes.tar"));
+
+    let hidden = path(".profile");
+    assert_eq!(hidden.extension(), None);
+    assert_eq!(hidden.file_stem(), Some(".profile"));
+
+    assert_eq!(path("/README").extension(), None);
+    assert_eq!(Path::root().file_stem(), None);
+}
+
+#[test]
+fn test_with_extension() {
+    assert_eq!(path("/DOCS/report.txt").with_extension("md").to_string(), "/DOCS/report.md");
+    assert_eq!(path("/DOCS/report.txt").with_extension("").to_string(), "/DOCS/report");
+    assert_eq!(path("/DOCS/report").with_extension("bak").to_string(), "/DOCS/report.bak");
+    assert!(Path::root().with_extension("txt").is_root());
+}
+
+#[test]
+fn test_starts_with_and_strip_prefix() {
+    let file = path("/DCIM/100CANON/IMG_0001.JPG");
+    assert!(file.starts_with(&path("/dcim")));
+    assert!(file.starts_with(&Path::root()));
+    assert!(!file.starts_with(&path("/DCI")));
+    assert!(!file.starts_with(&path("DCIM")));
+
+    let rest = file.strip_prefix(&path("/DCIM")).unwrap();
+    assert!(!rest.is_absolute());
+    assert_eq!(rest.to_string(), "100CANON/IMG_0001.JPG");
+    assert!(file.strip_prefix(&path("/LOGS")).is_err());
+}
+
+#[test]
+fn test_iter_and_ancestors() {
+    let file = path("/a/b/c.txt");
+    assert_eq!(file.iter().collect::<Vec<_>>(), vec!["a", "b", "c.txt"]);
+    assert_eq!(file.iter().next_back(), Some("c.txt"));
+    assert_eq!((&file).into_iter().len(), 3);
+
+    let ancestors: Vec<String> = file.ancestors().map(|p| p.to_string()).collect();
+    assert_eq!(ancestors, vec!["/a/b/c.txt", "/a/b", "/a", "/"]);
+}
+
+#[test]
+fn test_normalize() {
+    assert_eq!(path("/a/b/../c").normalize().to_string(), "/a/c");
+    // jamais au-dessus de la racine
+    assert_eq!(path("/../../a").normalize().to_string(), "/a");
+    // un chemin relatif garde les .. de tete
+    assert_eq!(path("../x/../y").normalize().to_string(), "../y");
+    assert_eq!(path("a/../..").normalize().to_string(), "..");
+}
+
+#[test]
+fn test_validate_illegal_characters() {
+    assert!(path("/My Documents/report (1).txt").validate().is_ok());
+    assert!(path("../up").validate().is_ok());
+    assert_eq!(path("/DCIM/*.JPG").validate(), Err(PathError::IllegalCharacter('*')));
+    assert_eq!(path("/a/b<c").validate(), Err(PathError::IllegalCharacter('<')));
+    assert_eq!(path("/bell\x07").validate(), Err(PathError::IllegalCharacter('\x07')));
+    assert_eq!(mini_fat32::fs::path::validate_component("a\\b"), Err(PathError::IllegalCharacter('\\')));
+
+    let mut buf = PathBuf::root();
+    buf.push("ok.txt").unwrap();
+    assert!(buf.as_path().validate().is_ok());
+}
+
+#[test]
+fn test_constructors_resolve_dotdot() {
+    assert_eq!(path("/a/../b").to_string(), "/b");
+    assert_eq!(path("/a/b/../../..").to_string(), "/");
+    assert_eq!(path("x/../../y").to_string(), "../y");
+    assert_eq!(path("/a").join(&path("../../b")).unwrap().to_string(), "/b");
+    assert_eq!(Path::new_verbatim("/a/../b").unwrap().components(), ["a", "..", "b"]);
+
+    let mut buf = PathBuf::new("/a/b").unwrap();
+    buf.push("..").unwrap();
+    assert_eq!(buf.to_string(), "/a");
+    assert_eq!(PathBuf::new("/../c").unwrap().to_string(), "/c");
+}
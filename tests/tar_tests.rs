@@ -0,0 +1,116 @@
+// Tests de l'export tar : en-tetes ustar, noms longs (pax), dates et dossiers
+#![cfg(feature = "tar")]
+
+use mini_fat32::fs::tar::{self, TarWriter, BLOCK_SIZE};
+use mini_fat32::fs::FixedTimeProvider;
+use mini_fat32::{DateTime, Fat32Fs, FileSystem};
+
+mod common;
+
+use common::create_test_filesystem;
+
+/// Entree lue dans l'archive
+#[derive(Debug)]
+struct Entry {
+    path: String,
+    typeflag: u8,
+    mode: u64,
+    mtime: u64,
+    data: Vec<u8>,
+}
+
+fn octal(field: &[u8]) -> u64 {
+    field.iter().take_while(|&&b| b != 0 && b != b' ').fold(0, |n, &b| n * 8 + (b - b'0') as u64)
+}
+
+fn text(field: &[u8]) -> String {
+    String::from_utf8(field.iter().take_while(|&&b| b != 0).copied().collect()).unwrap()
+}
+
+/// Helper: lecteur tar minimal (ustar + enregistrement pax `path`), verifie les checksums
+fn read_archive(archive: &[u8]) -> Vec<Entry> {
+    assert_eq!(archive.len() % BLOCK_SIZE, 0);
+    let mut entries = Vec::new();
+    let mut pax_path = None;
+    let mut offset = 0;
+    while archive[offset..offset + BLOCK_SIZE].iter().any(|&b| b != 0) {
+        let header = &archive[offset..offset + BLOCK_SIZE];
+        let mut blank = header.to_vec();
+        blank[148..156].fill(b' ');
+        assert_eq!(octal(&header[148..156]), blank.iter().map(|&b| b as u64).sum::<u64>());
+        assert_eq!(&header[257..265], b"ustar\x0000");
+
+        let size = octal(&header[124..136]) as usize;
+        let data = archive[offset + BLOCK_SIZE..offset + BLOCK_SIZE + size].to_vec();
+        offset += BLOCK_SIZE + size.div_ceil(BLOCK_SIZE) * BLOCK_SIZE;
+
+        if header[156] == b'x' {
+            let record = String::from_utf8(data).unwrap();
+            let (length, rest) = record.split_once(' ').unwrap();
+            assert_eq!(length.parse::<usize>().unwrap(), record.len());
+            pax_path = Some(rest.strip_prefix("path=").unwrap().trim_end_matches('\n').to_string());
+            continue;
+        }
+        let prefix = text(&header[345..500]);
+        let name = text(&header[..100]);
+        let path = pax_path.take().unwrap_or(if prefix.is_empty() { name } else { format!("{}/{}", prefix, name) });
+        entries.push(Entry { path, typeflag: header[156], mode: octal(&header[100..108]), mtime: octal(&header[136..148]), data });
+    }
+    // deux blocs vides pour finir
+    assert_eq!(archive.len(), offset + 2 * BLOCK_SIZE);
+    entries
+}
+
+#[test]
+fn test_writer_headers_and_long_names() {
+    let mut writer = TarWriter::new();
+    writer.append_dir("DCIM", 0o755, 1_600_000_000).unwrap();
+    writer.append_file("DCIM/IMG_0001.JPG", &[0xFF; 700], 0o644, 1_600_000_002).unwrap();
+
+    // > 100 octets : prefix ustar ; nom final > 100 ou non ASCII : en-tete pax
+    let split = format!("{}/{}", "d".repeat(120), "file.txt");
+    let long_name = format!("logs/{}.txt", "x".repeat(150));
+    writer.append_file(&split, b"a", 0o644, 0).unwrap();
+    writer.append_file(&long_name, b"b", 0o444, 0).unwrap();
+    writer.append_file("Ünïcode.txt", b"c", 0o644, 0).unwrap();
+    assert_eq!(writer.entries(), 5);
+    assert!(writer.append_file("/absolute", b"", 0o644, 0).is_err());
+
+    let entries = read_archive(&writer.finish());
+    let paths: Vec<&str> = entries.iter().map(|e| e.path.as_str()).collect();
+    assert_eq!(paths, ["DCIM/", "DCIM/IMG_0001.JPG", split.as_str(), long_name.as_str(), "Ünïcode.txt"]);
+    assert_eq!(entries[0].typeflag, b'5');
+    assert_eq!((entries[1].typeflag, entries[1].mode, entries[1].mtime), (b'0', 0o644, 1_600_000_002));
+    assert_eq!(entries[1].data, [0xFF; 700]);
+    assert_eq!(entries[3].mode, 0o444);
+}
+
+#[test]
+fn test_export_tree_with_dates() {
+    let img = create_test_filesystem();
+    let modified = DateTime { year: 2024, month: 5, day: 17, hour: 10, minute: 30, second: 20, millisecond: 0 };
+    let mut fs = unsafe { Fat32Fs::new(&img).unwrap() }.with_time_provider(Box::new(FixedTimeProvider::new(modified)));
+    fs.create_dir("/Logs").unwrap();
+    fs.create_dir("/Logs/2024").unwrap();
+    fs.write_file("/Logs/2024/boot sequence.log", b"ok\n").unwrap();
+    fs.write_file("/README.TXT", b"hi").unwrap();
+    fs.set_label("CARD").unwrap();
+
+    // racine : le contenu directement, sans `.`, `..` ni label
+    let entries = read_archive(&tar::export(&fs, "/").unwrap());
+    let paths: Vec<&str> = entries.iter().map(|e| e.path.as_str()).collect();
+    assert_eq!(paths, ["Logs/", "Logs/2024/", "Logs/2024/boot sequence.log", "README.TXT"]);
+    assert!(entries.iter().all(|e| e.mtime == modified.to_unix_timestamp()));
+    assert_eq!(entries[2].data, b"ok\n");
+    assert_eq!(entries[0].mode, 0o755);
+
+    // sous-dossier : les chemins commencent par son nom
+    fs.cd("/Logs").unwrap();
+    let entries = read_archive(&tar::export(&fs, "2024").unwrap());
+    let paths: Vec<&str> = entries.iter().map(|e| e.path.as_str()).collect();
+    assert_eq!(paths, ["2024/", "2024/boot sequence.log"]);
+
+    let entries = read_archive(&tar::export(&fs, "/README.TXT").unwrap());
+    assert_eq!((entries[0].path.as_str(), entries[0].data.as_slice()), ("README.TXT", &b"hi"[..]));
+    assert!(tar::export(&fs, "/missing").is_err());
+}
@@ -0,0 +1,135 @@
+// Tests des dates et heures FAT
+
+use mini_fat32::{DateTime, Fat32Fs, FileSystem};
+use mini_fat32::fs::FixedTimeProvider;
+use mini_fat32::fs::entry::{DirEntry, DirectoryEntry};
+
+mod common;
+
+use common::create_test_filesystem;
+
+/// Helper: 2024-05-17
+fn fat_date() -> u16 {
+    ((2024 - 1980) << 9) | (5 << 5) | 17
+}
+
+/// Helper: 10:30:20
+fn fat_time() -> u16 {
+    (10 << 11) | (30 << 5) | 10
+}
+
+#[test]
+fn test_decode_fat_datetime() {
+    let dt = DateTime::from_fat(fat_date(), fat_time(), 155).unwrap();
+    assert_eq!((dt.year, dt.month, dt.day), (2024, 5, 17));
+    assert_eq!((dt.hour, dt.minute, dt.second, dt.millisecond), (10, 30, 21, 550));
+    assert_eq!(dt.to_string(), "2024-05-17 10:30:21");
+
+    // aller-retour vers les champs FAT
+    assert_eq!(dt.fat_date(), fat_date());
+    assert_eq!(dt.fat_time(), fat_time());
+    assert_eq!(dt.fat_hundredths(), 155);
+}
+
+#[test]
+fn test_decode_invalid_fat_datetime() {
+    assert!(DateTime::from_fat(0, fat_time(), 0).is_none());
+    assert!(DateTime::from_fat(fat_date() & !0x1E0, fat_time(), 0).is_none()); // mois 0
+    assert!(DateTime::from_fat(fat_date(), 24 << 11, 0).is_none());
+    assert!(DateTime::from_fat(fat_date(), fat_time(), 200).is_none());
+}
+
+#[test]
+fn test_out_of_range_dates_clamped() {
+    // 1970-06-15 12:00 -> 1980-01-01 00:00:00
+    let before = DateTime { year: 1970, month: 6, day: 15, hour: 12, ..DateTime::FAT_EPOCH };
+    let dt = DateTime::from_fat(before.fat_date(), before.fat_time(), before.fat_hundredths()).unwrap();
+    assert_eq!(dt, DateTime::FAT_EPOCH);
+
+    // 2200-06-15 -> 2107-12-31 23:59:59.99
+    let after = DateTime { year: 2200, month: 6, day: 15, ..DateTime::FAT_EPOCH };
+    let dt = DateTime::from_fat(after.fat_date(), after.fat_time(), after.fat_hundredths()).unwrap();
+    assert_eq!(dt, DateTime::FAT_MAX);
+    assert_eq!(dt.to_string(), "2107-12-31 23:59:59");
+}
+
+#[test]
+fn test_dir_entry_timestamps() {
+    let mut data = [0u8; 32];
+    data[0..11].copy_from_slice(b"PHOTO   JPG");
+    data[11] = 0x20;
+    data[13] = 42; // creation 10 ms
+    data[14..16].copy_from_slice(&fat_time().to_le_bytes());
+    data[16..18].copy_from_slice(&fat_date().to_le_bytes());
+    data[18..20].copy_from_slice(&(fat_date() + 1).to_le_bytes());
+    data[22..24].copy_from_slice(&((11 << 11) as u16).to_le_bytes());
+    data[24..26].copy_from_slice(&fat_date().to_le_bytes());
+
+    let entry = DirEntry::new(unsafe { DirectoryEntry::from_bytes(&data).unwrap() });
+
+    let created = entry.created().unwrap();
+    assert_eq!((created.second, created.millisecond), (20, 420));
+
+    let modified = entry.modified().unwrap();
+    assert_eq!(modified.to_string(), "2024-05-17 11:00:00");
+
+    let accessed = entry.accessed().unwrap();
+    assert_eq!((accessed.day, accessed.hour), (18, 0));
+    assert!(created < modified);
+}
+
+#[test]
+fn test_unix_timestamp_conversion() {
+    assert_eq!(DateTime::from_unix_timestamp(0).to_string(), "1970-01-01 00:00:00");
+    assert_eq!(DateTime::from_unix_timestamp(951_782_400).to_string(), "2000-02-29 00:00:00");
+    assert_eq!(DateTime::from_unix_timestamp(1_715_941_821).to_string(), "2024-05-17 10:30:21");
+
+    for seconds in [0, 951_782_400, 1_715_941_821, 4_102_444_799] {
+        assert_eq!(DateTime::from_unix_timestamp(seconds).to_unix_timestamp(), seconds);
+    }
+    let time = DateTime { year: 2024, month: 5, day: 17, hour: 10, minute: 30, second: 21, millisecond: 550 };
+    assert_eq!(DateTime::from_system_time(time.to_system_time()), time);
+}
+
+#[test]
+fn test_time_provider_stamps_writes() {
+    let created_at = DateTime { year: 2024, month: 5, day: 17, hour: 10, minute: 30, second: 21, millisecond: 550 };
+    let mut fs = unsafe { Fat32Fs::new(&create_test_filesystem()).unwrap() }
+        .with_time_provider(Box::new(FixedTimeProvider::new(created_at)));
+    fs.create_file("/LOG.TXT").unwrap();
+
+    let entry = &fs.list("/").unwrap()[0];
+    assert_eq!(entry.created(), Some(created_at));
+    assert_eq!(entry.modified().unwrap().to_string(), "2024-05-17 10:30:20");
+    assert_eq!(entry.accessed().unwrap().day, 17);
+
+    let modified_at = DateTime { day: 20, ..created_at };
+    let mut fs = unsafe { Fat32Fs::new(&fs.into_device_data()).unwrap() }
+        .with_time_provider(Box::new(FixedTimeProvider::new(modified_at)));
+    fs.write_file("/LOG.TXT", b"entry").unwrap();
+
+    let entry = &fs.list("/").unwrap()[0];
+    assert_eq!(entry.created(), Some(created_at));
+    assert_eq!(entry.modified().unwrap().day, 20);
+}
+
+#[test]
+fn test_access_date_updated_on_read_when_enabled() {
+    let created_at = DateTime { year: 2024, month: 5, day: 17, ..DateTime::FAT_EPOCH };
+    let mut fs = unsafe { Fat32Fs::new(&create_test_filesystem()).unwrap() }
+        .with_time_provider(Box::new(FixedTimeProvider::new(created_at)));
+    fs.write_file("/DATA.TXT", b"x").unwrap();
+    let img = fs.into_device_data();
+
+    let later = DateTime { month: 6, day: 1, ..created_at };
+    let fs = unsafe { Fat32Fs::new(&img).unwrap() }
+        .with_time_provider(Box::new(FixedTimeProvider::new(later)));
+    fs.read_file("/DATA.TXT").unwrap();
+    assert_eq!(fs.list("/").unwrap()[0].accessed().unwrap().month, 5);
+
+    let fs = fs.with_access_date_updates(true);
+    fs.read_file("/DATA.TXT").unwrap();
+    let entry = &fs.list("/").unwrap()[0];
+    assert_eq!(entry.accessed().unwrap().month, 6);
+    assert_eq!(entry.modified().unwrap().month, 5);
+}
@@ -0,0 +1,119 @@
+// Tests du parcours recursif (walk)
+
+use mini_fat32::{Attributes, Fat32Fs, FileSystem, FileSystemError};
+use mini_fat32::fs::WalkOrder;
+
+mod common;
+
+/// Helper: image FAT32 avec une arborescence
+/// /DOCS/NOTES.TXT, /DOCS/DEEP/FILE.BIN, /README.TXT, /HIDDEN.SYS
+/// /DOCS/DEEP/LOOP pointe sur DOCS (cycle)
+fn create_tree_filesystem() -> Vec<u8> {
+    let mut img = common::create_test_filesystem();
+    common::write_dir(&mut img, 2, &[
+        common::dir_entry(b"DOCS       ", 0x10, 3, 0),
+        common::dir_entry(b"README  TXT", 0x20, 0, 0),
+        common::dir_entry(b"HIDDEN  SYS", 0x02 | 0x04, 0, 0),
+    ]);
+    common::write_dir(&mut img, 3, &[
+        common::dir_entry(b".          ", 0x10, 3, 0),
+        common::dir_entry(b"..         ", 0x10, 0, 0),
+        common::dir_entry(b"NOTES   TXT", 0x20, 5, 4),
+        common::dir_entry(b"DEEP       ", 0x10, 4, 0),
+    ]);
+    common::write_dir(&mut img, 4, &[
+        common::dir_entry(b".          ", 0x10, 4, 0),
+        common::dir_entry(b"..         ", 0x10, 3, 0),
+        common::dir_entry(b"LOOP       ", 0x10, 3, 0),
+        common::dir_entry(b"FILE    BIN", 0x01, 0, 0),
+    ]);
+    // contenu de NOTES.TXT
+    common::set_fat_entry(&mut img, 5, 0x0FFF_FFFF);
+    img
+}
+
+/// Helper: chemins renvoyes, `!` pour une erreur
+fn paths(walk: mini_fat32::fs::Walk<'_>) -> Vec<String> {
+    walk.map(|r| match r {
+        Ok(entry) => entry.path.to_string(),
+        Err(_) => String::from("!"),
+    })
+    .collect()
+}
+
+#[test]
+fn test_walk_depth_first_detects_cycle() {
+    let img = create_tree_filesystem();
+    let fs = unsafe { Fat32Fs::new(&img).unwrap() };
+    assert_eq!(
+        paths(fs.walk("/").unwrap()),
+        vec!["/DOCS", "/DOCS/NOTES.TXT", "/DOCS/DEEP", "!", "/DOCS/DEEP/FILE.BIN", "/README.TXT", "/HIDDEN.SYS"]
+    );
+
+    let cycle = fs.walk("/").unwrap().find_map(|r| r.err()).unwrap();
+    assert!(matches!(cycle, FileSystemError::DirectoryEntryError(ref msg) if msg.contains("/DOCS/DEEP/LOOP")));
+}
+
+#[test]
+fn test_walk_breadth_first_and_depth() {
+    let img = create_tree_filesystem();
+    let fs = unsafe { Fat32Fs::new(&img).unwrap() };
+    assert_eq!(
+        paths(fs.walk("/").unwrap().order(WalkOrder::BreadthFirst)),
+        vec!["/DOCS", "/README.TXT", "/HIDDEN.SYS", "/DOCS/NOTES.TXT", "/DOCS/DEEP", "!", "/DOCS/DEEP/FILE.BIN"]
+    );
+    assert_eq!(paths(fs.walk("/").unwrap().max_depth(1)), vec!["/DOCS", "/README.TXT", "/HIDDEN.SYS"]);
+    assert_eq!(paths(fs.walk("/docs").unwrap().breadth_first().max_depth(1)), vec!["/docs/NOTES.TXT", "/docs/DEEP"]);
+    assert!(paths(fs.walk("/").unwrap().max_depth(0)).is_empty());
+
+    let depths: Vec<usize> = fs.walk("/").unwrap().filter_map(|r| r.ok()).map(|e| e.depth).collect();
+    assert_eq!(depths, vec![1, 2, 2, 3, 1, 1]);
+}
+
+#[test]
+fn test_walk_filters() {
+    let img = create_tree_filesystem();
+    let fs = unsafe { Fat32Fs::new(&img).unwrap() };
+
+    // les filtres n'empechent pas de descendre dans les dossiers
+    let txt = fs.walk("/").unwrap().filter_name(|name| name.ends_with(".TXT"));
+    assert_eq!(paths(txt), vec!["/DOCS/NOTES.TXT", "!", "/README.TXT"]);
+
+    let read_only = fs.walk("/").unwrap().with_attributes(Attributes::READ_ONLY);
+    assert_eq!(paths(read_only), vec!["!", "/DOCS/DEEP/FILE.BIN"]);
+
+    let visible = fs.walk("/").unwrap().max_depth(1).without_attributes(Attributes::HIDDEN | Attributes::SYSTEM);
+    assert_eq!(paths(visible), vec!["/DOCS", "/README.TXT"]);
+
+    assert!(fs.walk("/README.TXT").is_err());
+}
+
+#[test]
+fn test_dotdot_is_resolved_lexically() {
+    let img = create_tree_filesystem();
+    let mut fs = unsafe { Fat32Fs::new(&img).unwrap() };
+
+    assert!(fs.read_file("/DOCS/../README.TXT").is_ok());
+    // jamais au-dessus de la racine
+    assert!(fs.read_file("/../../README.TXT").is_ok());
+    fs.cd("/DOCS/DEEP/LOOP/..").unwrap();
+    assert_eq!(fs.pwd(), "/DOCS/DEEP");
+    fs.cd("../../..").unwrap();
+    assert_eq!(fs.pwd(), "/");
+}
+
+#[test]
+fn test_dotdot_is_resolved_physically() {
+    let img = create_tree_filesystem();
+    let mut fs = unsafe { Fat32Fs::new(&img).unwrap() }.with_physical_parent_resolution(true);
+
+    fs.cd("/DOCS/DEEP/..").unwrap();
+    assert_eq!(fs.pwd(), "/DOCS");
+    // LOOP est le cluster de DOCS : son `..` sur disque est la racine
+    fs.cd("/DOCS/DEEP/LOOP/..").unwrap();
+    assert_eq!(fs.pwd(), "/");
+    fs.cd("/DOCS/DEEP").unwrap();
+    assert!(fs.read_file("../NOTES.TXT").is_ok());
+    fs.cd("../../..").unwrap();
+    assert_eq!(fs.pwd(), "/");
+}
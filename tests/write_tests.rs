@@ -0,0 +1,468 @@
+// Tests d'ecriture : creation d'entrees, noms longs, allocation de clusters
+
+use mini_fat32::{Attributes, DateTime, Fat32Fs, FatType, FileKind, FileSystem, FileSystemError};
+use mini_fat32::fs::FixedTimeProvider;
+use mini_fat32::fs::entry::DirectoryEntry;
+
+mod common;
+
+use common::create_test_filesystem;
+
+/// Helper: 2025-03-04 05:06:07
+fn fixed_time() -> DateTime {
+    DateTime { year: 2025, month: 3, day: 4, hour: 5, minute: 6, second: 7, millisecond: 890 }
+}
+
+fn mount(img: &[u8]) -> Fat32Fs {
+    unsafe { Fat32Fs::new(img).unwrap() }.with_time_provider(Box::new(FixedTimeProvider::new(fixed_time())))
+}
+
+#[test]
+fn test_create_and_write_short_name() {
+    let img = create_test_filesystem();
+    let mut fs = mount(&img);
+
+    fs.create_file("/EMPTY.TXT").unwrap();
+    fs.write_file("/DATA.BIN", &[7u8; 1500]).unwrap();
+
+    let entries = fs.list("/").unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].name().unwrap(), "EMPTY.TXT");
+    assert!(entries[0].long_name.is_none());
+    assert_eq!(entries[1].file_size(), 1500);
+    assert_eq!(fs.get_cluster_chain(entries[1].first_cluster()).unwrap().len(), 3);
+
+    assert!(fs.read_file("/EMPTY.TXT").unwrap().is_empty());
+    assert_eq!(fs.read_file("/data.bin").unwrap(), vec![7u8; 1500]);
+
+    // deja existant
+    assert!(matches!(fs.create_file("/empty.txt"), Err(FileSystemError::DirectoryEntryError(_))));
+}
+
+#[test]
+fn test_create_long_names_with_aliases() {
+    let img = create_test_filesystem();
+    let mut fs = mount(&img);
+
+    fs.write_file("/hello.txt", b"lower case").unwrap();
+    fs.write_file("/A very long file name, with spaces.log", b"first").unwrap();
+    fs.write_file("/A very long file name, other.log", b"second").unwrap();
+
+    let entries = fs.list("/").unwrap();
+    let names: Vec<String> = entries.iter().map(|e| e.name().unwrap()).collect();
+    assert_eq!(names, vec!["hello.txt", "A very long file name, with spaces.log", "A very long file name, other.log"]);
+    assert_eq!(entries[0].entry.short_name().unwrap(), "HELLO.TXT");
+    assert_eq!(entries[1].entry.short_name().unwrap(), "AVERYL~1.LOG");
+    assert_eq!(entries[2].entry.short_name().unwrap(), "AVERYL~2.LOG");
+
+    // lookup par nom long ou par alias
+    assert_eq!(fs.read_file("/a very long file name, OTHER.log").unwrap(), b"second");
+    assert_eq!(fs.read_file("/AVERYL~1.LOG").unwrap(), b"first");
+}
+
+#[test]
+fn test_numeric_tail_range() {
+    let basis = DirectoryEntry::short_name_basis("A very long file name.log");
+    assert_eq!(&DirectoryEntry::with_numeric_tail(&basis, 1).unwrap(), b"AVERYL~1LOG");
+    assert_eq!(&DirectoryEntry::with_numeric_tail(&basis, 4321).unwrap(), b"AVE~4321LOG");
+    assert_eq!(&DirectoryEntry::with_numeric_tail(&basis, 9_999_999).unwrap(), b"~9999999LOG");
+    // ~N doit tenir dans les 8 caracteres du nom
+    assert!(DirectoryEntry::with_numeric_tail(&basis, 10_000_000).is_none());
+    assert!(DirectoryEntry::with_numeric_tail(&basis, u32::MAX).is_none());
+}
+
+#[test]
+fn test_overwrite_frees_old_chain() {
+    let img = create_test_filesystem();
+    let mut fs = mount(&img);
+
+    fs.write_file("/FILE.BIN", &[1u8; 2048]).unwrap();
+    let first = fs.list("/").unwrap()[0].first_cluster();
+    fs.write_file("/FILE.BIN", b"short").unwrap();
+
+    let entry = &fs.list("/").unwrap()[0];
+    assert_eq!(entry.file_size(), 5);
+    assert_eq!(fs.get_cluster_chain(entry.first_cluster()).unwrap().len(), 1);
+    assert_eq!(fs.read_file("/FILE.BIN").unwrap(), b"short");
+    // les clusters liberes sont reutilises
+    fs.write_file("/OTHER.BIN", &[2u8; 1024]).unwrap();
+    assert!(fs.list("/").unwrap()[1].first_cluster() <= first + 4);
+}
+
+#[test]
+fn test_failed_overwrite_keeps_old_contents() {
+    let img = create_test_filesystem();
+    let mut fs = mount(&img);
+
+    fs.write_file("/FILE.BIN", &[1u8; 2048]).unwrap();
+    // on remplit le volume, il reste 2 clusters libres
+    let free = fs.volume_info().free_clusters as usize;
+    fs.write_file("/FILL.BIN", &vec![3u8; (free - 2) * 512]).unwrap();
+    assert_eq!(fs.volume_info().free_clusters, 2);
+
+    // 10 clusters : ni les 2 libres ni les 4 de l'ancienne chaine ne suffisent
+    assert!(matches!(fs.write_file("/FILE.BIN", &[9u8; 10 * 512]), Err(FileSystemError::IoError(_))));
+    assert_eq!(fs.metadata("/FILE.BIN").unwrap().size, 2048);
+    assert_eq!(fs.read_file("/FILE.BIN").unwrap(), vec![1u8; 2048]);
+    assert_eq!(fs.volume_info().free_clusters, 2);
+
+    // ce qui tient encore passe, et l'ancienne chaine est liberee
+    fs.write_file("/FILE.BIN", &[9u8; 1024]).unwrap();
+    assert_eq!(fs.read_file("/FILE.BIN").unwrap(), vec![9u8; 1024]);
+    assert_eq!(fs.volume_info().free_clusters, 4);
+}
+
+#[test]
+fn test_failed_write_leaves_no_new_entry() {
+    let img = create_test_filesystem();
+    let mut fs = mount(&img);
+
+    let free = fs.volume_info().free_clusters as usize;
+    fs.write_file("/FILL.BIN", &vec![3u8; (free - 2) * 512]).unwrap();
+
+    // 3 clusters pour 2 libres : ni l'entree ni les clusters ne restent
+    assert!(matches!(fs.write_file("/New file.txt", &[9u8; 3 * 512]), Err(FileSystemError::IoError(_))));
+    assert!(!fs.exists("/New file.txt"));
+    assert_eq!(fs.list("/").unwrap().len(), 1);
+    assert_eq!(fs.volume_info().free_clusters, 2);
+}
+
+#[test]
+fn test_directory_grows_past_one_cluster() {
+    let img = create_test_filesystem();
+    let mut fs = mount(&img);
+
+    // 16 entrees par cluster de 512 octets
+    for i in 0..40 {
+        fs.create_file(&format!("/FILE{:02}.TXT", i)).unwrap();
+    }
+    let entries = fs.list("/").unwrap();
+    assert_eq!(entries.len(), 40);
+    assert_eq!(entries[39].name().unwrap(), "FILE39.TXT");
+    assert_eq!(fs.get_cluster_chain(2).unwrap().len(), 3);
+}
+
+#[test]
+fn test_written_image_remounts() {
+    let img = create_test_filesystem();
+    let mut fs = mount(&img);
+    fs.write_file("/Notes about the trip.txt", b"remember the towel").unwrap();
+
+    let written = fs.into_device_data();
+    let fs = unsafe { Fat32Fs::new(&written).unwrap() };
+    assert_eq!(fs.read_file("/notes about the trip.txt").unwrap(), b"remember the towel");
+
+    // les deux copies de la FAT sont identiques
+    let [first, second] = common::fat_offsets(&written);
+    let fat_size = common::fat_size(&written);
+    assert_eq!(written[first..first + fat_size], written[second..second + fat_size]);
+}
+
+#[test]
+fn test_set_attributes_and_hide_entries() {
+    let img = create_test_filesystem();
+    let mut fs = mount(&img);
+    fs.create_file("/VISIBLE.TXT").unwrap();
+    fs.create_file("/SECRET.TXT").unwrap();
+    fs.create_file("/BOOT.SYS").unwrap();
+
+    fs.set_attributes("/SECRET.TXT", Attributes::HIDDEN).unwrap();
+    fs.set_attributes("/BOOT.SYS", Attributes::SYSTEM | Attributes::READ_ONLY).unwrap();
+
+    let entries = fs.list("/").unwrap();
+    assert_eq!(entries.len(), 3);
+    assert!(entries[0].is_archive());
+    assert!(entries[1].is_hidden() && !entries[1].is_archive());
+    assert!(entries[2].is_system() && entries[2].is_read_only());
+    assert_eq!(entries[2].attributes().to_string(), "R-S---");
+
+    // DIRECTORY et VOLUME_ID ne peuvent pas etre changes
+    fs.set_attributes("/VISIBLE.TXT", Attributes::DIRECTORY | Attributes::ARCHIVE).unwrap();
+    assert!(fs.list("/").unwrap()[0].is_file());
+
+    let fs = fs.with_hidden_entries(false);
+    let names: Vec<String> = fs.list("/").unwrap().iter().map(|e| e.name().unwrap()).collect();
+    assert_eq!(names, vec!["VISIBLE.TXT"]);
+    // toujours accessibles par leur chemin
+    assert!(fs.read_file("/SECRET.TXT").unwrap().is_empty());
+}
+
+#[test]
+fn test_read_only_blocks_write_and_remove() {
+    let img = create_test_filesystem();
+    let mut fs = mount(&img);
+    fs.write_file("/LOCKED.TXT", b"keep me").unwrap();
+    fs.set_attributes("/LOCKED.TXT", Attributes::READ_ONLY).unwrap();
+
+    assert!(matches!(fs.write_file("/LOCKED.TXT", b"nope"), Err(FileSystemError::ReadOnly(_))));
+    assert!(matches!(fs.remove_file("/LOCKED.TXT"), Err(FileSystemError::ReadOnly(_))));
+    assert_eq!(fs.read_file("/LOCKED.TXT").unwrap(), b"keep me");
+
+    fs.set_attributes("/LOCKED.TXT", Attributes::empty()).unwrap();
+    fs.write_file("/LOCKED.TXT", b"changed").unwrap();
+    assert_eq!(fs.read_file("/LOCKED.TXT").unwrap(), b"changed");
+}
+
+#[test]
+fn test_remove_file_frees_entries_and_clusters() {
+    let img = create_test_filesystem();
+    let mut fs = mount(&img);
+    fs.write_file("/A long name to remove.bin", &[3u8; 1024]).unwrap();
+    fs.write_file("/KEEP.TXT", b"kept").unwrap();
+    let first = fs.list("/").unwrap()[0].first_cluster();
+
+    fs.remove_file("/a long name to remove.BIN").unwrap();
+    let names: Vec<String> = fs.list("/").unwrap().iter().map(|e| e.name().unwrap()).collect();
+    assert_eq!(names, vec!["KEEP.TXT"]);
+    assert!(matches!(fs.read_file("/A long name to remove.bin"), Err(FileSystemError::FileNotFound(_))));
+
+    // les entrees et clusters liberes sont reutilises
+    fs.write_file("/NEW.BIN", &[4u8; 512]).unwrap();
+    let entries = fs.list("/").unwrap();
+    assert_eq!(entries[0].name().unwrap(), "NEW.BIN");
+    assert_eq!(entries[0].first_cluster(), first);
+}
+
+#[test]
+fn test_metadata_and_exists() {
+    let img = create_test_filesystem();
+    let mut fs = mount(&img);
+    fs.write_file("/Some notes.txt", &[b'n'; 1500]).unwrap();
+
+    let meta = fs.metadata("/SOME NOTES.TXT").unwrap();
+    assert_eq!(meta.kind, FileKind::File);
+    assert_eq!((meta.size, meta.allocated_size), (1500, 1536));
+    assert_eq!(meta.short_name, "SOMENO~1.TXT");
+    assert_eq!(meta.long_name.as_deref(), Some("Some notes.txt"));
+    assert!(meta.attributes.is_archive());
+    assert_eq!(meta.created, Some(fixed_time()));
+    assert_eq!(meta.modified.unwrap().second, 6);
+    assert_eq!(fs.get_cluster_chain(meta.first_cluster).unwrap().len(), 3);
+    // 14 caracteres : deux entrees LFN puis l'entree courte, racine au cluster 2
+    let location = meta.location.unwrap();
+    assert_eq!((location.dir_cluster, location.index), (2, 2));
+
+    let root = fs.metadata("/").unwrap();
+    assert!(root.is_directory() && root.location.is_none());
+    assert_eq!((root.first_cluster, root.allocated_size), (2, 512));
+
+    assert!(fs.exists("/some notes.txt"));
+    assert!(!fs.exists("/other.txt"));
+    assert!(matches!(fs.metadata("/missing/file"), Err(FileSystemError::DirectoryNotFound(_))));
+}
+
+#[test]
+fn test_read_dir_streams_across_clusters() {
+    let img = create_test_filesystem();
+    let mut fs = mount(&img);
+    // 15 entrees courtes : le nom long suivant est a cheval sur les clusters 1 et 2
+    for i in 0..15 {
+        fs.create_file(&format!("/F{:02}.TXT", i)).unwrap();
+    }
+    fs.write_file("/Crossing the boundary.txt", b"across").unwrap();
+    fs.create_file("/LAST.TXT").unwrap();
+    assert_eq!(fs.get_cluster_chain(2).unwrap().len(), 2);
+
+    let entries: Vec<_> = fs.read_dir("/").unwrap().collect::<Result<_, _>>().unwrap();
+    assert_eq!(entries.len(), 17);
+    assert_eq!(entries[15].name().unwrap(), "Crossing the boundary.txt");
+    assert_eq!(entries[16].name().unwrap(), "LAST.TXT");
+
+    // meme resultat que list, et meme filtre des entrees cachees
+    fs.set_attributes("/F00.TXT", Attributes::HIDDEN).unwrap();
+    let fs = fs.with_hidden_entries(false);
+    let streamed = fs.read_dir("/").unwrap().map(|e| e.unwrap().name().unwrap());
+    let listed = fs.list("/").unwrap().into_iter().map(|e| e.name().unwrap());
+    assert!(streamed.eq(listed));
+    assert_eq!(fs.read_dir("/").unwrap().count(), 16);
+
+    assert!(fs.read_dir("/LAST.TXT").is_err());
+}
+
+#[test]
+fn test_read_dir_reports_broken_chain() {
+    let img = create_test_filesystem();
+    let mut fs = mount(&img);
+    for i in 0..20 {
+        fs.create_file(&format!("/F{:02}.TXT", i)).unwrap();
+    }
+    let second = fs.get_cluster_chain(2).unwrap().clusters()[1];
+
+    // le cluster suivant de la racine est marque libre
+    let mut img = fs.into_device_data();
+    common::set_fat_entry(&mut img, 2, second);
+    common::set_fat_entry(&mut img, second, 1);
+    let fs = mount(&img);
+    let results: Vec<_> = fs.read_dir("/").unwrap().collect();
+    assert_eq!(results.len(), 17);
+    assert!(results[..16].iter().all(|r| r.is_ok()));
+    assert!(matches!(results[16], Err(FileSystemError::ClusterChainError(_))));
+}
+
+/// Helper: ajoute un secteur FSInfo (secteur 1), un numero de serie et un label BPB
+fn with_fs_info(mut img: Vec<u8>, free_count: u32) -> Vec<u8> {
+    img[48..50].copy_from_slice(&1u16.to_le_bytes());
+    img[66] = 0x29;
+    img[67..71].copy_from_slice(&0xCAFE_1234u32.to_le_bytes());
+    img[71..82].copy_from_slice(b"CAMERA     ");
+    let info = 512;
+    img[info..info + 4].copy_from_slice(&0x4161_5252u32.to_le_bytes());
+    img[info + 484..info + 488].copy_from_slice(&0x6141_7272u32.to_le_bytes());
+    img[info + 488..info + 492].copy_from_slice(&free_count.to_le_bytes());
+    img[info + 492..info + 496].copy_from_slice(&0xFFFF_FFFFu32.to_le_bytes());
+    img[info + 508..info + 512].copy_from_slice(&0xAA55_0000u32.to_le_bytes());
+    img
+}
+
+#[test]
+fn test_volume_info_counts_clusters() {
+    let mut img = create_test_filesystem();
+    // cluster 10 defectueux dans les deux FATs
+    common::set_fat_entry(&mut img, 10, 0x0FFF_FFF7);
+    let img = with_fs_info(img, 68880);
+    let mut fs = mount(&img);
+
+    let info = fs.volume_info();
+    assert_eq!(info.fat_type, FatType::Fat32);
+    assert_eq!(info.cluster_size, 512);
+    assert_eq!(info.total_clusters, 68882);
+    assert_eq!((info.used_clusters, info.bad_clusters, info.free_clusters), (1, 1, 68880));
+    assert_eq!(info.fs_info_free_clusters, Some(68880));
+    assert_eq!(info.volume_id, Some(0xCAFE_1234));
+    assert_eq!(info.volume_label.as_deref(), Some("CAMERA"));
+    assert_eq!(info.free_bytes(), 68880 * 512);
+
+    // FSInfo suit les allocations et liberations
+    fs.write_file("/BIG.BIN", &[1u8; 1536]).unwrap();
+    let info = fs.volume_info();
+    assert_eq!((info.used_clusters, info.free_clusters), (4, 68877));
+    assert_eq!(info.fs_info_free_clusters, Some(68877));
+    assert!(fs.fs_info().unwrap().next_free.is_some());
+
+    fs.remove_file("/BIG.BIN").unwrap();
+    assert_eq!(fs.volume_info().fs_info_free_clusters, Some(68880));
+}
+
+#[test]
+fn test_volume_info_without_fs_info() {
+    // ni secteur FSInfo ni signature de BPB etendu
+    let mut img = create_test_filesystem();
+    img[48..50].copy_from_slice(&0u16.to_le_bytes());
+    img[66] = 0;
+    let fs = mount(&img);
+    let info = fs.volume_info();
+    assert!(fs.fs_info().is_none());
+    assert_eq!(info.fs_info_free_clusters, None);
+    assert_eq!(info.volume_id, None);
+    assert_eq!(info.volume_label, None);
+    assert_eq!(info.used_clusters + info.free_clusters, info.total_clusters);
+}
+
+#[test]
+fn test_label_prefers_root_entry_and_updates_bpb() {
+    let mut img = with_fs_info(create_test_filesystem(), 0xFFFF_FFFF);
+    // copie de secours du boot sector au secteur 6
+    img[50..52].copy_from_slice(&6u16.to_le_bytes());
+    let boot = img[..512].to_vec();
+    img[6 * 512..7 * 512].copy_from_slice(&boot);
+    let mut fs = mount(&img);
+    assert_eq!(fs.label().as_deref(), Some("CAMERA"));
+
+    fs.create_file("/A.TXT").unwrap();
+    fs.set_label("Holiday 24").unwrap();
+    assert_eq!(fs.label().as_deref(), Some("HOLIDAY 24"));
+    // l'entree label n'apparait pas dans le listing
+    assert_eq!(fs.list("/").unwrap().len(), 1);
+
+    let mut img = fs.into_device_data();
+    assert_eq!(&img[71..82], b"HOLIDAY 24 ");
+    assert_eq!(&img[6 * 512 + 71..6 * 512 + 82], b"HOLIDAY 24 ");
+
+    // l'entree de la racine passe avant le BPB
+    img[71..82].copy_from_slice(b"OTHER      ");
+    let mut fs = mount(&img);
+    assert_eq!(fs.label().as_deref(), Some("HOLIDAY 24"));
+    assert_eq!(fs.volume_info().volume_label.as_deref(), Some("HOLIDAY 24"));
+
+    // chaine vide : plus de label
+    fs.set_label("").unwrap();
+    assert_eq!(fs.label(), None);
+    assert_eq!(&fs.device_data()[71..82], b"NO NAME    ");
+    fs.create_file("/B.TXT").unwrap();
+    assert_eq!(fs.list("/").unwrap().len(), 2);
+}
+
+#[test]
+fn test_label_validation() {
+    // pas de signature de BPB etendu
+    let mut img = create_test_filesystem();
+    img[66] = 0;
+    let mut fs = mount(&img);
+    assert!(matches!(fs.set_label("TWELVE CHARS"), Err(FileSystemError::InvalidPath(_))));
+    assert!(fs.set_label("A*B").is_err());
+    assert!(fs.set_label("DOT.NAME").is_err());
+    assert!(fs.set_label(" LEADING").is_err());
+    assert_eq!(fs.label(), None);
+
+    // sans BPB etendu, seule l'entree de la racine porte le label
+    fs.set_label("usb_key").unwrap();
+    assert_eq!(fs.label().as_deref(), Some("USB_KEY"));
+    assert_eq!(&fs.device_data()[71..82], b"NO NAME    ");
+}
+
+#[test]
+fn test_create_rejects_illegal_characters() {
+    let img = create_test_filesystem();
+    let mut fs = mount(&img);
+
+    for name in ["/what?.txt", "/a:b", "/pipe|name", "/tab\tname", "/quote\"d"] {
+        assert!(matches!(fs.create_file(name), Err(FileSystemError::InvalidPath(_))), "{:?}", name);
+    }
+    assert!(fs.list("/").unwrap().is_empty());
+}
+
+#[test]
+fn test_create_dir_with_dot_entries() {
+    let img = create_test_filesystem();
+    let mut fs = mount(&img);
+
+    fs.create_dir("/Firmware").unwrap();
+    fs.create_dir("/Firmware/v2").unwrap();
+    fs.write_file("/Firmware/v2/app.bin", b"fw").unwrap();
+
+    let top = fs.metadata("/firmware").unwrap();
+    assert!(top.is_directory());
+    assert_eq!(top.created, Some(DateTime { millisecond: 890, ..fixed_time() }));
+
+    // `.` pointe sur le dossier lui-meme, `..` sur le parent (0 pour la racine)
+    let entries = fs.list("/Firmware/v2").unwrap();
+    let names: Vec<String> = entries.iter().map(|e| e.name().unwrap()).collect();
+    assert_eq!(names, [".", "..", "app.bin"]);
+    assert_eq!(entries[0].first_cluster(), fs.metadata("/Firmware/v2").unwrap().first_cluster);
+    assert_eq!(entries[1].first_cluster(), top.first_cluster);
+    assert_eq!(fs.list("/Firmware").unwrap()[1].first_cluster(), 0);
+    assert_eq!(fs.read_file("/Firmware/v2/app.bin").unwrap(), b"fw");
+
+    assert!(matches!(fs.create_dir("/firmware"), Err(FileSystemError::DirectoryEntryError(_))));
+    assert!(matches!(fs.create_dir("/missing/dir"), Err(FileSystemError::DirectoryNotFound(_))));
+}
+
+#[test]
+fn test_set_times_keeps_unset_dates() {
+    let img = create_test_filesystem();
+    let mut fs = mount(&img);
+    fs.write_file("/LOG.TXT", b"x").unwrap();
+
+    let modified = DateTime { year: 2019, month: 12, day: 31, hour: 23, minute: 59, second: 58, millisecond: 0 };
+    fs.set_times("/LOG.TXT", None, Some(modified), None).unwrap();
+
+    let meta = fs.metadata("/LOG.TXT").unwrap();
+    assert_eq!(meta.modified, Some(modified));
+    assert_eq!(meta.created, Some(fixed_time()));
+    assert_eq!(meta.accessed.map(|t| (t.year, t.month, t.day)), Some((2025, 3, 4)));
+
+    // avant 1980 : ramene a la premiere date FAT
+    let old = DateTime { year: 1970, month: 1, day: 1, hour: 0, minute: 0, second: 0, millisecond: 0 };
+    fs.set_times("/LOG.TXT", Some(old), None, None).unwrap();
+    assert_eq!(fs.metadata("/LOG.TXT").unwrap().created.map(|t| t.year), Some(1980));
+}